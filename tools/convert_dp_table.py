@@ -2,22 +2,40 @@
 """
 DPテーブル変換ツール
 
-yacht_dp_table.hpp からdouble値を抽出し、
-f32バイナリ (little-endian) として出力する。
+<rules>_dp_table.hpp からdouble値を抽出し、
+到達可能な状態だけを量子化・差分符号化したコンパクト形式 (YDP1) で出力する。
+
+使い方: convert_dp_table.py [--rules yacht|yatzy]（省略時は yacht）
 
 構造: dp_table[upper_sum][used_hands]
 - upper_sum: 0-63 (64通り)
-- used_hands: 0-4095 (4096通り)
-- 合計: 64 * 4096 = 262,144 エントリ
+- used_hands: yacht は 0-4095 (12ビット)、yatzy は 0-32767 (15ビット)
+- yacht の合計: 64 * 4096 = 262,144 エントリ（うち到達可能なのは 178,816）
+
+YDP1 形式（little-endian）:
+- magic: b"YDP1"
+- rules_checksum: u32  得点ルールのFNV-1aハッシュ（エンジン側と一致しないと読み込まない）
+- scale: u32           量子化の倍率（値 * scale を整数に丸める）
+- entry_count: u32     到達可能なエントリ数
+- payload_checksum: u32 ペイロードのFNV-1aハッシュ
+- payload: used_hands ごとに到達可能な upper_sum を昇順に並べ、
+           直前の値との差分を zigzag + LEB128 で符号化（used_hands ごとに0から開始）
 
-出力: dp_table.bin (262,144 * 4 = 1,048,576 bytes = 1MB)
+出力: yacht は dp_table.ydp (約 270KB)、yatzy は yatzy_dp_table.ydp
 """
 
+import argparse
 import re
 import struct
 import sys
 from pathlib import Path
 
+UPPER_BONUS_THRESHOLD = 63
+SCALE = 1024
+
+FNV_OFFSET = 0x811C9DC5
+FNV_PRIME = 0x01000193
+
 
 def parse_hpp_file(filepath: Path) -> list[list[float]]:
     """C++ヘッダーファイルからDP値を抽出"""
@@ -43,10 +61,10 @@ def parse_hpp_file(filepath: Path) -> list[list[float]]:
     return dp_table
 
 
-def validate_table(dp_table: list[list[float]]) -> None:
+def validate_table(dp_table: list[list[float]], rules: dict) -> None:
     """テーブルの構造を検証"""
     expected_rows = 64
-    expected_cols = 4096
+    expected_cols = 1 << rules['num_categories']
 
     if len(dp_table) != expected_rows:
         raise ValueError(f"行数が不正: 期待={expected_rows}, 実際={len(dp_table)}")
@@ -59,24 +77,168 @@ def validate_table(dp_table: list[list[float]]) -> None:
     initial_score = dp_table[0][0]
     print(f"初期期待値: {initial_score:.6f}")
 
-    if not (180 < initial_score < 200):
+    low, high = rules['initial_range']
+    if not (low < initial_score < high):
         print(f"警告: 初期期待値が予想範囲外です")
 
 
-def write_binary(dp_table: list[list[float]], output_path: Path) -> None:
-    """f32 little-endian バイナリとして出力"""
-    with output_path.open('wb') as f:
-        for row in dp_table:
-            for value in row:
-                # double -> float32 に変換して書き込み
-                f.write(struct.pack('<f', value))
+def fnv1a(data: bytes, h: int = FNV_OFFSET) -> int:
+    for b in data:
+        h = ((h ^ b) * FNV_PRIME) & 0xFFFFFFFF
+    return h
+
+
+def enumerate_patterns(num_dice: int) -> list[list[int]]:
+    """出目パターンを yacht-core の dice_patterns と同じ順序で列挙"""
+    patterns = []
+
+    def recurse(dice_left: int, face: int, current: list[int]) -> None:
+        if face == 6:
+            if dice_left == 0:
+                patterns.append(current.copy())
+            return
+        for k in range(dice_left + 1):
+            current[face] = k
+            recurse(dice_left - k, face + 1, current)
+        current[face] = 0
+
+    recurse(num_dice, 0, [0] * 6)
+    return patterns
+
+
+def calculate_score(p: list[int], cat: int) -> int:
+    """yacht-core の dp_table::calculate_score と同じ得点計算（ヨット）"""
+    pips = sum(c * (i + 1) for i, c in enumerate(p))
+    if cat < 6:
+        return p[cat] * (cat + 1)
+    if cat == 6:
+        return pips if 3 in p and 2 in p else 0
+    if cat == 7:
+        return pips if any(c >= 4 for c in p) else 0
+    if cat == 8:
+        return 15 if any(all(c >= 1 for c in p[i:i + 4]) for i in range(3)) else 0
+    if cat == 9:
+        return 30 if any(all(c >= 1 for c in p[i:i + 5]) for i in range(2)) else 0
+    if cat == 10:
+        return pips
+    if cat == 11:
+        return 50 if 5 in p else 0
+    return 0
+
+
+def highest_of_a_kind(p: list[int], n: int, below: int = 6) -> int | None:
+    """n個以上ある最も大きい目（0-5、below より小さい目から探す）"""
+    return next((face for face in range(below - 1, -1, -1) if p[face] >= n), None)
+
+
+def calculate_yatzy_score(p: list[int], cat: int) -> int:
+    """yacht-core の dp_table::calculate_yatzy_score と同じ得点計算（北欧式ヤッツィー）"""
+    pips = sum(c * (i + 1) for i, c in enumerate(p))
+
+    def of_a_kind(n: int) -> int:
+        face = highest_of_a_kind(p, n)
+        return 0 if face is None else n * (face + 1)
+
+    if cat < 6:
+        return p[cat] * (cat + 1)
+    if cat == 6:
+        return of_a_kind(2)
+    if cat == 7:
+        high = highest_of_a_kind(p, 2)
+        low = None if high is None else highest_of_a_kind(p, 2, high)
+        return 0 if low is None else 2 * (high + low + 2)
+    if cat == 8:
+        return of_a_kind(3)
+    if cat == 9:
+        return of_a_kind(4)
+    if cat == 10:
+        return 15 if p == [1, 1, 1, 1, 1, 0] else 0
+    if cat == 11:
+        return 20 if p == [0, 1, 1, 1, 1, 1] else 0
+    if cat == 12:
+        return pips if 3 in p and 2 in p else 0
+    if cat == 13:
+        return pips
+    if cat == 14:
+        return 50 if 5 in p else 0
+    return 0
+
+
+# 得点ルールごとの設定（yacht-core の dp_table::YACHT / YATZY に対応）
+RULES = {
+    'yacht': {
+        'num_categories': 12,
+        'upper_bonus_points': 35,
+        'score': calculate_score,
+        'initial_range': (180, 200),
+        'input': 'yacht_dp_table.hpp',
+        'output': 'dp_table.ydp',
+    },
+    'yatzy': {
+        'num_categories': 15,
+        'upper_bonus_points': 50,
+        'score': calculate_yatzy_score,
+        'initial_range': (240, 260),
+        'input': 'yatzy_dp_table.hpp',
+        'output': 'yatzy_dp_table.ydp',
+    },
+}
+
+
+def rules_checksum(rules: dict) -> int:
+    """得点ルールのチェックサム（yacht-core の Rules::rules_checksum と一致する）"""
+    num_categories = rules['num_categories']
+    data = struct.pack('<III', num_categories, UPPER_BONUS_THRESHOLD, rules['upper_bonus_points'])
+    data += bytes(rules['score'](p, cat) for p in enumerate_patterns(5) for cat in range(num_categories))
+    return fnv1a(data)
+
+
+def reachable_upper_sums(upper_mask: int) -> list[int]:
+    """使用済みの上段カテゴリから到達可能な上段累計（63上限）"""
+    sums = {0}
+    for face in range(6):
+        if upper_mask >> face & 1:
+            sums = {min(UPPER_BONUS_THRESHOLD, s + k * (face + 1)) for s in sums for k in range(6)}
+    return sorted(sums)
+
+
+def encode_table(dp_table: list[list[float]], rules: dict) -> bytes:
+    """YDP1 形式に符号化"""
+    reachable = [reachable_upper_sums(m) for m in range(64)]
+    payload = bytearray()
+    entry_count = 0
+
+    for used_hands in range(1 << rules['num_categories']):
+        prev = 0
+        for upper_sum in reachable[used_hands & 0x3F]:
+            q = round(dp_table[upper_sum][used_hands] * SCALE)
+            delta = q - prev
+            prev = q
+            z = (delta << 1) ^ (delta >> 63)
+            while True:
+                b = z & 0x7F
+                z >>= 7
+                if z:
+                    payload.append(b | 0x80)
+                else:
+                    payload.append(b)
+                    break
+            entry_count += 1
+
+    header = b'YDP1' + struct.pack('<IIII', rules_checksum(rules), SCALE, entry_count, fnv1a(payload))
+    return header + bytes(payload)
 
 
 def main():
+    parser = argparse.ArgumentParser(description='DPテーブルを YDP1 形式に変換する')
+    parser.add_argument('--rules', choices=sorted(RULES), default='yacht', help='得点ルール')
+    args = parser.parse_args()
+    rules = RULES[args.rules]
+
     # パス設定
     script_dir = Path(__file__).parent
-    input_path = script_dir / 'yacht_dp_table.hpp'
-    output_path = script_dir.parent / 'yacht-core' / 'src' / 'dp_table.bin'
+    input_path = script_dir / rules['input']
+    output_path = script_dir.parent / 'yacht-core' / 'src' / rules['output']
 
     if not input_path.exists():
         print(f"エラー: {input_path} が見つかりません")
@@ -90,22 +252,24 @@ def main():
     dp_table = parse_hpp_file(input_path)
 
     print("テーブル構造を検証中...")
-    validate_table(dp_table)
+    validate_table(dp_table, rules)
 
-    print("バイナリ出力中...")
+    print("コンパクト形式で出力中...")
     output_path.parent.mkdir(parents=True, exist_ok=True)
-    write_binary(dp_table, output_path)
+    output_path.write_bytes(encode_table(dp_table, rules))
 
     # 結果表示
     size_bytes = output_path.stat().st_size
-    print(f"完了: {size_bytes:,} bytes ({size_bytes / 1024 / 1024:.2f} MB)")
+    print(f"完了: {size_bytes:,} bytes ({size_bytes / 1024:.1f} KB)")
+    print(f"ルールチェックサム: 0x{rules_checksum(rules):08x}")
 
     # サンプル値を表示
     print("\nサンプル値:")
+    last = (1 << rules['num_categories']) - 1
     print(f"  dp[0][0] = {dp_table[0][0]:.6f}")
-    print(f"  dp[0][4095] = {dp_table[0][4095]:.6f}")
+    print(f"  dp[0][{last}] = {dp_table[0][last]:.6f}")
     print(f"  dp[63][0] = {dp_table[63][0]:.6f}")
-    print(f"  dp[63][4095] = {dp_table[63][4095]:.6f}")
+    print(f"  dp[63][{last}] = {dp_table[63][last]:.6f}")
 
 
 if __name__ == '__main__':
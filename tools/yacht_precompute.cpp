@@ -7,6 +7,10 @@
  * 
  * 出力:
  *   yacht_dp_table.hpp - dp[upper_sum][used_hands] の二重vector
+ *
+ * 使い方:
+ *   ./yacht_precompute          ヨット（12役、ボーナス35点）→ yacht_dp_table.hpp
+ *   ./yacht_precompute yatzy    北欧式ヤッツィー（15役、ボーナス50点）→ yatzy_dp_table.hpp
  */
 
 #include <bits/stdc++.h>
@@ -52,6 +56,27 @@ namespace Hand {
     inline bool is_upper(int hand) { return hand < 6; }
 }
 
+/// 北欧式ヤッツィーの役（上段は共通）
+namespace YatzyHand {
+    constexpr int ONE_PAIR = 6, TWO_PAIRS = 7;
+    constexpr int THREE_OF_A_KIND = 8, FOUR_OF_A_KIND = 9;
+    constexpr int SMALL_STRAIGHT = 10, LARGE_STRAIGHT = 11;
+    constexpr int FULL_HOUSE = 12, CHANCE = 13, YATZY = 14;
+}
+
+/// 計算するルール
+struct Rules {
+    const char* name;
+    const char* output;
+    int num_hands;
+    int upper_bonus_points;
+    int (*score)(int hand, const Dice& d);
+    /// true: 振るたびにホールドを選び直せる / false: キープしたダイスは手番の間ロック
+    bool free_holds;
+
+    int used_hands_max() const { return 1 << num_hands; }
+};
+
 // =============================================================================
 // Dice ユーティリティ
 // =============================================================================
@@ -92,7 +117,7 @@ namespace DiceUtil {
 
 namespace Scoring {
     
-    inline int calculate(int hand, const Dice& d) {
+    inline int calculate_yacht(int hand, const Dice& d) {
         switch (hand) {
             case Hand::ONES:   return d[0] * 1;
             case Hand::TWOS:   return d[1] * 2;
@@ -130,8 +155,60 @@ namespace Scoring {
         }
         return 0;
     }
+
+    /// n個以上ある最も大きい目（なければ -1）
+    inline int highest_of_a_kind(const Dice& d, int n, int below = 6) {
+        for (int i = below - 1; i >= 0; i--) if (d[i] >= n) return i;
+        return -1;
+    }
+
+    inline int calculate_yatzy(int hand, const Dice& d) {
+        if (Hand::is_upper(hand)) return d[hand] * (hand + 1);
+        switch (hand) {
+            case YatzyHand::ONE_PAIR: {
+                int f = highest_of_a_kind(d, 2);
+                return f < 0 ? 0 : 2 * (f + 1);
+            }
+            case YatzyHand::TWO_PAIRS: {
+                int hi = highest_of_a_kind(d, 2);
+                int lo = hi < 0 ? -1 : highest_of_a_kind(d, 2, hi);
+                return lo < 0 ? 0 : 2 * (hi + 1) + 2 * (lo + 1);
+            }
+            case YatzyHand::THREE_OF_A_KIND: {
+                int f = highest_of_a_kind(d, 3);
+                return f < 0 ? 0 : 3 * (f + 1);
+            }
+            case YatzyHand::FOUR_OF_A_KIND: {
+                int f = highest_of_a_kind(d, 4);
+                return f < 0 ? 0 : 4 * (f + 1);
+            }
+            case YatzyHand::SMALL_STRAIGHT:
+                return (d[0]==1 && d[1]==1 && d[2]==1 && d[3]==1 && d[4]==1) ? 15 : 0;
+            case YatzyHand::LARGE_STRAIGHT:
+                return (d[1]==1 && d[2]==1 && d[3]==1 && d[4]==1 && d[5]==1) ? 20 : 0;
+            case YatzyHand::FULL_HOUSE: {
+                bool h3 = false, h2 = false;
+                for (int i = 0; i < 6; i++) {
+                    if (d[i] == 3) h3 = true;
+                    if (d[i] == 2) h2 = true;
+                }
+                return (h3 && h2) ? DiceUtil::total_pips(d) : 0;
+            }
+            case YatzyHand::CHANCE: return DiceUtil::total_pips(d);
+            case YatzyHand::YATZY: {
+                for (int i = 0; i < 6; i++) if (d[i] == 5) return 50;
+                return 0;
+            }
+        }
+        return 0;
+    }
 }
 
+const Rules YACHT_RULES = {"yacht", "yacht_dp_table.hpp", 12, 35, Scoring::calculate_yacht, false};
+const Rules YATZY_RULES = {"yatzy", "yatzy_dp_table.hpp", 15, 50, Scoring::calculate_yatzy, true};
+
+Rules g_rules = YACHT_RULES;
+
 // =============================================================================
 // 出目パターンと確率の事前計算
 // =============================================================================
@@ -258,11 +335,11 @@ public:
         : dice_table_(dice_table), keep_table_(keep_table)
     {
         dp_.assign(Constants::UPPER_SUM_MAX,
-                   vector<double>(Constants::USED_HANDS_MAX, 0.0));
+                   vector<double>(g_rules.used_hands_max(), 0.0));
     }
     
     void solve() {
-        int total = Constants::USED_HANDS_MAX;
+        int total = g_rules.used_hands_max();
         int processed = 0;
         auto start = chrono::steady_clock::now();
         
@@ -316,10 +393,10 @@ private:
         Dice d = DiceUtil::decode(dice_code);
         double best = -1e18;
         
-        for (int h = 0; h < Constants::NUM_HANDS; h++) {
+        for (int h = 0; h < g_rules.num_hands; h++) {
             if ((current_hands_ >> h) & 1) continue;
             
-            int pts = Scoring::calculate(h, d);
+            int pts = g_rules.score(h, d);
             int new_hands = current_hands_ | (1 << h);
             
             double val;
@@ -327,7 +404,7 @@ private:
                 int new_x = min(Constants::UPPER_BONUS_THRESHOLD, current_x_ + pts);
                 double bonus = (current_x_ < Constants::UPPER_BONUS_THRESHOLD &&
                                new_x >= Constants::UPPER_BONUS_THRESHOLD)
-                               ? Constants::UPPER_BONUS_POINTS : 0.0;
+                               ? g_rules.upper_bonus_points : 0.0;
                 val = dp_[new_x][new_hands] + pts + bonus;
             } else {
                 val = dp_[current_x_][new_hands] + pts;
@@ -368,11 +445,19 @@ private:
         for (const auto& roll_pattern : dice_table_.get(n_roll)) {
             double best = -1e18;
             
-            // 全キープパターンを試す
-            for (const auto& keep : keep_table_.get(roll_pattern.code)) {
-                Dice new_state = DiceUtil::add(state, keep.keep);
-                double val = compute_stage(stage + 1, DiceUtil::encode(new_state));
-                best = max(best, val);
+            if (g_rules.free_holds) {
+                // 5個全体からキープを選び直す
+                Dice full = DiceUtil::add(state, roll_pattern.dice);
+                for (const auto& keep : keep_table_.get(DiceUtil::encode(full))) {
+                    best = max(best, compute_stage(stage + 1, keep.code));
+                }
+            } else {
+                // 全キープパターンを試す（キープ済みのダイスはそのまま）
+                for (const auto& keep : keep_table_.get(roll_pattern.code)) {
+                    Dice new_state = DiceUtil::add(state, keep.keep);
+                    double val = compute_stage(stage + 1, DiceUtil::encode(new_state));
+                    best = max(best, val);
+                }
             }
             
             total += roll_pattern.probability * best;
@@ -395,12 +480,12 @@ private:
 // ファイル出力
 // =============================================================================
 
-void write_output(const string& filename, const vector<vector<double>>& dp) {
-    ofstream ofs(filename);
+void write_output(const vector<vector<double>>& dp) {
+    ofstream ofs(g_rules.output);
     ofs << fixed << setprecision(6);
     
     ofs << R"(/**
- * ヨット DP テーブル（自動生成）
+ * )" << g_rules.name << R"( DP テーブル（自動生成）
  * 
  * dp_table[upper_sum][used_hands] = その状態からの期待得点
  */
@@ -408,7 +493,7 @@ void write_output(const string& filename, const vector<vector<double>>& dp) {
 #pragma once
 #include <vector>
 
-namespace yacht {
+namespace )" << g_rules.name << R"( {
 
 const std::vector<std::vector<double>> dp_table = {
 )";
@@ -432,19 +517,25 @@ inline double get_initial_expected_score() {
     return dp_table[0][0];
 }
 
-} // namespace yacht
-)";
+} // namespace )" << g_rules.name << "\n";
 }
 
 // =============================================================================
 // メイン
 // =============================================================================
 
-int main() {
+int main(int argc, char** argv) {
     ios::sync_with_stdio(false);
     cin.tie(nullptr);
     
-    cerr << "=== ヨット期待値計算（高速化版） ===" << endl;
+    if (argc > 1 && string(argv[1]) == "yatzy") {
+        g_rules = YATZY_RULES;
+    } else if (argc > 1 && string(argv[1]) != "yacht") {
+        cerr << "usage: " << argv[0] << " [yacht|yatzy]" << endl;
+        return 1;
+    }
+    
+    cerr << "=== " << g_rules.name << " 期待値計算（高速化版） ===" << endl;
     
     cerr << "出目パターンテーブルを構築中..." << endl;
     DicePatternTable dice_table;
@@ -470,8 +561,8 @@ int main() {
     cerr << "計算時間: " << elapsed << " 秒" << endl;
     cerr << "期待得点: " << solver.get_initial_score() << endl;
     
-    write_output("yacht_dp_table.hpp", solver.get_dp());
-    cerr << "出力ファイル: yacht_dp_table.hpp" << endl;
+    write_output(solver.get_dp());
+    cerr << "出力ファイル: " << g_rules.output << endl;
     
     cout << fixed << setprecision(15);
     cout << solver.get_initial_score() << endl;
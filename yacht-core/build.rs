@@ -0,0 +1,14 @@
+// ffi フィーチャーが有効なときに C ヘッダーを OUT_DIR に生成する
+// （リポジトリの include/yacht_core.h は tests/c/run.sh --update-header で更新し、ffi のテストで一致を確認する）
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        cbindgen::generate(&crate_dir)
+            .expect("failed to generate C header")
+            .write_to_file(format!("{}/yacht_core.h", out_dir));
+    }
+}
@@ -0,0 +1,271 @@
+#ifndef YACHT_CORE_H
+#define YACHT_CORE_H
+
+/* このファイルは cbindgen が自動生成する。直接編集しないこと */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// エラーコード
+typedef enum YachtStatus {
+  YACHT_STATUS_OK = 0,
+  // ハンドルまたは出力引数が NULL
+  YACHT_STATUS_NULL_POINTER = 1,
+  // カテゴリ・席・インデックスなどが範囲外
+  YACHT_STATUS_INVALID_ARGUMENT = 2,
+  // 現在の状態ではできない操作（振れない、使用済みのカテゴリなど）
+  YACHT_STATUS_INVALID_STATE = 3,
+} YachtStatus;
+
+// AIのハンドル
+typedef struct YachtAi YachtAi;
+
+// ゲームのハンドル
+typedef struct YachtGame YachtGame;
+
+// カテゴリ選択の候補
+typedef struct YachtCategoryChoice {
+  uint8_t category;
+  // 即時得点
+  uint8_t score;
+  // 最終的な合計点数の期待値
+  float expected;
+  // 最善の候補との期待値の差
+  float loss;
+  // 他の候補と期待値が同じ
+  bool tied;
+} YachtCategoryChoice;
+
+// キープの候補
+typedef struct YachtHoldChoice {
+  // ホールドするダイス（0/1）
+  uint8_t holds[5];
+  // 振り直さずに今の出目で得点する
+  bool stop;
+  // 最終的な合計点数の期待値
+  float expected;
+  // 最善の候補との期待値の差
+  float loss;
+  // 他の候補と期待値が同じ
+  bool tied;
+} YachtHoldChoice;
+
+// エラーコードの説明（静的な NUL 終端文字列）
+const char *yacht_status_message(enum YachtStatus status);
+
+// 出目とカテゴリから得点を計算
+//
+// # Safety
+// `dice` は5要素の配列、`out_score` は書き込み可能なポインタであること
+enum YachtStatus yacht_calculate_score(const uint8_t *dice, uint8_t category, uint8_t *out_score);
+
+// ヤッツィーのDPテーブル（YDP1 形式）を読み込む
+// embed-yatzy-table フィーチャーなしのビルドでは、ヤッツィーをAIに打たせる前に呼ぶ
+// （壊れたデータは InvalidArgument、読み込み済みなら InvalidState）
+//
+// # Safety
+// `data` は len バイトの読み込み可能な配列であること
+enum YachtStatus yacht_load_yatzy_table(const uint8_t *data,
+                                        size_t len);
+
+// ゲームを作成（hold_rule: 0 = Free, 1 = Locking）
+// 不正な hold_rule なら NULL
+struct YachtGame *yacht_game_new(uint8_t hold_rule);
+
+// ルールを指定してゲームを作成
+// （ruleset: 0 = ヨット, 1 = ヘネラル, 2 = トリプルヨット, 3 = ヤンブ, 4 = ヤッツィー。solo なら1人用）
+// 不正な ruleset なら NULL
+struct YachtGame *yacht_game_new_with_ruleset(uint8_t ruleset,
+                                              bool solo);
+
+// ハウスルール（`custom` の JSON 定義、NUL 終端の UTF-8）のゲームを作成（solo なら1人用）
+// 定義が不正なら NULL
+//
+// # Safety
+// `rules_json` は NULL か NUL 終端の文字列であること
+struct YachtGame *yacht_game_new_custom(const char *rules_json,
+                                        bool solo);
+
+// ゲームを解放（NULL は何もしない）
+//
+// # Safety
+// `game` は `yacht_game_new` が返したポインタで、まだ解放していないこと
+void yacht_game_free(struct YachtGame *game);
+
+// 以降のロールをシードから決定的に生成する
+//
+// # Safety
+// `game` は有効なハンドルであること
+enum YachtStatus yacht_game_set_seed(struct YachtGame *game, uint64_t seed);
+
+// 以降のロールで振るダイスを目ごとの重み（weights[0] が 1 の目、2-8 面）で設定する
+// （面の数や重みが不正、または標準以外のダイスで遊べないルールなら InvalidArgument）
+//
+// # Safety
+// `game` は有効なハンドル、`weights` は len 要素の配列であること
+enum YachtStatus yacht_game_set_dice_weights(struct YachtGame *game,
+                                             const double *weights,
+                                             size_t len);
+
+// サイコロを振る（残りロールがなければ InvalidState）
+//
+// # Safety
+// `game` は有効なハンドルであること
+enum YachtStatus yacht_game_roll(struct YachtGame *game);
+
+// 実物のダイスで振った出目を入力する
+// 出目が 1-6 でない、またはホールドしたダイスの出目が変わっていれば InvalidArgument
+//
+// # Safety
+// `game` は有効なハンドル、`values` は 5 要素の配列であること
+enum YachtStatus yacht_game_enter_roll(struct YachtGame *game,
+                                       const uint8_t *values);
+
+// 残りのロールを使わずに振り直しをやめる
+//
+// # Safety
+// `game` は有効なハンドルであること
+enum YachtStatus yacht_game_stand(struct YachtGame *game);
+
+// ダイスのホールドを切り替える
+//
+// # Safety
+// `game` は有効なハンドルであること
+enum YachtStatus yacht_game_toggle_hold(struct YachtGame *game, uint8_t index);
+
+// カテゴリを選んで得点し、手番を交代する
+//
+// # Safety
+// `game` は有効なハンドルであること
+enum YachtStatus yacht_game_select_category(struct YachtGame *game, uint8_t category);
+
+// 欄（列・行）を選んで得点し、手番を交代する。得点が不要なら out_score は NULL でよい
+//
+// # Safety
+// `game` は有効なハンドル、`out_score` は NULL か書き込み可能なポインタであること
+enum YachtStatus yacht_game_select_cell(struct YachtGame *game,
+                                        uint8_t column,
+                                        uint8_t row,
+                                        uint8_t *out_score);
+
+// この手番で記入する行を宣言する（宣言の列があるルールの1回目のロールの後）
+//
+// # Safety
+// `game` は有効なハンドルであること
+enum YachtStatus yacht_game_announce(struct YachtGame *game,
+                                     uint8_t row);
+
+// 現在の出目（5要素）
+//
+// # Safety
+// `game` は有効なハンドル、`out_dice` は5要素の書き込み可能な配列であること
+enum YachtStatus yacht_game_get_dice(const struct YachtGame *game,
+                                     uint8_t *out_dice);
+
+// ホールドとロックの状態（各5要素、0/1）。不要な方は NULL でよい
+//
+// # Safety
+// `game` は有効なハンドル、出力は NULL か5要素の書き込み可能な配列であること
+enum YachtStatus yacht_game_get_holds(const struct YachtGame *game,
+                                      uint8_t *out_holds,
+                                      uint8_t *out_locks);
+
+// 残りロール数・手番の席・ゲーム終了。不要な出力は NULL でよい
+//
+// # Safety
+// `game` は有効なハンドル、出力は NULL か書き込み可能なポインタであること
+enum YachtStatus yacht_game_get_turn(const struct YachtGame *game,
+                                     uint8_t *out_rolls_left,
+                                     uint8_t *out_current_player,
+                                     bool *out_game_over);
+
+// 得点表の1カテゴリ（未使用なら out_used = false, out_score = 0）
+//
+// # Safety
+// `game` は有効なハンドル、出力は書き込み可能なポインタであること
+enum YachtStatus yacht_game_get_score(const struct YachtGame *game,
+                                      uint8_t seat,
+                                      uint8_t category,
+                                      uint8_t *out_score,
+                                      bool *out_used);
+
+// 得点表の1欄（未記入なら out_used = false, out_score = 0）
+//
+// # Safety
+// `game` は有効なハンドル、出力は書き込み可能なポインタであること
+enum YachtStatus yacht_game_get_cell(const struct YachtGame *game,
+                                     uint8_t seat,
+                                     uint8_t column,
+                                     uint8_t row,
+                                     uint8_t *out_score,
+                                     bool *out_used);
+
+// 得点表の集計。不要な出力は NULL でよい
+//
+// # Safety
+// `game` は有効なハンドル、出力は NULL か書き込み可能なポインタであること
+enum YachtStatus yacht_game_get_totals(const struct YachtGame *game,
+                                       uint8_t seat,
+                                       uint16_t *out_upper_total,
+                                       uint16_t *out_upper_bonus,
+                                       uint16_t *out_total);
+
+// AIを作成
+struct YachtAi *yacht_ai_new(void);
+
+// AIを解放（NULL は何もしない）
+//
+// # Safety
+// `ai` は `yacht_ai_new` が返したポインタで、まだ解放していないこと
+void yacht_ai_free(struct YachtAi *ai);
+
+// 推奨するホールド（5要素、0/1）と、振り直しをやめるべきか
+//
+// # Safety
+// `ai` / `game` は有効なハンドル、`out_holds` は5要素の配列、`out_stop` は書き込み可能なポインタであること
+enum YachtStatus yacht_ai_hold_decision(const struct YachtAi *ai,
+                                        const struct YachtGame *game,
+                                        uint8_t *out_holds,
+                                        bool *out_stop);
+
+// 推奨するカテゴリ（決まらなければ `InvalidState`）
+//
+// # Safety
+// `ai` / `game` は有効なハンドル、`out_category` は書き込み可能なポインタであること
+enum YachtStatus yacht_ai_category_decision(const struct YachtAi *ai,
+                                            const struct YachtGame *game,
+                                            uint8_t *out_category);
+
+// 手番の席のターンをAIが最後まで進める（ヨット以外のルールはそのルールのAIで進める。
+// ヤッツィーのDPテーブルを読み込む前は InvalidState）
+//
+// # Safety
+// `ai` / `game` は有効なハンドルであること
+enum YachtStatus yacht_ai_play_turn(const struct YachtAi *ai,
+                                    struct YachtGame *game);
+
+// カテゴリ選択の候補を期待値の高い順に最大 capacity 件書き込み、件数を out_len に返す
+//
+// # Safety
+// `ai` / `game` は有効なハンドル、`out_choices` は capacity 要素の配列、`out_len` は書き込み可能なポインタであること
+enum YachtStatus yacht_ai_top_category_choices(const struct YachtAi *ai,
+                                               const struct YachtGame *game,
+                                               struct YachtCategoryChoice *out_choices,
+                                               size_t capacity,
+                                               size_t *out_len);
+
+// キープの候補を期待値の高い順に最大 capacity 件書き込み、件数を out_len に返す
+// （手番の最初に振る前・振り切った後・ゲーム終了後は InvalidState）
+//
+// # Safety
+// `ai` / `game` は有効なハンドル、`out_choices` は capacity 要素の配列、`out_len` は書き込み可能なポインタであること
+enum YachtStatus yacht_ai_top_hold_choices(const struct YachtAi *ai,
+                                           const struct YachtGame *game,
+                                           struct YachtHoldChoice *out_choices,
+                                           size_t capacity,
+                                           size_t *out_len);
+
+#endif /* YACHT_CORE_H */
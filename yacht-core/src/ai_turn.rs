@@ -0,0 +1,168 @@
+//! AIの手番を1ステップずつ進めるプランナー
+//!
+//! フロントエンドはステップごとにアニメーションを挟みながら
+//! `next_step` → `apply` を繰り返すだけで、AIの制御ロジックを持たずに済む。
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Category, GameError, GameState, HoldDecision, YachtAI};
+
+/// AIの手番のステップの種類
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AiStepKind {
+    /// サイコロを振る
+    Roll = 0,
+    /// ホールドを変更する
+    SetHolds = 1,
+    /// 振り直しをやめる
+    Stop = 2,
+    /// カテゴリを選ぶ
+    ChooseCategory = 3,
+}
+
+/// AIの手番の1ステップ
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AiStep {
+    /// サイコロを振る
+    Roll,
+    /// ホールドするダイスを変更する
+    SetHolds([bool; 5]),
+    /// 振り直しをやめる
+    Stop,
+    /// カテゴリを選ぶ
+    ChooseCategory(Category),
+}
+
+impl AiStep {
+    pub fn kind(&self) -> AiStepKind {
+        match self {
+            AiStep::Roll => AiStepKind::Roll,
+            AiStep::SetHolds(_) => AiStepKind::SetHolds,
+            AiStep::Stop => AiStepKind::Stop,
+            AiStep::ChooseCategory(_) => AiStepKind::ChooseCategory,
+        }
+    }
+}
+
+/// AIの手番の進行状態
+#[derive(Clone, Debug, Default)]
+pub struct AiTurn {
+    ai: YachtAI,
+    /// 今のロールに対するホールドを決定済み
+    holds_decided: bool,
+    /// カテゴリを選んで手番が終わった
+    finished: bool,
+}
+
+impl AiTurn {
+    pub fn new() -> AiTurn {
+        AiTurn::default()
+    }
+
+    /// 次に行うステップを決める（ゲーム状態は変更しない）
+    /// 手番が終わっていれば None（`YachtAI` が扱えないルールで記入するカテゴリが決まらないときも None）
+    pub fn next_step(&self, game: &GameState) -> Option<AiStep> {
+        if self.finished || game.is_game_over() {
+            return None;
+        }
+
+        let rolls_left = game.rolls_left();
+        if rolls_left == 3 {
+            return Some(AiStep::Roll);
+        }
+        if rolls_left == 0 {
+            return self.ai.decide_category(game).map(AiStep::ChooseCategory);
+        }
+
+        let holds = match self.ai.decide_holds(game) {
+            HoldDecision::Stop => return Some(AiStep::Stop),
+            HoldDecision::Reroll(holds) => holds,
+        };
+
+        if !self.holds_decided && holds != game.dice().held() {
+            return Some(AiStep::SetHolds(holds));
+        }
+
+        Some(AiStep::Roll)
+    }
+
+    /// ステップをゲーム状態に適用する
+    pub fn apply(&mut self, game: &mut GameState, step: &AiStep) -> Result<(), GameError> {
+        match *step {
+            AiStep::Roll => {
+                self.holds_decided = false;
+                game.roll()
+            }
+            AiStep::SetHolds(holds) => {
+                self.ai.apply_holds(game, &holds);
+                self.holds_decided = true;
+                Ok(())
+            }
+            AiStep::Stop => game.stand(),
+            AiStep::ChooseCategory(category) => {
+                game.select(category)?;
+                self.finished = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// 次のステップを決めて適用し、そのステップを返す（手番が終わっていれば None）
+    pub fn step(&mut self, game: &mut GameState) -> Result<Option<AiStep>, GameError> {
+        let Some(step) = self.next_step(game) else {
+            return Ok(None);
+        };
+        self.apply(game, &step)?;
+        Ok(Some(step))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ai_turn_steps() {
+        let mut game = GameState::new();
+        let mut turn = AiTurn::new();
+
+        let first = turn.step(&mut game).unwrap().unwrap();
+        assert_eq!(first, AiStep::Roll);
+
+        let mut kinds = vec![first.kind()];
+        while let Some(step) = turn.step(&mut game).unwrap() {
+            kinds.push(step.kind());
+        }
+
+        assert!(turn.is_finished());
+        assert_eq!(kinds.last(), Some(&AiStepKind::ChooseCategory));
+        assert!(kinds.iter().filter(|&&k| k == AiStepKind::Roll).count() <= 3);
+        // 手番が終わったら次のプレイヤーへ
+        assert_eq!(game.current_player(), 1);
+        assert!(turn.next_step(&game).is_none());
+    }
+
+    #[test]
+    fn test_ai_turn_generala() {
+        let mut game = GameState::with_ruleset(crate::Ruleset::Generala);
+        game.set_seed(11);
+        let mut turn = AiTurn::new();
+        let mut last = None;
+        while let Some(step) = turn.step(&mut game).unwrap() {
+            last = Some(step);
+        }
+        // ヘネラルで使わないカテゴリは選ばない
+        let Some(AiStep::ChooseCategory(category)) = last else {
+            panic!("expected a category, got {:?}", last);
+        };
+        assert!(crate::generala::CATEGORIES.contains(&category));
+        assert_eq!(game.current_player(), 1);
+    }
+}
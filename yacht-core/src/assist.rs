@@ -0,0 +1,480 @@
+//! オンライン対戦でのエンジン使用（ヒントの丸写し）の検出
+//!
+//! ゲームの記録を再生し、プレイヤーの各判断（ホールド・振り直しをやめる・カテゴリ選択）を
+//! 最適な方策（`YachtAI`）と比べて、一致率と1ゲームあたりの期待値損失を求める。
+//! 人間と分かっているプレイヤー・ボットと分かっているプレイヤーの分布（`Reference`）と比べ、
+//! どちらに近いかを疑わしさ（0-1）と根拠として出す。
+//!
+//! 疑わしさは2つの指標を独立な正規分布とみなした尤度比から、事前確率を半々とした事後確率。
+//! 判断が少ないと標本のばらつきが大きいので、どちらにも寄りにくい。ヨットのルールのゲームだけが対象。
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dp_table, is_valid_dice, Category, GameEvent, GameState, HoldRule, Ruleset, ScoreBoard, YachtAI,
+};
+
+/// 判断を最適とみなす期待値の差
+const MATCH_TOLERANCE: f32 = crate::EXPECTED_TIE_EPSILON;
+/// 分布の標準偏差の下限（ボットはほぼばらつかないので、尤度が発散しないようにする）
+const MIN_RATE_STD_DEV: f64 = 0.01;
+const MIN_LOSS_STD_DEV: f64 = 0.5;
+/// これより判断が少ないと根拠に「データ不足」を加える
+pub const MIN_DECISIONS: usize = 100;
+
+/// 判断の種類
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionKind {
+    /// 何をホールドして振り直すか
+    Hold,
+    /// 振り直さずに得点する
+    Stand,
+    /// 記入するカテゴリ
+    Category,
+}
+
+/// 1つの判断の評価
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecisionReview {
+    pub kind: DecisionKind,
+    /// 最適な判断との期待値の差
+    pub loss: f32,
+    /// 最適な判断と（誤差の範囲で）同じ
+    pub matched: bool,
+    /// 選択肢が1つしかない（一致率に数えない）
+    pub forced: bool,
+}
+
+/// 記録を再生して seat の判断を評価する（ヨット以外のルールなら空）
+///
+/// 損失の合計は `YachtAI::luck_skill_breakdown` の skill_loss と一致する。
+/// 改ざんされた JSON などで起こりえない手（残りロール数や出目が範囲外・使用済みカテゴリへの記入）は飛ばす。
+pub fn review_game(ai: &YachtAI, game: &GameState, seat: u8) -> Vec<DecisionReview> {
+    if *game.ruleset() != Ruleset::Yacht {
+        return Vec::new();
+    }
+    let hold_rule = game.hold_rule();
+    let mut board = ScoreBoard::new();
+    // 直前の状態から最適に続けた場合の最終合計点数の期待値
+    let mut live = dp_table::get_initial_expected_score();
+    let mut reviews = Vec::new();
+    let mut review = |kind, loss: f32, forced| {
+        reviews.push(DecisionReview {
+            kind,
+            loss,
+            matched: loss <= MATCH_TOLERANCE,
+            forced,
+        })
+    };
+
+    for event in game.history() {
+        match *event {
+            GameEvent::Roll {
+                seat: s,
+                rolls_left,
+                held,
+                before,
+                after,
+            } if s == seat => {
+                let Some(rolls_after) = rolls_left.checked_sub(1).filter(|&r| r < 3) else {
+                    continue;
+                };
+                if !is_valid_dice(&before) || !is_valid_dice(&after) {
+                    continue;
+                }
+                let upper_sum = board.upper_sum_capped();
+                let used_hands = board.used_hands_mask();
+                let current_total = board.total() as f32;
+                // 1回目のロールは判断ではない
+                if rolls_left < 3 {
+                    let chosen = current_total
+                        + ai.held_future_value(
+                            &before, &held, rolls_left, upper_sum, used_hands, hold_rule,
+                        );
+                    review(DecisionKind::Hold, live - chosen, false);
+                }
+                let locked = match hold_rule {
+                    HoldRule::Free => [false; 5],
+                    HoldRule::Locking => held,
+                };
+                live = current_total
+                    + ai.live_future_value(
+                        &after,
+                        &locked,
+                        rolls_after,
+                        upper_sum,
+                        used_hands,
+                        hold_rule,
+                    );
+            }
+            GameEvent::Stand { seat: s, dice, .. } if s == seat && is_valid_dice(&dice) => {
+                let chosen = board.total() as f32
+                    + ai.best_category_value(
+                        &dp_table::dice_to_pattern(&dice),
+                        board.upper_sum_capped(),
+                        board.used_hands_mask(),
+                    );
+                review(DecisionKind::Stand, live - chosen, false);
+                live = chosen;
+            }
+            GameEvent::Score {
+                seat: s,
+                cell,
+                score,
+                ..
+            } if s == seat && cell.column == 0 => {
+                let Some(category) = Category::from_index(cell.row).filter(|&c| !board.is_used(c))
+                else {
+                    continue;
+                };
+                let chosen = board.total() as f32
+                    + dp_table::evaluate_category_choice(
+                        board.upper_sum_capped(),
+                        board.used_hands_mask(),
+                        category as usize,
+                        score,
+                    );
+                let forced = board.available_categories().len() == 1;
+                review(DecisionKind::Category, live - chosen, forced);
+                board.set_score(category, score);
+                live = chosen;
+            }
+            _ => {}
+        }
+    }
+    reviews
+}
+
+/// 1人のプレイヤーの複数ゲームの集計
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    /// 選択肢が複数あった判断の数
+    pub decisions: usize,
+    /// そのうち最適と一致した数
+    pub matched: usize,
+    /// ゲームごとの期待値損失の合計
+    pub game_losses: Vec<f64>,
+}
+
+impl PlayerProfile {
+    pub fn new() -> PlayerProfile {
+        PlayerProfile::default()
+    }
+
+    /// seat として遊んだゲームを加える
+    pub fn add_game(&mut self, ai: &YachtAI, game: &GameState, seat: u8) {
+        self.add_reviews(&review_game(ai, game, seat));
+    }
+
+    /// 1ゲーム分の判断の評価を加える
+    pub fn add_reviews(&mut self, reviews: &[DecisionReview]) {
+        let free: Vec<&DecisionReview> = reviews.iter().filter(|r| !r.forced).collect();
+        self.decisions += free.len();
+        self.matched += free.iter().filter(|r| r.matched).count();
+        self.game_losses
+            .push(reviews.iter().map(|r| r.loss.max(0.0) as f64).sum());
+    }
+
+    pub fn games(&self) -> usize {
+        self.game_losses.len()
+    }
+
+    /// 最適な判断との一致率（判断がなければ 0）
+    pub fn match_rate(&self) -> f64 {
+        if self.decisions == 0 {
+            0.0
+        } else {
+            self.matched as f64 / self.decisions as f64
+        }
+    }
+
+    /// 1ゲームあたりの期待値損失
+    pub fn loss_per_game(&self) -> f64 {
+        Distribution::from_samples(&self.game_losses).map_or(0.0, |d| d.mean)
+    }
+
+    /// 一致率の標本のばらつき（二項分布の分散、全て一致でも 0 にならないよう (k+1)/(n+2) で見積もる）
+    fn match_rate_variance(&self) -> f64 {
+        let p = (self.matched + 1) as f64 / (self.decisions + 2) as f64;
+        p * (1.0 - p) / self.decisions.max(1) as f64
+    }
+
+    /// 1ゲームあたりの損失の平均の標本のばらつき
+    fn loss_variance(&self) -> f64 {
+        Distribution::from_samples(&self.game_losses)
+            .map_or(0.0, |d| d.std_dev.powi(2) / self.games() as f64)
+    }
+}
+
+/// 正規分布（平均と標準偏差）
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Distribution {
+    /// 標本から推定する（標本がなければ None、1つなら標準偏差 0）
+    pub fn from_samples(samples: &[f64]) -> Option<Distribution> {
+        if samples.is_empty() {
+            return None;
+        }
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = if samples.len() > 1 {
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        Some(Distribution {
+            mean,
+            std_dev: variance.sqrt(),
+        })
+    }
+
+    /// 平均からのずれ（標準偏差の何倍か）
+    fn z(&self, x: f64, extra_variance: f64, min_std_dev: f64) -> f64 {
+        (x - self.mean) / self.spread(extra_variance, min_std_dev)
+    }
+
+    fn spread(&self, extra_variance: f64, min_std_dev: f64) -> f64 {
+        (self.std_dev.max(min_std_dev).powi(2) + extra_variance).sqrt()
+    }
+
+    /// 正規分布の対数尤度
+    fn log_likelihood(&self, x: f64, extra_variance: f64, min_std_dev: f64) -> f64 {
+        let z = self.z(x, extra_variance, min_std_dev);
+        -0.5 * z * z - self.spread(extra_variance, min_std_dev).ln()
+    }
+}
+
+/// 人間またはボットのプレイヤー群の指標の分布
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Reference {
+    /// プレイヤーごとの一致率
+    pub match_rate: Distribution,
+    /// プレイヤーごとの1ゲームあたりの期待値損失
+    pub loss_per_game: Distribution,
+}
+
+impl Reference {
+    /// プレイヤーの集計から推定する（ゲームのあるプレイヤーがいなければ None）
+    pub fn from_profiles(profiles: &[PlayerProfile]) -> Option<Reference> {
+        let played: Vec<&PlayerProfile> = profiles.iter().filter(|p| p.games() > 0).collect();
+        let rates: Vec<f64> = played.iter().map(|p| p.match_rate()).collect();
+        let losses: Vec<f64> = played.iter().map(|p| p.loss_per_game()).collect();
+        Some(Reference {
+            match_rate: Distribution::from_samples(&rates)?,
+            loss_per_game: Distribution::from_samples(&losses)?,
+        })
+    }
+}
+
+/// 疑わしさの根拠
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Evidence {
+    /// 一致率と、人間・ボットの分布からのずれ（標準偏差の何倍か）
+    MatchRate { rate: f64, human_z: f64, bot_z: f64 },
+    /// 1ゲームあたりの期待値損失と、人間・ボットの分布からのずれ
+    LossPerGame { loss: f64, human_z: f64, bot_z: f64 },
+    /// 判断が `MIN_DECISIONS` より少ない
+    InsufficientData { decisions: usize },
+}
+
+/// 判定の結果
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Assessment {
+    /// ボットの分布に属する確率（0-1、人間と半々を事前確率とする）
+    pub suspicion: f64,
+    pub games: usize,
+    pub decisions: usize,
+    pub evidence: Vec<Evidence>,
+}
+
+/// プレイヤーの集計を人間・ボットの分布と比べる
+pub fn assess(profile: &PlayerProfile, human: &Reference, bot: &Reference) -> Assessment {
+    let rate = profile.match_rate();
+    let rate_variance = profile.match_rate_variance();
+    let loss = profile.loss_per_game();
+    let loss_variance = profile.loss_variance();
+
+    let log_likelihood = |r: &Reference| {
+        r.match_rate
+            .log_likelihood(rate, rate_variance, MIN_RATE_STD_DEV)
+            + r.loss_per_game
+                .log_likelihood(loss, loss_variance, MIN_LOSS_STD_DEV)
+    };
+    let log_ratio = log_likelihood(bot) - log_likelihood(human);
+    let suspicion = if profile.games() == 0 {
+        0.0
+    } else {
+        1.0 / (1.0 + (-log_ratio).exp())
+    };
+
+    let mut evidence = vec![
+        Evidence::MatchRate {
+            rate,
+            human_z: human.match_rate.z(rate, rate_variance, MIN_RATE_STD_DEV),
+            bot_z: bot.match_rate.z(rate, rate_variance, MIN_RATE_STD_DEV),
+        },
+        Evidence::LossPerGame {
+            loss,
+            human_z: human.loss_per_game.z(loss, loss_variance, MIN_LOSS_STD_DEV),
+            bot_z: bot.loss_per_game.z(loss, loss_variance, MIN_LOSS_STD_DEV),
+        },
+    ];
+    if profile.decisions < MIN_DECISIONS {
+        evidence.push(Evidence::InsufficientData {
+            decisions: profile.decisions,
+        });
+    }
+    Assessment {
+        suspicion,
+        games: profile.games(),
+        decisions: profile.decisions,
+        evidence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::play::Strategy;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// 席0 はときどき次善の手を選ぶ人間役、席1 は AI で1ゲーム遊ぶ
+    fn play(ai: &YachtAI, seed: u64, mistake_rate: f64) -> GameState {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut game = GameState::new();
+        game.set_seed(seed);
+        while !game.is_game_over() {
+            if game.current_player() == 1 {
+                ai.play_turn(&mut game).unwrap();
+                continue;
+            }
+            game.roll().unwrap();
+            while game.rolls_left() > 0 {
+                let choices = ai.hold_choices(&game, 2);
+                let pick = if choices.len() > 1 && rng.gen_bool(mistake_rate) {
+                    1
+                } else {
+                    0
+                };
+                let choice = choices[pick];
+                if choice.stop {
+                    game.stand().unwrap();
+                    break;
+                }
+                for (i, &hold) in choice.holds.iter().enumerate() {
+                    let _ = game.set_hold(i, hold);
+                }
+                game.roll().unwrap();
+            }
+            let choices = ai.category_choices(&game, 2);
+            let pick = if choices.len() > 1 && rng.gen_bool(mistake_rate) {
+                1
+            } else {
+                0
+            };
+            game.select(choices[pick].category).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_review_game() {
+        let ai = YachtAI::new();
+        let game = play(&ai, 1, 0.3);
+        let bot = review_game(&ai, &game, 1);
+        assert!(bot.iter().all(|r| r.matched), "{bot:?}");
+        assert_eq!(bot.iter().filter(|r| r.forced).count(), 1);
+        assert!(bot.iter().any(|r| r.kind == DecisionKind::Hold));
+
+        let human = review_game(&ai, &game, 0);
+        assert!(human.iter().any(|r| !r.matched));
+        let total: f32 = human.iter().map(|r| r.loss).sum();
+        let breakdown = ai.luck_skill_breakdown(&game, 0);
+        assert!((total - breakdown.skill_loss).abs() < 0.01);
+        assert!(review_game(&ai, &GameState::with_ruleset(Ruleset::Generala), 0).is_empty());
+    }
+
+    #[test]
+    fn test_review_tampered_game() {
+        let ai = YachtAI::new();
+        let game = play(&ai, 2, 0.0);
+        let mut json: serde_json::Value = serde_json::from_str(&game.to_json()).unwrap();
+        let history = json["history"].as_array_mut().unwrap();
+        history[0]["Roll"]["rolls_left"] = 0.into();
+        history[1]["Roll"]["after"] = serde_json::json!([0, 7, 1, 1, 1]);
+        history.push(history.last().unwrap().clone());
+        let tampered: GameState = serde_json::from_value(json).unwrap();
+        let reviews = review_game(&ai, &tampered, 0);
+        assert!(!reviews.is_empty());
+        assert!(reviews.len() <= review_game(&ai, &game, 0).len());
+    }
+
+    #[test]
+    fn test_breakdown_tampered_game() {
+        let ai = YachtAI::new();
+        let game = play(&ai, 3, 0.0);
+        let mut json: serde_json::Value = serde_json::from_str(&game.to_json()).unwrap();
+        let mut rolls = json["history"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .filter_map(|event| event.get_mut("Roll"));
+        rolls.next().unwrap()["rolls_left"] = 0.into();
+        rolls.next().unwrap()["rolls_left"] = 9.into();
+        rolls.next().unwrap()["before"] = serde_json::json!([0, 7, 1, 1, 1]);
+        let tampered: GameState = serde_json::from_value(json).unwrap();
+        // 飛ばす記録は review_game と同じなので、損失の合計も一致する
+        let breakdown = ai.luck_skill_breakdown(&tampered, 0);
+        let total: f32 = review_game(&ai, &tampered, 0).iter().map(|r| r.loss).sum();
+        assert!((total - breakdown.skill_loss).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_assess() {
+        let ai = YachtAI::new();
+        let mut humans = Vec::new();
+        let mut bots = Vec::new();
+        for player in 0..5u64 {
+            let mut human = PlayerProfile::new();
+            let mut bot = PlayerProfile::new();
+            for game in 0..2 {
+                let game = play(&ai, player * 10 + game, 0.2 + 0.05 * player as f64);
+                human.add_game(&ai, &game, 0);
+                bot.add_game(&ai, &game, 1);
+            }
+            humans.push(human);
+            bots.push(bot);
+        }
+        let human_ref = Reference::from_profiles(&humans).unwrap();
+        let bot_ref = Reference::from_profiles(&bots).unwrap();
+        assert!(human_ref.match_rate.mean < bot_ref.match_rate.mean);
+        assert!(bot_ref.loss_per_game.mean < 0.01);
+
+        let mut suspect = PlayerProfile::new();
+        let mut careful = PlayerProfile::new();
+        for seed in 100..102 {
+            let game = play(&ai, seed, 0.25);
+            suspect.add_game(&ai, &game, 1);
+            careful.add_game(&ai, &game, 0);
+        }
+        let assessment = assess(&suspect, &human_ref, &bot_ref);
+        assert!(assessment.suspicion > 0.95, "{assessment:?}");
+        assert_eq!(assessment.games, 2);
+        assert!(assessment
+            .evidence
+            .iter()
+            .any(|e| matches!(e, Evidence::InsufficientData { .. })));
+        let assessment = assess(&careful, &human_ref, &bot_ref);
+        assert!(assessment.suspicion < 0.05, "{assessment:?}");
+        match assessment.evidence[0] {
+            Evidence::MatchRate { human_z, bot_z, .. } => assert!(human_z.abs() < bot_z.abs()),
+            ref e => panic!("{e:?}"),
+        }
+        assert_eq!(Reference::from_profiles(&[PlayerProfile::new()]), None);
+    }
+}
@@ -0,0 +1,538 @@
+//! ダイスの公正さの統計的な監査
+//!
+//! ゲームの記録（`GameEvent::Roll` でホールドせずに振った位置の出目）から、目の頻度のカイ二乗適合度検定と
+//! 連の検定（出目を小さい目・大きい目に分けた並びの連の数、Wald-Wolfowitz）を行う。
+//! サーバーの乱数まわりを変えた後の不具合や、実物のダイスを入力するモードでの偏ったダイスを見つけるのに使う。
+//!
+//! 検定は位置（ダイス）ごと・席ごと・全体で行い、それぞれの p 値と、検定の数で補正した
+//! （Bonferroni）全体の p 値を有意水準で報告する。期待度数が小さい（ロールが少ない）と p 値は近似が粗い。
+
+use serde::{Deserialize, Serialize};
+
+use crate::dice_model::DiceModel;
+use crate::GameEvent;
+
+/// 偏りの有意性
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Significance {
+    /// p >= 0.05
+    None,
+    /// p < 0.05
+    Weak,
+    /// p < 0.01
+    Strong,
+    /// p < 0.001
+    Extreme,
+}
+
+impl Significance {
+    pub fn from_p_value(p_value: f64) -> Significance {
+        if p_value < 0.001 {
+            Significance::Extreme
+        } else if p_value < 0.01 {
+            Significance::Strong
+        } else if p_value < 0.05 {
+            Significance::Weak
+        } else {
+            Significance::None
+        }
+    }
+}
+
+/// 記録された1個のダイスの出目
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RolledDie {
+    pub seat: u8,
+    /// ダイスの位置（0-4）
+    pub die: u8,
+    pub value: u8,
+}
+
+/// 記録から振った出目を順に取り出す（ホールドしていた位置は含めない）
+pub fn rolled_dice(history: &[GameEvent]) -> Vec<RolledDie> {
+    let mut rolled = Vec::new();
+    for event in history {
+        if let GameEvent::Roll {
+            seat, held, after, ..
+        } = event
+        {
+            for die in (0..5).filter(|&i| !held[i]) {
+                rolled.push(RolledDie {
+                    seat: *seat,
+                    die: die as u8,
+                    value: after[die],
+                });
+            }
+        }
+    }
+    rolled
+}
+
+/// カイ二乗適合度検定の結果
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChiSquareTest {
+    /// 目ごとの観測度数
+    pub counts: Vec<u32>,
+    /// 目ごとの期待度数
+    pub expected: Vec<f64>,
+    pub statistic: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+}
+
+/// 目の頻度が dice の確率に従うかのカイ二乗検定（出目がなければ None）
+///
+/// 出る確率が 0 の目が出ていれば p 値は 0。
+pub fn chi_square_test(values: &[u8], dice: &DiceModel) -> Option<ChiSquareTest> {
+    if values.is_empty() {
+        return None;
+    }
+    let faces = dice.faces();
+    let mut counts = vec![0u32; faces];
+    let mut impossible = false;
+    for &value in values {
+        match counts.get_mut((value as usize).wrapping_sub(1)) {
+            Some(count) => *count += 1,
+            None => impossible = true,
+        }
+    }
+    let n = values.len() as f64;
+    let expected: Vec<f64> = (1..=faces as u8)
+        .map(|face| n * dice.probability(face))
+        .collect();
+    let mut statistic = 0.0;
+    let mut categories = 0;
+    for (&count, &e) in counts.iter().zip(&expected) {
+        if e > 0.0 {
+            statistic += (count as f64 - e).powi(2) / e;
+            categories += 1;
+        } else if count > 0 {
+            impossible = true;
+        }
+    }
+    let degrees_of_freedom = categories.max(1) - 1;
+    let p_value = if impossible {
+        statistic = f64::INFINITY;
+        0.0
+    } else if degrees_of_freedom == 0 {
+        1.0
+    } else {
+        gamma_q(degrees_of_freedom as f64 / 2.0, statistic / 2.0)
+    };
+    Some(ChiSquareTest {
+        counts,
+        expected,
+        statistic,
+        degrees_of_freedom,
+        p_value,
+    })
+}
+
+/// 連の検定の結果（出目を小さい目・大きい目に分けた並び）
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunsTest {
+    /// 小さい目の上限（これ以下が小さい目）
+    pub split: u8,
+    pub runs: u32,
+    pub expected_runs: f64,
+    /// 正規近似の z 値（負なら連が少ない = 同じ側が続きやすい）
+    pub z: f64,
+    /// 両側の p 値
+    pub p_value: f64,
+}
+
+/// 出目の並びがランダムかの連の検定（片側の目しか出ていなければ None）
+///
+/// 小さい目と大きい目は dice の確率がなるべく半々になるように分ける。
+pub fn runs_test(values: &[u8], dice: &DiceModel) -> Option<RunsTest> {
+    let split = median_face(dice);
+    let low: Vec<bool> = values.iter().map(|&v| v <= split).collect();
+    let n1 = low.iter().filter(|&&l| l).count() as f64;
+    let n2 = low.len() as f64 - n1;
+    if n1 == 0.0 || n2 == 0.0 {
+        return None;
+    }
+    let n = n1 + n2;
+    let runs = 1 + low.windows(2).filter(|w| w[0] != w[1]).count() as u32;
+    let expected_runs = 2.0 * n1 * n2 / n + 1.0;
+    let variance = 2.0 * n1 * n2 * (2.0 * n1 * n2 - n) / (n * n * (n - 1.0));
+    if variance <= 0.0 {
+        return None;
+    }
+    let z = (runs as f64 - expected_runs) / variance.sqrt();
+    Some(RunsTest {
+        split,
+        runs,
+        expected_runs,
+        z,
+        p_value: erfc(z.abs() / std::f64::consts::SQRT_2),
+    })
+}
+
+/// 累積確率が 1/2 に最も近くなる目
+fn median_face(dice: &DiceModel) -> u8 {
+    let mut cumulative = 0.0;
+    let mut best = (1, f64::INFINITY);
+    for face in 1..dice.faces() as u8 {
+        cumulative += dice.probability(face);
+        let distance = (cumulative - 0.5).abs();
+        if distance < best.1 {
+            best = (face, distance);
+        }
+    }
+    best.0
+}
+
+/// 1組の出目に対する検定
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SampleAudit {
+    pub rolls: usize,
+    pub chi_square: Option<ChiSquareTest>,
+    pub runs: Option<RunsTest>,
+}
+
+impl SampleAudit {
+    pub fn new(values: &[u8], dice: &DiceModel) -> SampleAudit {
+        SampleAudit {
+            rolls: values.len(),
+            chi_square: chi_square_test(values, dice),
+            runs: runs_test(values, dice),
+        }
+    }
+
+    fn p_values(&self) -> impl Iterator<Item = f64> + '_ {
+        let chi = self.chi_square.iter().map(|t| t.p_value);
+        chi.chain(self.runs.iter().map(|t| t.p_value))
+    }
+
+    /// 検定の最小の p 値（検定できなければ None）
+    pub fn min_p_value(&self) -> Option<f64> {
+        self.p_values().reduce(f64::min)
+    }
+
+    pub fn significance(&self) -> Significance {
+        self.min_p_value()
+            .map_or(Significance::None, Significance::from_p_value)
+    }
+}
+
+/// 監査の結果
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// 全ての出目
+    pub overall: SampleAudit,
+    /// 位置（ダイス）ごと
+    pub per_die: Vec<SampleAudit>,
+    /// 席ごと（席の番号順、出目がない席も含む）
+    pub per_seat: Vec<SampleAudit>,
+    /// 全ての検定の最小の p 値を検定の数で補正したもの
+    pub p_value: f64,
+    pub significance: Significance,
+}
+
+impl AuditReport {
+    /// 有意な偏りがある席
+    pub fn flagged_seats(&self, level: Significance) -> Vec<u8> {
+        (0..self.per_seat.len() as u8)
+            .filter(|&seat| self.per_seat[seat as usize].significance() >= level)
+            .collect()
+    }
+}
+
+/// 出目を集めて監査する
+#[derive(Clone, Debug, Default)]
+pub struct DiceAudit {
+    dice: DiceModel,
+    rolled: Vec<RolledDie>,
+}
+
+impl DiceAudit {
+    /// 6面の一様なダイスを前提とする
+    pub fn new() -> DiceAudit {
+        DiceAudit::default()
+    }
+
+    /// dice の確率を前提とする（偏ったダイスや d8 など）
+    pub fn with_dice(dice: DiceModel) -> DiceAudit {
+        DiceAudit {
+            dice,
+            rolled: Vec::new(),
+        }
+    }
+
+    /// ゲームの記録の出目を追加する
+    pub fn add_history(&mut self, history: &[GameEvent]) {
+        self.rolled.extend(rolled_dice(history));
+    }
+
+    pub fn add_die(&mut self, rolled: RolledDie) {
+        self.rolled.push(rolled);
+    }
+
+    pub fn rolls(&self) -> usize {
+        self.rolled.len()
+    }
+
+    fn sample(&self, filter: impl Fn(&RolledDie) -> bool) -> SampleAudit {
+        let values: Vec<u8> = self
+            .rolled
+            .iter()
+            .filter(|r| filter(r))
+            .map(|r| r.value)
+            .collect();
+        SampleAudit::new(&values, &self.dice)
+    }
+
+    pub fn report(&self) -> AuditReport {
+        let overall = self.sample(|_| true);
+        let per_die: Vec<SampleAudit> = (0..5).map(|die| self.sample(|r| r.die == die)).collect();
+        let seats = self
+            .rolled
+            .iter()
+            .map(|r| r.seat as usize + 1)
+            .max()
+            .unwrap_or(0);
+        let per_seat: Vec<SampleAudit> = (0..seats as u8)
+            .map(|seat| self.sample(|r| r.seat == seat))
+            .collect();
+
+        let samples = std::iter::once(&overall).chain(&per_die).chain(&per_seat);
+        let p_values: Vec<f64> = samples.flat_map(SampleAudit::p_values).collect();
+        let min_p = p_values.iter().copied().fold(1.0, f64::min);
+        let p_value = (min_p * p_values.len().max(1) as f64).min(1.0);
+        AuditReport {
+            overall,
+            per_die,
+            per_seat,
+            p_value,
+            significance: Significance::from_p_value(p_value),
+        }
+    }
+}
+
+/// ゲームの記録1つ（6面の一様なダイス）を監査する
+pub fn audit_history(history: &[GameEvent]) -> AuditReport {
+    let mut audit = DiceAudit::new();
+    audit.add_history(history);
+    audit.report()
+}
+
+/// 複数のセッションの監査
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionsAudit {
+    /// セッションごと
+    pub sessions: Vec<AuditReport>,
+    /// 全セッションをまとめたもの
+    pub combined: AuditReport,
+}
+
+impl SessionsAudit {
+    /// 有意な偏りがあるセッションの番号
+    pub fn flagged_sessions(&self, level: Significance) -> Vec<usize> {
+        (0..self.sessions.len())
+            .filter(|&i| self.sessions[i].significance >= level)
+            .collect()
+    }
+}
+
+/// セッション（ゲームの記録）ごとと全体を監査する
+pub fn audit_sessions<'a, I>(histories: I, dice: &DiceModel) -> SessionsAudit
+where
+    I: IntoIterator<Item = &'a [GameEvent]>,
+{
+    let mut combined = DiceAudit::with_dice(dice.clone());
+    let mut sessions = Vec::new();
+    for history in histories {
+        let mut audit = DiceAudit::with_dice(dice.clone());
+        audit.add_history(history);
+        sessions.push(audit.report());
+        combined.add_history(history);
+    }
+    SessionsAudit {
+        sessions,
+        combined: combined.report(),
+    }
+}
+
+/// ln Γ(x)（Lanczos 近似、x > 0）
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .zip(1..)
+        .fold(COEFFICIENTS[0], |s, (c, i)| s + c / (x + i as f64));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// 正則化された上側不完全ガンマ関数 Q(a, x)
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let log_prefix = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1.0 {
+        // 級数で P(a, x) を求める
+        let (mut term, mut sum, mut ap) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..500 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1.0 - sum * log_prefix.exp()).clamp(0.0, 1.0)
+    } else {
+        // 連分数（Lentz 法）
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        (h * log_prefix.exp()).clamp(0.0, 1.0)
+    }
+}
+
+/// 相補誤差関数（x >= 0）
+fn erfc(x: f64) -> f64 {
+    gamma_q(0.5, x * x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_distributions() {
+        // カイ二乗分布（自由度5）の 5% 点と 1% 点
+        assert!((gamma_q(2.5, 11.0705 / 2.0) - 0.05).abs() < 1e-4);
+        assert!((gamma_q(2.5, 15.0863 / 2.0) - 0.01).abs() < 1e-4);
+        // 自由度2では Q = exp(-x/2)
+        assert!((gamma_q(1.0, 3.0) - (-3.0f64).exp()).abs() < 1e-12);
+        assert!((erfc(1.959964 / std::f64::consts::SQRT_2) - 0.05).abs() < 1e-6);
+        assert!((ln_gamma(5.0) - 24.0f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_chi_square_and_runs() {
+        let dice = DiceModel::standard();
+        let even: Vec<u8> = (0..600u32).map(|i| (i % 6 + 1) as u8).collect();
+        let chi = chi_square_test(&even, dice).unwrap();
+        assert_eq!(chi.counts, vec![100; 6]);
+        assert_eq!(chi.statistic, 0.0);
+        assert_eq!(chi.p_value, 1.0);
+        // 1-2-3-4-5-6 の繰り返しは小さい目と大きい目が3個ずつ続く: 連が少なすぎる
+        let runs = runs_test(&even, dice).unwrap();
+        assert_eq!(runs.split, 3);
+        assert_eq!(runs.runs, 200);
+        assert!(runs.z < 0.0);
+        assert_eq!(
+            Significance::from_p_value(runs.p_value),
+            Significance::Extreme
+        );
+        // 小さい目と大きい目の交互は連が多すぎる
+        let alternating: Vec<u8> = (0..100).map(|i| if i % 2 == 0 { 2 } else { 5 }).collect();
+        let runs = runs_test(&alternating, dice).unwrap();
+        assert_eq!(runs.runs, 100);
+        assert!(runs.z > 0.0 && runs.p_value < 1e-6);
+
+        let loaded: Vec<u8> = (0..300u32)
+            .map(|i| if i % 3 == 0 { 6 } else { (i % 6 + 1) as u8 })
+            .collect();
+        let chi = chi_square_test(&loaded, dice).unwrap();
+        assert_eq!(chi.degrees_of_freedom, 5);
+        assert!(chi.p_value < 0.001, "{chi:?}");
+        // 6面ダイスにない目
+        assert_eq!(chi_square_test(&[1, 7], dice).unwrap().p_value, 0.0);
+        let no_ones = DiceModel::weighted(&[0.0, 1.0, 1.0, 1.0, 1.0, 1.0]).unwrap();
+        assert_eq!(chi_square_test(&[1, 2], &no_ones).unwrap().p_value, 0.0);
+        assert_eq!(chi_square_test(&[], dice), None);
+        assert_eq!(runs_test(&[1, 2, 3], dice), None);
+    }
+
+    #[test]
+    fn test_audit_fair_rng() {
+        let histories: Vec<Vec<GameEvent>> = (0..20)
+            .map(|seed| {
+                let mut game = GameState::new();
+                game.set_seed(seed);
+                while !game.is_game_over() {
+                    game.roll().unwrap();
+                    let category = game.current_board().available_categories()[0];
+                    game.select(category).unwrap();
+                }
+                game.history().to_vec()
+            })
+            .collect();
+        let audit = audit_sessions(histories.iter().map(Vec::as_slice), DiceModel::standard());
+        assert_eq!(audit.sessions.len(), 20);
+        assert_eq!(audit.combined.overall.rolls, 20 * 24 * 5);
+        assert_eq!(audit.combined.per_seat.len(), 2);
+        assert!(
+            audit.combined.significance < Significance::Strong,
+            "{:?}",
+            audit.combined
+        );
+        assert!(audit.flagged_sessions(Significance::Extreme).is_empty());
+    }
+
+    #[test]
+    fn test_audit_physical_dice() {
+        // 席1 のプレイヤーのダイスだけ 6 に偏っている（4番目のダイスは特に）
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut game = GameState::new();
+        while !game.is_game_over() {
+            let seat = game.current_player();
+            for _ in 0..3 {
+                let values = std::array::from_fn(|die| {
+                    let loaded = if die == 3 { 0.6 } else { 0.3 };
+                    if seat == 1 && rng.gen_bool(loaded) {
+                        6
+                    } else {
+                        rng.gen_range(1..=6)
+                    }
+                });
+                game.enter_roll(values).unwrap();
+            }
+            let category = game.current_board().available_categories()[0];
+            game.select(category).unwrap();
+        }
+        let report = audit_history(game.history());
+        assert_eq!(report.per_die[3].significance(), Significance::Extreme);
+        assert_eq!(report.flagged_seats(Significance::Strong), vec![1]);
+        assert!(report.significance >= Significance::Strong, "{report:?}");
+    }
+}
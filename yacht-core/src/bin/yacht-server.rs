@@ -0,0 +1,16 @@
+//! ローカル対戦サーバー
+//!
+//! `cargo run --release --features server --bin yacht-server -- [アドレス]`
+//! （アドレスの既定値は 127.0.0.1:9000）。プロトコルは `yacht_core::server` を参照。
+
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:9000".to_string());
+    let listener = TcpListener::bind(&addr).await?;
+    eprintln!("yacht-server: listening on ws://{}", listener.local_addr()?);
+    yacht_core::server::serve(listener).await
+}
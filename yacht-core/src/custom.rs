@@ -0,0 +1,826 @@
+//! ハウスルールのカテゴリ（宣言的な定義）
+//!
+//! カテゴリを「条件」と「得点」の組で定義する。どちらも出目パターン（各目の個数）だけで決まる。
+//! 定義は JSON で書ける（`CustomRuleset::from_json`）:
+//!
+//! ```json
+//! {
+//!   "categories": [
+//!     {"name": "Two Pairs", "when": {"groups": [2, 2]}, "score": {"groups": [2, 2]}},
+//!     {"name": "Odds only", "when": {"only_faces": [1, 3, 5]}, "score": "sum"},
+//!     {"name": "Sum >= 25", "when": {"sum_at_least": 25}, "score": "sum"}
+//!   ],
+//!   "upper_bonus": null
+//! }
+//! ```
+//!
+//! 条件を満たさなければ 0点。状態数（カテゴリの組 × 上段累計）が `MAX_SOLVER_STATES` 以下なら
+//! 最適な期待値を実行時に計算して AI に使える（`CustomAI`）。
+//!
+//! ゲームは `GameState`（`Ruleset::Custom`、1列で欄の行はカテゴリの番号）で遊ぶ。
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dice_model::DiceModel;
+use crate::dp_table::{self, DicePattern, MAX_FACES};
+use crate::play::Strategy;
+use crate::solver::{self, keep_to_holds, Leaf, TurnSolver};
+use crate::{Cell, DpTableError, GameError, GameState, HoldDecision, Ruleset, ScoreBoard};
+
+/// 1ルールのカテゴリ数の上限
+pub const MAX_CATEGORIES: usize = 20;
+/// 期待値を計算する状態数の上限
+///
+/// 1状態ごとに手番を探索するので、リリースビルドで1状態あたり1ミリ秒ほどかかる。対話的に
+/// 待てる 0.5秒ほどに収めるため、ボーナスなしなら9カテゴリ、上段ボーナスが 63点なら3カテゴリまで。
+/// 超えるルールは `CustomAI::greedy` で遊ぶ（ヨットの12カテゴリそのものは組み込みの DPテーブル）。
+pub const MAX_SOLVER_STATES: usize = 1 << 9;
+
+/// 出目の条件
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// 常に満たす
+    Always,
+    /// n個以上同じ目がある
+    Kind(u8),
+    /// 異なる目でそれぞれ指定の個数以上の組がある（[3, 2] = フルハウス、[2, 2] = ツーペア）
+    Groups(Vec<u8>),
+    /// n種類の連続した目がある
+    Straight(u8),
+    /// 全てのダイスが指定の目のどれか
+    OnlyFaces(Vec<u8>),
+    /// 出目の合計が n 以上
+    SumAtLeast(u8),
+    /// 出目の合計が n 以下
+    SumAtMost(u8),
+    /// 全て満たす
+    All(Vec<Condition>),
+    /// どれかを満たす
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+/// 条件を満たしたときの得点
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Score {
+    /// 固定の点数
+    Fixed(u8),
+    /// 全てのダイスの合計
+    Sum,
+    /// 指定の目のダイスの合計（[3] = 3の目）
+    Faces(Vec<u8>),
+    /// `Condition::Groups` の組のダイスの合計（組み方が複数あれば最大）
+    Groups(Vec<u8>),
+    /// 得点の和（255 で頭打ち）
+    Add(Vec<Score>),
+}
+
+/// 上段ボーナス（`upper` のカテゴリの合計が threshold 以上で points 点）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UpperBonus {
+    pub threshold: u8,
+    pub points: u8,
+}
+
+/// ハウスルールのカテゴリ
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CustomCategory {
+    pub name: String,
+    #[serde(default = "always")]
+    pub when: Condition,
+    pub score: Score,
+    /// 上段ボーナスの対象か
+    #[serde(default)]
+    pub upper: bool,
+}
+
+fn always() -> Condition {
+    Condition::Always
+}
+
+/// 異なる目で sizes の組を作ったときの最大の合計（作れなければ None）
+fn best_groups(p: &DicePattern, sizes: &[u8], used_faces: u8) -> Option<u8> {
+    let Some((&size, rest)) = sizes.split_first() else {
+        return Some(0);
+    };
+    (0..MAX_FACES)
+        .filter(|&face| used_faces >> face & 1 == 0 && p[face] >= size)
+        .filter_map(|face| {
+            best_groups(p, rest, used_faces | 1 << face).map(|s| s + size * (face as u8 + 1))
+        })
+        .max()
+}
+
+impl Condition {
+    pub fn matches(&self, p: &DicePattern) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::Kind(n) => p.iter().any(|c| c >= n),
+            Condition::Groups(sizes) => best_groups(p, sizes, 0).is_some(),
+            Condition::Straight(len) => p.windows(*len as usize).any(|w| w.iter().all(|&c| c >= 1)),
+            Condition::OnlyFaces(faces) => {
+                (0..MAX_FACES).all(|face| p[face] == 0 || faces.contains(&(face as u8 + 1)))
+            }
+            Condition::SumAtLeast(n) => dp_table::pattern_pips(p) >= *n,
+            Condition::SumAtMost(n) => dp_table::pattern_pips(p) <= *n,
+            Condition::All(conditions) => conditions.iter().all(|c| c.matches(p)),
+            Condition::Any(conditions) => conditions.iter().any(|c| c.matches(p)),
+            Condition::Not(condition) => !condition.matches(p),
+        }
+    }
+
+    fn validate(&self) -> Result<(), CustomRulesError> {
+        match self {
+            Condition::Kind(n) if !(1..=5).contains(n) => Err(CustomRulesError::InvalidCount(*n)),
+            Condition::Groups(sizes) => validate_groups(sizes),
+            Condition::Straight(len) if !(1..=MAX_FACES as u8).contains(len) => {
+                Err(CustomRulesError::InvalidCount(*len))
+            }
+            Condition::OnlyFaces(faces) => validate_faces(faces),
+            Condition::All(conditions) | Condition::Any(conditions) => {
+                conditions.iter().try_for_each(Condition::validate)
+            }
+            Condition::Not(condition) => condition.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Score {
+    pub fn evaluate(&self, p: &DicePattern) -> u8 {
+        match self {
+            Score::Fixed(points) => *points,
+            Score::Sum => dp_table::pattern_pips(p),
+            Score::Faces(faces) => faces.iter().fold(0u8, |total, &face| {
+                total.saturating_add(p[face as usize - 1] * face)
+            }),
+            Score::Groups(sizes) => best_groups(p, sizes, 0).unwrap_or(0),
+            Score::Add(scores) => scores
+                .iter()
+                .fold(0u8, |total, s| total.saturating_add(s.evaluate(p))),
+        }
+    }
+
+    fn validate(&self) -> Result<(), CustomRulesError> {
+        match self {
+            Score::Faces(faces) => validate_faces(faces),
+            Score::Groups(sizes) => validate_groups(sizes),
+            Score::Add(scores) => scores.iter().try_for_each(Score::validate),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn validate_faces(faces: &[u8]) -> Result<(), CustomRulesError> {
+    match faces.iter().find(|f| !(1..=MAX_FACES as u8).contains(*f)) {
+        Some(&face) => Err(CustomRulesError::InvalidFace(face)),
+        None => Ok(()),
+    }
+}
+
+fn validate_groups(sizes: &[u8]) -> Result<(), CustomRulesError> {
+    let total: u8 = sizes.iter().fold(0, |t, &s| t.saturating_add(s));
+    match sizes.iter().find(|&&s| s == 0) {
+        Some(&size) => Err(CustomRulesError::InvalidCount(size)),
+        None if total > 5 || sizes.len() > 6 => Err(CustomRulesError::InvalidCount(total)),
+        None => Ok(()),
+    }
+}
+
+impl CustomCategory {
+    pub fn new(name: &str, when: Condition, score: Score) -> CustomCategory {
+        CustomCategory {
+            name: name.to_string(),
+            when,
+            score,
+            upper: false,
+        }
+    }
+
+    /// 上段ボーナスの対象にする
+    pub fn upper(mut self) -> CustomCategory {
+        self.upper = true;
+        self
+    }
+
+    /// 出目パターンの得点（条件を満たさなければ 0）
+    pub fn score_pattern(&self, p: &DicePattern) -> u8 {
+        if self.when.matches(p) {
+            self.score.evaluate(p)
+        } else {
+            0
+        }
+    }
+}
+
+/// ハウスルールの定義のエラー
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CustomRulesError {
+    /// JSON として読めない
+    Json(String),
+    NoCategories,
+    TooManyCategories(usize),
+    /// 目が 1-8 ではない
+    InvalidFace(u8),
+    /// 個数・長さが範囲外
+    InvalidCount(u8),
+    /// 上段ボーナスの閾値が 0（何も記入しなくてもボーナスになる）
+    ZeroBonusThreshold,
+    /// 上段ボーナスがあるのに `upper` のカテゴリがない
+    BonusWithoutUpper,
+}
+
+impl fmt::Display for CustomRulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomRulesError::Json(message) => write!(f, "invalid rules json: {message}"),
+            CustomRulesError::NoCategories => write!(f, "rules have no categories"),
+            CustomRulesError::TooManyCategories(n) => {
+                write!(f, "too many categories ({n}, max {MAX_CATEGORIES})")
+            }
+            CustomRulesError::InvalidFace(face) => {
+                write!(f, "face must be 1-{MAX_FACES} (got {face})")
+            }
+            CustomRulesError::InvalidCount(n) => write!(f, "dice count out of range ({n})"),
+            CustomRulesError::ZeroBonusThreshold => {
+                write!(f, "upper bonus threshold must be at least 1")
+            }
+            CustomRulesError::BonusWithoutUpper => {
+                write!(f, "upper bonus needs at least one upper category")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CustomRulesError {}
+
+/// ハウスルール（カテゴリの一覧と上段ボーナス）
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawCustomRuleset")]
+pub struct CustomRuleset {
+    pub categories: Vec<CustomCategory>,
+    #[serde(default)]
+    pub upper_bonus: Option<UpperBonus>,
+}
+
+/// 検証する前のハウスルールの JSON（保存したゲームから読むときも検証する）
+#[derive(Deserialize)]
+struct RawCustomRuleset {
+    categories: Vec<CustomCategory>,
+    #[serde(default)]
+    upper_bonus: Option<UpperBonus>,
+}
+
+impl TryFrom<RawCustomRuleset> for CustomRuleset {
+    type Error = CustomRulesError;
+
+    fn try_from(raw: RawCustomRuleset) -> Result<CustomRuleset, CustomRulesError> {
+        CustomRuleset::new(raw.categories, raw.upper_bonus)
+    }
+}
+
+impl CustomRuleset {
+    pub fn new(
+        categories: Vec<CustomCategory>,
+        upper_bonus: Option<UpperBonus>,
+    ) -> Result<CustomRuleset, CustomRulesError> {
+        let rules = CustomRuleset {
+            categories,
+            upper_bonus,
+        };
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub fn from_json(json: &str) -> Result<CustomRuleset, CustomRulesError> {
+        let raw: RawCustomRuleset =
+            serde_json::from_str(json).map_err(|e| CustomRulesError::Json(e.to_string()))?;
+        CustomRuleset::try_from(raw)
+    }
+
+    fn validate(&self) -> Result<(), CustomRulesError> {
+        if self.categories.is_empty() {
+            return Err(CustomRulesError::NoCategories);
+        }
+        if self.categories.len() > MAX_CATEGORIES {
+            return Err(CustomRulesError::TooManyCategories(self.categories.len()));
+        }
+        for category in &self.categories {
+            category.when.validate()?;
+            category.score.validate()?;
+        }
+        if let Some(bonus) = self.upper_bonus {
+            if bonus.threshold == 0 {
+                return Err(CustomRulesError::ZeroBonusThreshold);
+            }
+            if !self.categories.iter().any(|c| c.upper) {
+                return Err(CustomRulesError::BonusWithoutUpper);
+            }
+        }
+        Ok(())
+    }
+
+    /// ヨットの12カテゴリ（`Category` と同じ順番。ハウスルールを足す土台）
+    pub fn yacht() -> CustomRuleset {
+        use Condition::*;
+        let mut categories: Vec<CustomCategory> =
+            ["Ones", "Twos", "Threes", "Fours", "Fives", "Sixes"]
+                .iter()
+                .zip(1..)
+                .map(|(name, face)| {
+                    CustomCategory::new(name, Always, Score::Faces(vec![face])).upper()
+                })
+                .collect();
+        categories.extend([
+            CustomCategory::new("Full House", Groups(vec![3, 2]), Score::Sum),
+            CustomCategory::new("Four of a Kind", Kind(4), Score::Sum),
+            CustomCategory::new("Little Straight", Straight(4), Score::Fixed(15)),
+            CustomCategory::new("Big Straight", Straight(5), Score::Fixed(30)),
+            CustomCategory::new("Choice", Always, Score::Sum),
+            CustomCategory::new("Yacht", Kind(5), Score::Fixed(50)),
+        ]);
+        CustomRuleset {
+            categories,
+            upper_bonus: Some(UpperBonus {
+                threshold: dp_table::UPPER_BONUS_THRESHOLD as u8,
+                points: dp_table::UPPER_BONUS_POINTS,
+            }),
+        }
+    }
+
+    /// index 番目のカテゴリの得点（範囲外なら None）
+    pub fn score(&self, dice: &[u8; 5], index: usize) -> Option<u8> {
+        self.categories
+            .get(index)
+            .map(|c| c.score_pattern(&dp_table::dice_to_pattern(dice)))
+    }
+
+    /// 上段累計の取りうる数（ボーナスがなければ 1）
+    fn upper_states(&self) -> usize {
+        self.upper_bonus.map_or(1, |b| b.threshold as usize + 1)
+    }
+
+    /// 期待値の計算に必要な状態数
+    pub fn state_count(&self) -> usize {
+        self.upper_states()
+            .saturating_mul(1 << self.categories.len())
+    }
+
+    /// 上段累計 upper_sum で index に score を記入した後の (上段累計, ボーナス)
+    fn after_score(&self, upper_sum: usize, index: usize, score: u8) -> (usize, u8) {
+        match self.upper_bonus {
+            Some(bonus) if self.categories[index].upper => {
+                let threshold = bonus.threshold as usize;
+                let next = (upper_sum + score as usize).min(threshold);
+                let points = if upper_sum < threshold && next >= threshold {
+                    bonus.points
+                } else {
+                    0
+                };
+                (next, points)
+            }
+            _ => (upper_sum, 0),
+        }
+    }
+}
+
+/// 手番の途中状態の葉の評価（記入後の期待値はテーブルから引く）
+struct CustomLeaf<'a> {
+    rules: &'a CustomRuleset,
+    dice: &'a DiceModel,
+    table: &'a [f32],
+    upper_sum: usize,
+    used_hands: usize,
+}
+
+impl CustomLeaf<'_> {
+    /// 記入後の残りの期待得点（テーブルのない貪欲な AI は 0）
+    fn future(&self, upper_sum: usize, used_hands: usize) -> f32 {
+        self.table
+            .get(upper_sum << self.rules.categories.len() | used_hands)
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+impl Leaf for CustomLeaf<'_> {
+    fn num_dice(&self) -> usize {
+        5
+    }
+
+    fn dice(&self) -> &DiceModel {
+        self.dice
+    }
+
+    fn best_category(&self, dice: &DicePattern, _rolls_left: u8) -> Option<(usize, f32)> {
+        let mut best: Option<(usize, f32)> = None;
+        for (i, category) in self.rules.categories.iter().enumerate() {
+            if self.used_hands >> i & 1 == 1 {
+                continue;
+            }
+            let score = category.score_pattern(dice);
+            let (upper_sum, bonus) = self.rules.after_score(self.upper_sum, i, score);
+            let value =
+                score as f32 + bonus as f32 + self.future(upper_sum, self.used_hands | 1 << i);
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((i, value));
+            }
+        }
+        best
+    }
+}
+
+/// ハウスルールの最適AI（ホールドは自由ルールを前提とする）
+#[derive(Clone, Debug)]
+pub struct CustomAI {
+    rules: Arc<CustomRuleset>,
+    dice: DiceModel,
+    /// [上段累計][使用済みカテゴリ] → 残りの期待得点（`greedy` は空）
+    table: Vec<f32>,
+}
+
+impl CustomAI {
+    /// 期待値のテーブルを計算する（状態数が `MAX_SOLVER_STATES` を超えるなら `DpTableError::TooLarge`）
+    ///
+    /// 呼び出しの中で全ての状態の手番を探索するので、状態数（`CustomRuleset::state_count`）に
+    /// 比例して時間がかかる。上限の状態数でリリースビルド 0.5秒ほど、デバッグビルドはその10倍ほど。
+    pub fn new(rules: &CustomRuleset) -> Result<CustomAI, DpTableError> {
+        CustomAI::with_dice(rules, DiceModel::standard().clone())
+    }
+
+    /// dice を振る前提で期待値のテーブルを計算する（d4・d8 や偏ったダイス。かかる時間は `new` と同じ）
+    pub fn with_dice(rules: &CustomRuleset, dice: DiceModel) -> Result<CustomAI, DpTableError> {
+        if rules.state_count() > MAX_SOLVER_STATES {
+            return Err(DpTableError::TooLarge(rules.state_count()));
+        }
+        let n = rules.categories.len();
+        let all = (1 << n) - 1;
+        let mut table = vec![0.0f32; rules.state_count()];
+        // 上段のカテゴリごとの最高点（使用済みの最高点の和を超える上段累計には届かない）
+        let top: Vec<usize> = rules
+            .categories
+            .iter()
+            .map(|c| match c.upper {
+                true => dice
+                    .patterns(5)
+                    .iter()
+                    .map(|pp| c.score_pattern(&pp.pattern) as usize)
+                    .max()
+                    .unwrap_or(0),
+                false => 0,
+            })
+            .collect();
+        for used_hands in solver::used_sets_descending(all) {
+            let reachable: usize = (0..n)
+                .filter(|&i| used_hands >> i & 1 == 1)
+                .map(|i| top[i])
+                .sum();
+            for upper_sum in 0..=reachable.min(rules.upper_states() - 1) {
+                let leaf = CustomLeaf {
+                    rules,
+                    dice: &dice,
+                    table: &table,
+                    upper_sum,
+                    used_hands,
+                };
+                table[upper_sum << n | used_hands] = TurnSolver::new(leaf).turn_value(3);
+            }
+        }
+        Ok(CustomAI {
+            rules: Arc::new(rules.clone()),
+            dice,
+            table,
+        })
+    }
+
+    /// 比較用・大きなルール用: その手番の得点（とボーナス）だけを見るAI（テーブルを計算しない）
+    pub fn greedy(rules: &CustomRuleset) -> CustomAI {
+        CustomAI::greedy_with_dice(rules, DiceModel::standard().clone())
+    }
+
+    /// dice を振る前提の `greedy`（振り直しの判断だけ dice の確率に従う）
+    pub fn greedy_with_dice(rules: &CustomRuleset, dice: DiceModel) -> CustomAI {
+        CustomAI {
+            rules: Arc::new(rules.clone()),
+            dice,
+            table: Vec::new(),
+        }
+    }
+
+    /// このAIが遊ぶルール（`GameState::solo(ai.ruleset())` で遊べる）
+    pub fn ruleset(&self) -> Ruleset {
+        Ruleset::Custom(self.rules.clone())
+    }
+
+    fn leaf<'a>(&'a self, board: &ScoreBoard) -> CustomLeaf<'a> {
+        CustomLeaf {
+            rules: &self.rules,
+            dice: &self.dice,
+            table: &self.table,
+            upper_sum: board.upper_sum_capped(),
+            used_hands: board.used_hands_mask(),
+        }
+    }
+
+    /// 手番の開始時点から最適に続けた場合の最終合計点数の期待値（`greedy` は今の合計点数）
+    pub fn expected_score(&self, board: &ScoreBoard) -> f32 {
+        let leaf = self.leaf(board);
+        board.total() as f32 + leaf.future(leaf.upper_sum, leaf.used_hands)
+    }
+
+    /// 振った後（あと rolls_left 回振れる）にホールドするダイスを決める
+    fn holds(&self, board: &ScoreBoard, dice: &[u8; 5], rolls_left: u8) -> HoldDecision {
+        let pattern = dp_table::dice_to_pattern(dice);
+        let mut solver = TurnSolver::new(self.leaf(board));
+        match solver.best_keep(&pattern, rolls_left) {
+            Some((keep, _)) => HoldDecision::Reroll(keep_to_holds(dice, &keep)),
+            None => HoldDecision::Stop,
+        }
+    }
+
+    /// 出目を記入するカテゴリ（インデックス）を決める（全て埋まっていれば None）
+    fn category(&self, board: &ScoreBoard, dice: &[u8; 5]) -> Option<usize> {
+        let pattern = dp_table::dice_to_pattern(dice);
+        self.leaf(board).best_category(&pattern, 0).map(|(i, _)| i)
+    }
+
+    /// 期待値の計算に使ったダイス
+    pub fn dice(&self) -> &DiceModel {
+        &self.dice
+    }
+}
+
+impl Strategy<GameState> for CustomAI {
+    /// ルールと振るダイスが期待値を計算したものと同じゲームだけを遊ぶ
+    fn check_game(&self, game: &GameState) -> Result<(), GameError> {
+        match game.ruleset() {
+            Ruleset::Custom(rules) if **rules == *self.rules && *game.dice_model() == self.dice => {
+                Ok(())
+            }
+            _ => Err(GameError::CategoryUnavailable),
+        }
+    }
+
+    fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        self.holds(game.current_board(), &game.dice().values, game.rolls_left())
+    }
+
+    fn decide_cell(&self, game: &GameState) -> Option<Cell> {
+        let index = self.category(game.current_board(), &game.dice().values)?;
+        Some(Cell::new(0, index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dp_table::dice_patterns::get_patterns;
+    use crate::play::simulate;
+
+    #[test]
+    fn test_yacht_in_dsl() {
+        let rules = CustomRuleset::yacht();
+        for pp in get_patterns(5) {
+            for (cat, category) in rules.categories.iter().enumerate() {
+                assert_eq!(
+                    category.score_pattern(&pp.pattern),
+                    dp_table::calculate_score(&pp.pattern, cat),
+                    "{} {:?}",
+                    category.name,
+                    pp.pattern
+                );
+            }
+        }
+        let mut game = GameState::solo(Ruleset::Custom(Arc::new(rules)));
+        for (i, face) in (1..=6).enumerate() {
+            game.enter_roll([face, face, face, 1, 2]).unwrap();
+            game.select_cell(Cell::new(0, i)).unwrap();
+        }
+        assert_eq!(game.board(0).upper_bonus(), 35);
+        game.enter_roll([1; 5]).unwrap();
+        assert_eq!(
+            game.select_cell(Cell::new(0, 0)),
+            Err(GameError::CategoryUsed)
+        );
+        assert_eq!(
+            game.select_cell(Cell::new(0, 12)),
+            Err(GameError::CategoryUnavailable)
+        );
+        assert_eq!(game.ruleset().row_name(11), "Yacht");
+        let restored: GameState = serde_json::from_str(&game.to_json()).unwrap();
+        assert_eq!(restored.ruleset(), game.ruleset());
+        assert_eq!(restored.board(0).upper_bonus(), 35);
+
+        // 書き換えた保存データのルールも検証する（範囲外の目で得点を計算しない）
+        let json = game.to_json();
+        assert!(json.contains(r#"{"faces":[1]}"#));
+        for tampered in [r#"{"faces":[0]}"#, r#"{"faces":[9]}"#] {
+            let json = json.replace(r#"{"faces":[1]}"#, tampered);
+            assert!(
+                serde_json::from_str::<GameState>(&json).is_err(),
+                "{tampered}"
+            );
+        }
+        let json = json.replace(r#"{"straight":4}"#, r#"{"straight":0}"#);
+        assert!(serde_json::from_str::<GameState>(&json).is_err());
+    }
+
+    #[test]
+    fn test_large_scores_do_not_overflow() {
+        let rules = CustomRuleset::from_json(
+            r#"{"categories": [{"name": "Sixes x10", "score": {"faces": [6, 6, 6, 6, 6, 6, 6, 6, 6, 6]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(rules.score(&[6; 5], 0), Some(255));
+
+        let rules = CustomRuleset::new(
+            vec![CustomCategory::new("Jackpot", Condition::Always, Score::Fixed(250)).upper()],
+            Some(UpperBonus {
+                threshold: 10,
+                points: 100,
+            }),
+        )
+        .unwrap();
+        let ai = CustomAI::new(&rules).unwrap();
+        let mut board = ScoreBoard::with_ruleset(ai.ruleset());
+        assert!((ai.expected_score(&board) - 350.0).abs() < 1e-3);
+        board
+            .set_cell(Cell::new(0, 0), rules.score(&[1; 5], 0).unwrap())
+            .unwrap();
+        assert_eq!(board.total(), 350);
+    }
+
+    #[test]
+    fn test_house_rules_json() {
+        let rules = CustomRuleset::from_json(
+            r#"{"categories": [
+                {"name": "Two Pairs", "when": {"groups": [2, 2]}, "score": {"groups": [2, 2]}},
+                {"name": "Odds only", "when": {"only_faces": [1, 3, 5]}, "score": "sum"},
+                {"name": "Sum >= 25", "when": {"sum_at_least": 25}, "score": "sum"},
+                {"name": "Pair + 10", "when": {"kind": 2},
+                 "score": {"add": [{"groups": [2]}, {"fixed": 10}]}}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(rules.score(&[6, 6, 3, 3, 3], 0), Some(18));
+        assert_eq!(rules.score(&[6, 6, 6, 6, 3], 0), Some(0));
+        assert_eq!(rules.score(&[1, 3, 5, 5, 3], 1), Some(17));
+        assert_eq!(rules.score(&[1, 3, 5, 5, 2], 1), Some(0));
+        assert_eq!(rules.score(&[6, 6, 5, 5, 3], 2), Some(25));
+        assert_eq!(rules.score(&[6, 6, 5, 4, 3], 2), Some(0));
+        assert_eq!(rules.score(&[6, 6, 5, 5, 3], 3), Some(22));
+        assert_eq!(rules.score(&[6, 6, 5, 5, 3], 4), None);
+
+        assert_eq!(
+            CustomRuleset::from_json(r#"{"categories": []}"#),
+            Err(CustomRulesError::NoCategories)
+        );
+        assert_eq!(
+            CustomRuleset::from_json(r#"{"categories": [{"name": "x", "score": {"faces": [9]}}]}"#),
+            Err(CustomRulesError::InvalidFace(9))
+        );
+        assert!(matches!(
+            CustomRuleset::from_json(r#"{"categories": [{"name": "x"}]}"#),
+            Err(CustomRulesError::Json(_))
+        ));
+        let bonus = |threshold| {
+            Some(UpperBonus {
+                threshold,
+                points: 10,
+            })
+        };
+        let chance = CustomCategory::new("Chance", Condition::Always, Score::Sum);
+        assert_eq!(
+            CustomRuleset::new(vec![chance.clone().upper()], bonus(0)),
+            Err(CustomRulesError::ZeroBonusThreshold)
+        );
+        assert_eq!(
+            CustomRuleset::new(vec![chance], bonus(20)),
+            Err(CustomRulesError::BonusWithoutUpper)
+        );
+    }
+
+    #[test]
+    fn test_solver() {
+        assert!(matches!(
+            CustomAI::new(&CustomRuleset::yacht()),
+            Err(DpTableError::TooLarge(_))
+        ));
+
+        // 合計だけのカテゴリ1つ: 1個ずつ 4以上を残すのが最適で 5 × 14/3
+        let chance = CustomRuleset::new(
+            vec![CustomCategory::new("Chance", Condition::Always, Score::Sum)],
+            None,
+        )
+        .unwrap();
+        let ai = CustomAI::new(&chance).unwrap();
+        let mut game = GameState::solo(ai.ruleset());
+        assert!((ai.expected_score(game.board(0)) - 70.0 / 3.0).abs() < 1e-3);
+        game.enter_roll([6, 5, 4, 3, 1]).unwrap();
+        assert_eq!(
+            ai.decide_holds(&game),
+            HoldDecision::Reroll([true, true, false, false, false])
+        );
+
+        let rules = CustomRuleset::new(
+            vec![
+                CustomCategory::new("Sixes", Condition::Always, Score::Faces(vec![6])).upper(),
+                CustomCategory::new("Fives", Condition::Always, Score::Faces(vec![5])).upper(),
+                CustomCategory::new("Yacht", Condition::Kind(5), Score::Fixed(50)),
+            ],
+            Some(UpperBonus {
+                threshold: 30,
+                points: 20,
+            }),
+        )
+        .unwrap();
+        let ai = CustomAI::new(&rules).unwrap();
+        let decide = |dice| {
+            let mut game = GameState::solo(ai.ruleset());
+            game.enter_roll(dice).unwrap();
+            ai.decide_cell(&game).map(|cell| cell.row)
+        };
+        assert_eq!(decide([6, 6, 6, 6, 6]), Some(2));
+        assert_eq!(decide([6, 6, 6, 5, 1]), Some(0));
+        let expected = ai.expected_score(&ScoreBoard::with_ruleset(ai.ruleset()));
+        assert!(expected > 30.0 && expected < 100.0, "{expected}");
+
+        let mut game = GameState::solo(ai.ruleset());
+        game.set_seed(5);
+        assert_eq!(game.select_cell(Cell::new(0, 0)), Err(GameError::NotRolled));
+        for _ in 0..3 {
+            ai.play_turn(&mut game).unwrap();
+        }
+        assert!(game.is_game_over());
+        assert_eq!(game.roll(), Err(GameError::GameOver));
+        assert!(game.board(0).upper_total() > 0);
+        // ほかのルールのゲームは遊ばない
+        assert_eq!(
+            ai.play_turn(&mut GameState::new()).unwrap_err(),
+            GameError::CategoryUnavailable
+        );
+    }
+
+    #[test]
+    fn test_other_dice() {
+        let rules = CustomRuleset::from_json(
+            r#"{"categories": [
+                {"name": "Chance", "score": "sum"},
+                {"name": "Straight", "when": {"straight": 5}, "score": {"fixed": 40}}
+            ]}"#,
+        )
+        .unwrap();
+        // 8の目と 4-8 のストレートは d8 でだけ出る
+        assert_eq!(rules.score(&[4, 5, 6, 7, 8], 0), Some(30));
+        assert_eq!(rules.score(&[4, 5, 6, 7, 8], 1), Some(40));
+
+        let chance = CustomRuleset::new(
+            vec![CustomCategory::new("Chance", Condition::Always, Score::Sum)],
+            None,
+        )
+        .unwrap();
+        let d6 = CustomAI::new(&chance).unwrap();
+        let mut game = GameState::solo(d6.ruleset());
+        let board = game.board(0).clone();
+        let d8 = CustomAI::with_dice(&chance, DiceModel::uniform(8).unwrap()).unwrap();
+        let loaded_dice = DiceModel::weighted(&[1.0, 1.0, 1.0, 1.0, 1.0, 5.0]).unwrap();
+        let loaded = CustomAI::with_dice(&chance, loaded_dice.clone()).unwrap();
+        assert!((d6.expected_score(&board) - 70.0 / 3.0).abs() < 1e-3);
+        assert!((d8.expected_score(&board) - 30.3125).abs() < 1e-3);
+        assert!(loaded.expected_score(&board) > 28.0);
+        // 6が出やすいなら 5 も振り直す
+        game.enter_roll([6, 5, 4, 3, 1]).unwrap();
+        assert_eq!(
+            d6.decide_holds(&game),
+            HoldDecision::Reroll([true, true, false, false, false])
+        );
+        assert_eq!(
+            loaded.decide_holds(&game),
+            HoldDecision::Reroll([true, false, false, false, false])
+        );
+        assert_eq!(loaded.dice(), &loaded_dice);
+        // 振るダイスが違うゲームは遊ばない
+        assert_eq!(
+            loaded.check_game(&game),
+            Err(GameError::CategoryUnavailable)
+        );
+        game.set_dice_model(loaded_dice).unwrap();
+        assert_eq!(loaded.check_game(&game), Ok(()));
+        let restored: GameState = serde_json::from_str(&game.to_json()).unwrap();
+        assert_eq!(loaded.check_game(&restored), Ok(()));
+
+        let new_game = || {
+            let mut game = GameState::solo(d8.ruleset());
+            game.set_dice_model(DiceModel::uniform(8).unwrap()).unwrap();
+            game
+        };
+        let scores = simulate(&d8, new_game, 20, Some(3)).unwrap();
+        assert!(scores.iter().all(|&s| (5..=40).contains(&s)));
+        assert!(scores.iter().any(|&s| s > 30));
+    }
+}
@@ -0,0 +1,250 @@
+//! ダイスの種類と目の出る確率
+//!
+//! 標準は6面の一様なダイス（`DiceModel::standard`）。d4 や d8、目ごとに重みのある（偏った）
+//! ダイスも表せる（面の数は 2-8）。出目パターンの確率は多項分布
+//! （多項係数 × 各目の確率の個数乗）で計算し、ソルバーはこの確率で期待値を求める。
+//!
+//! ハウスルールのゲームは `GameState::set_dice_model` で設定したダイスを振り（`Turn` が持つ）、
+//! 得点も出目から計算するので d8 の 7・8 の目もそのまま合計やストレートに入る。AI
+//! （`custom::CustomAI`）はこのダイスの確率で期待値を実行時に計算する。ヨット・ヤッツィー・
+//! ヘネラル・トリプル・ヤンブは期待値の表や AI を d6 で事前計算しているので、標準以外のダイスは
+//! `GameError::UnsupportedDice` で受け付けない。検証可能なダイス（`fair`）も d6 だけ。
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::dp_table::{dice_patterns, PatternProbability, MAX_FACES};
+
+/// パターンを計算するダイスの個数の上限
+const MAX_DICE: usize = 6;
+
+/// ダイスの定義のエラー
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiceModelError {
+    /// 面の数が 2-8 ではない
+    FaceCount(usize),
+    /// 重みが負・有限でない、または全て 0
+    InvalidWeight(f64),
+}
+
+impl fmt::Display for DiceModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiceModelError::FaceCount(n) => {
+                write!(f, "dice must have 2-{MAX_FACES} faces (got {n})")
+            }
+            DiceModelError::InvalidWeight(w) => write!(f, "invalid face weight ({w})"),
+        }
+    }
+}
+
+impl std::error::Error for DiceModelError {}
+
+/// ダイス（面の数と各目の出る確率。JSON では各目の確率の配列）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "Vec<f64>", try_from = "Vec<f64>")]
+pub struct DiceModel {
+    /// 各目（1, 2, ...）の確率（合計 1）
+    probabilities: Vec<f64>,
+    /// 一様な d6（従来のパターンの表をそのまま使う）
+    standard: bool,
+    /// [ダイスの個数] → 出目パターンと確率
+    patterns: [OnceLock<Vec<PatternProbability>>; MAX_DICE + 1],
+}
+
+impl PartialEq for DiceModel {
+    fn eq(&self, other: &DiceModel) -> bool {
+        self.probabilities == other.probabilities
+    }
+}
+
+impl From<DiceModel> for Vec<f64> {
+    fn from(model: DiceModel) -> Vec<f64> {
+        model.probabilities
+    }
+}
+
+impl TryFrom<Vec<f64>> for DiceModel {
+    type Error = DiceModelError;
+
+    fn try_from(weights: Vec<f64>) -> Result<DiceModel, DiceModelError> {
+        DiceModel::weighted(&weights)
+    }
+}
+
+impl Default for DiceModel {
+    fn default() -> DiceModel {
+        DiceModel::standard().clone()
+    }
+}
+
+impl DiceModel {
+    fn from_probabilities(probabilities: Vec<f64>) -> DiceModel {
+        let standard = probabilities.len() == 6 && probabilities.iter().all(|&p| p == 1.0 / 6.0);
+        DiceModel {
+            probabilities,
+            standard,
+            patterns: Default::default(),
+        }
+    }
+
+    /// 6面の一様なダイス
+    pub fn standard() -> &'static DiceModel {
+        static STANDARD: OnceLock<DiceModel> = OnceLock::new();
+        STANDARD.get_or_init(|| DiceModel::from_probabilities(vec![1.0 / 6.0; 6]))
+    }
+
+    /// faces 面の一様なダイス（d4, d8 など）
+    pub fn uniform(faces: usize) -> Result<DiceModel, DiceModelError> {
+        if !(2..=MAX_FACES).contains(&faces) {
+            return Err(DiceModelError::FaceCount(faces));
+        }
+        Ok(DiceModel::from_probabilities(vec![
+            1.0 / faces as f64;
+            faces
+        ]))
+    }
+
+    /// 目ごとの重み（weights[0] が 1 の目）のダイス。重みは合計 1 に正規化する
+    pub fn weighted(weights: &[f64]) -> Result<DiceModel, DiceModelError> {
+        if !(2..=MAX_FACES).contains(&weights.len()) {
+            return Err(DiceModelError::FaceCount(weights.len()));
+        }
+        if let Some(&w) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+            return Err(DiceModelError::InvalidWeight(w));
+        }
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return Err(DiceModelError::InvalidWeight(total));
+        }
+        // 重みが全て同じなら一様なダイス（d6 なら標準のパターンの表を使う）
+        if weights.iter().all(|&w| w == weights[0]) {
+            return DiceModel::uniform(weights.len());
+        }
+        Ok(DiceModel::from_probabilities(
+            weights.iter().map(|w| w / total).collect(),
+        ))
+    }
+
+    /// 6面の一様なダイスか
+    pub fn is_standard(&self) -> bool {
+        self.standard
+    }
+
+    /// 面の数
+    pub fn faces(&self) -> usize {
+        self.probabilities.len()
+    }
+
+    /// face（1 始まり）の出る確率（範囲外は 0）
+    pub fn probability(&self, face: u8) -> f64 {
+        (face as usize)
+            .checked_sub(1)
+            .and_then(|i| self.probabilities.get(i))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// ダイス1個の目の期待値
+    pub fn expected_face(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .zip(1..)
+            .map(|(p, face)| p * face as f64)
+            .sum()
+    }
+
+    /// ダイスを1個振る
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> u8 {
+        if self.standard {
+            return rng.gen_range(1..=6);
+        }
+        let mut x: f64 = rng.gen();
+        for (i, &p) in self.probabilities.iter().enumerate() {
+            if x < p {
+                return i as u8 + 1;
+            }
+            x -= p;
+        }
+        // 丸め誤差で残った分は確率が正の最後の目
+        self.probabilities
+            .iter()
+            .rposition(|&p| p > 0.0)
+            .unwrap_or(0) as u8
+            + 1
+    }
+
+    /// num_dice 個振った時の出目パターンと確率
+    pub(crate) fn patterns(&self, num_dice: usize) -> &[PatternProbability] {
+        if self.standard || num_dice == 0 || num_dice > MAX_DICE {
+            return dice_patterns::get_patterns(num_dice);
+        }
+        self.patterns[num_dice].get_or_init(|| {
+            dice_patterns::generate_patterns(num_dice as u8, Some(&self.probabilities))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_pattern_probabilities() {
+        let d4 = DiceModel::uniform(4).unwrap();
+        // 重複組合せ: C(4+5-1, 5) = 56, C(8+5-1, 5) = 792
+        assert_eq!(d4.patterns(5).len(), 56);
+        let d8 = DiceModel::uniform(8).unwrap();
+        assert_eq!(d8.patterns(5).len(), 792);
+        assert!(d8.patterns(5).iter().any(|pp| pp.pattern[7] == 5));
+
+        let loaded = DiceModel::weighted(&[1.0, 1.0, 1.0, 1.0, 1.0, 5.0]).unwrap();
+        assert_eq!(loaded.probability(6), 0.5);
+        assert_eq!(loaded.probability(7), 0.0);
+        for model in [&d4, &d8, &loaded, DiceModel::standard()] {
+            for n in 1..=5 {
+                let total: f32 = model.patterns(n).iter().map(|pp| pp.probability).sum();
+                assert!((total - 1.0).abs() < 1e-4, "{} {}", model.faces(), total);
+            }
+        }
+        // 6が5個: 0.5^5
+        let sixes = loaded
+            .patterns(5)
+            .iter()
+            .find(|pp| pp.pattern[5] == 5)
+            .unwrap();
+        assert!((sixes.probability - 0.03125).abs() < 1e-6);
+        assert_eq!(
+            DiceModel::uniform(6).unwrap().patterns(5),
+            dice_patterns::get_patterns(5)
+        );
+
+        assert_eq!(DiceModel::uniform(9), Err(DiceModelError::FaceCount(9)));
+        assert_eq!(
+            DiceModel::weighted(&[1.0, -1.0]),
+            Err(DiceModelError::InvalidWeight(-1.0))
+        );
+        assert_eq!(
+            DiceModel::weighted(&[0.0, 0.0]),
+            Err(DiceModelError::InvalidWeight(0.0))
+        );
+    }
+
+    #[test]
+    fn test_roll_follows_weights() {
+        let model = DiceModel::weighted(&[0.0, 1.0, 0.0, 3.0]).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = [0u32; 5];
+        for _ in 0..4000 {
+            counts[model.roll(&mut rng) as usize] += 1;
+        }
+        assert_eq!(counts[0] + counts[1] + counts[3], 0);
+        assert!(counts[4] > 2700 && counts[4] < 3300, "{:?}", counts);
+        assert_eq!(model.expected_face(), 3.5);
+    }
+}
@@ -1,12 +1,34 @@
 //! DPテーブルと期待値計算モジュール
 //!
 //! 前計算されたDPテーブルを使って、最適な意思決定を行う。
+//! テーブルは得点ルール（`Rules`）ごとに1つあり、ヨット（`YACHT`）と北欧式ヤッツィー（`YATZY`）を持つ。
 
-/// DPテーブルデータ（f32 little-endian）
+use std::fmt;
+use std::sync::OnceLock;
+
+/// DPテーブルデータ（YDP1 コンパクト形式、tools/convert_dp_table.py で生成）
 /// 構造: dp_table[upper_sum][used_hands]
 /// - upper_sum: 0-63 (64通り)
 /// - used_hands: 0-4095 (4096通り、12ビットマスク)
-static DP_TABLE_DATA: &[u8] = include_bytes!("dp_table.bin");
+///
+/// 到達不能な (upper_sum, used_hands) は省略し、値は 1/1024 単位に量子化して
+/// upper_sum 方向の差分を可変長整数で格納している。
+#[cfg(feature = "embed-dp-table")]
+static DP_TABLE_DATA: &[u8] = include_bytes!("dp_table.ydp");
+
+/// ヤッツィーのDPテーブルデータ（YDP1 形式、used_hands は 15ビット）
+#[cfg(feature = "embed-yatzy-table")]
+static YATZY_TABLE_DATA: &[u8] = include_bytes!("yatzy_dp_table.ydp");
+
+#[cfg(feature = "embed-dp-table")]
+const YACHT_EMBEDDED: Option<&[u8]> = Some(DP_TABLE_DATA);
+#[cfg(not(feature = "embed-dp-table"))]
+const YACHT_EMBEDDED: Option<&[u8]> = None;
+
+#[cfg(feature = "embed-yatzy-table")]
+const YATZY_EMBEDDED: Option<&[u8]> = Some(YATZY_TABLE_DATA);
+#[cfg(not(feature = "embed-yatzy-table"))]
+const YATZY_EMBEDDED: Option<&[u8]> = None;
 
 // =============================================================================
 // 定数
@@ -17,7 +39,9 @@ pub const UPPER_BONUS_THRESHOLD: usize = 63;
 pub const UPPER_BONUS_POINTS: u8 = 35;
 
 const UPPER_SUM_MAX: usize = 64;
-const USED_HANDS_MAX: usize = 4096;
+
+const TABLE_MAGIC: &[u8; 4] = b"YDP1";
+const TABLE_HEADER_LEN: usize = 20;
 
 // =============================================================================
 // カテゴリID
@@ -43,16 +67,186 @@ pub mod category {
     }
 }
 
-/// DPテーブルから期待得点を取得
+/// 北欧式ヤッツィーのカテゴリID（上段はヨットと共通）
+pub mod yatzy_category {
+    pub const ONE_PAIR: usize = 6;
+    pub const TWO_PAIRS: usize = 7;
+    pub const THREE_OF_A_KIND: usize = 8;
+    pub const FOUR_OF_A_KIND: usize = 9;
+    pub const SMALL_STRAIGHT: usize = 10;
+    pub const LARGE_STRAIGHT: usize = 11;
+    pub const FULL_HOUSE: usize = 12;
+    pub const CHANCE: usize = 13;
+    pub const YATZY: usize = 14;
+
+    pub const NUM_CATEGORIES: usize = 15;
+    pub const UPPER_BONUS_POINTS: u8 = 50;
+}
+
+/// マキシヤッツィー（6個のダイス）のカテゴリID（上段はヨットと共通）
+pub mod maxi_category {
+    pub const ONE_PAIR: usize = 6;
+    pub const TWO_PAIRS: usize = 7;
+    pub const THREE_PAIRS: usize = 8;
+    pub const THREE_OF_A_KIND: usize = 9;
+    pub const FOUR_OF_A_KIND: usize = 10;
+    pub const FIVE_OF_A_KIND: usize = 11;
+    pub const SMALL_STRAIGHT: usize = 12;
+    pub const LARGE_STRAIGHT: usize = 13;
+    pub const FULL_STRAIGHT: usize = 14;
+    pub const FULL_HOUSE: usize = 15;
+    pub const CASTLE: usize = 16;
+    pub const TOWER: usize = 17;
+    pub const CHANCE: usize = 18;
+    pub const MAXI_YATZY: usize = 19;
+
+    pub const NUM_CATEGORIES: usize = 20;
+    pub const NUM_DICE: usize = 6;
+    pub const UPPER_BONUS_THRESHOLD: usize = 84;
+    pub const UPPER_BONUS_POINTS: u8 = 50;
+}
+
+// =============================================================================
+// 得点ルール
+// =============================================================================
+
+/// 得点ルールと、それに対応するDPテーブル
+///
+/// 上段（カテゴリ 0-5 = 1-6の目）と 63点でのボーナスは共通で、
+/// 下段のカテゴリ数と得点計算、ボーナス点がルールごとに異なる。
+pub struct Rules {
+    pub name: &'static str,
+    pub num_categories: usize,
+    pub upper_bonus_points: u8,
+    /// 出目パターンとカテゴリIDから得点を計算する
+    pub score: fn(&DicePattern, usize) -> u8,
+    embedded: Option<&'static [u8]>,
+    table: OnceLock<Vec<f32>>,
+}
+
+/// ヨット（12カテゴリ、ボーナス35点）
+pub static YACHT: Rules = Rules {
+    name: "yacht",
+    num_categories: NUM_CATEGORIES,
+    upper_bonus_points: UPPER_BONUS_POINTS,
+    score: calculate_score,
+    embedded: YACHT_EMBEDDED,
+    table: OnceLock::new(),
+};
+
+/// 北欧式ヤッツィー（15カテゴリ、ボーナス50点）
+pub static YATZY: Rules = Rules {
+    name: "yatzy",
+    num_categories: yatzy_category::NUM_CATEGORIES,
+    upper_bonus_points: yatzy_category::UPPER_BONUS_POINTS,
+    score: calculate_yatzy_score,
+    embedded: YATZY_EMBEDDED,
+    table: OnceLock::new(),
+};
+
+// =============================================================================
+// テーブルの読み込み
+// =============================================================================
+
+/// DPテーブルの読み込み・計算のエラー
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DpTableError {
+    /// YDP1 形式ではない
+    BadMagic,
+    /// データが途中で切れている、または余分なデータがある
+    Truncated,
+    /// ペイロードが壊れている
+    PayloadChecksum,
+    /// チェックサムは合うが値が読めない（倍率が 0、可変長整数が長すぎる、累積が溢れる）
+    Corrupt,
+    /// エンジンと異なる得点ルールで計算されたテーブル
+    RulesMismatch { expected: u32, found: u32 },
+    /// すでに読み込み済み
+    AlreadyLoaded,
+    /// まだ読み込んでいない（埋め込んでいないテーブルは `load_table` で読み込む）
+    NotLoaded,
+    /// 実行時に計算するには状態数が多すぎる（`custom::MAX_SOLVER_STATES` 参照）
+    TooLarge(usize),
+}
+
+impl fmt::Display for DpTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DpTableError::BadMagic => write!(f, "not a YDP1 dp table"),
+            DpTableError::Truncated => write!(f, "dp table data has unexpected length"),
+            DpTableError::PayloadChecksum => write!(f, "dp table payload checksum mismatch"),
+            DpTableError::Corrupt => write!(f, "dp table payload is corrupt"),
+            DpTableError::RulesMismatch { expected, found } => write!(
+                f,
+                "dp table was computed for different rules (expected {expected:#010x}, found {found:#010x})"
+            ),
+            DpTableError::AlreadyLoaded => write!(f, "dp table is already loaded"),
+            DpTableError::NotLoaded => write!(f, "dp table is not loaded"),
+            DpTableError::TooLarge(states) => write!(f, "too many states to compute a dp table ({states})"),
+        }
+    }
+}
+
+impl std::error::Error for DpTableError {}
+
+/// FNV-1a (32bit)
+fn fnv1a(data: &[u8], mut hash: u32) -> u32 {
+    for &b in data {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+const FNV_OFFSET: u32 = 0x811C_9DC5;
+
+/// ヨットの得点ルールのチェックサム
+pub fn rules_checksum() -> u32 {
+    YACHT.rules_checksum()
+}
+
+/// 使用済みの上段カテゴリ（6ビット）から到達可能な上段累計（63上限、昇順）
+fn reachable_upper_sums(upper_mask: usize) -> Vec<usize> {
+    let mut reachable = [false; UPPER_SUM_MAX];
+    reachable[0] = true;
+    for face in 0..6 {
+        if (upper_mask >> face) & 1 == 0 {
+            continue;
+        }
+        let mut next = [false; UPPER_SUM_MAX];
+        for sum in (0..UPPER_SUM_MAX).filter(|&s| reachable[s]) {
+            for k in 0..=5 {
+                next[(sum + k * (face + 1)).min(UPPER_BONUS_THRESHOLD)] = true;
+            }
+        }
+        reachable = next;
+    }
+    (0..UPPER_SUM_MAX).filter(|&s| reachable[s]).collect()
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// 実行時にヨットのDPテーブルを読み込む（embed-dp-table 無効時に使用）
+pub fn load_table(data: &[u8]) -> Result<(), DpTableError> {
+    YACHT.load_table(data)
+}
+
+/// ヨットのDPテーブルが利用可能か
+pub fn is_table_loaded() -> bool {
+    YACHT.is_table_loaded()
+}
+
+/// ヨットのDPテーブルから期待得点を取得
 #[inline]
 pub fn get_expected_score(upper_sum: usize, used_hands: usize) -> f32 {
-    debug_assert!(upper_sum < UPPER_SUM_MAX);
-    debug_assert!(used_hands < USED_HANDS_MAX);
-
-    let idx = upper_sum * USED_HANDS_MAX + used_hands;
-    let offset = idx * 4;
-    let bytes = &DP_TABLE_DATA[offset..offset + 4];
-    f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    YACHT.expected_score(upper_sum, used_hands)
 }
 
 /// 初期期待得点を取得
@@ -60,7 +254,169 @@ pub fn get_initial_expected_score() -> f32 {
     get_expected_score(0, 0)
 }
 
-/// カテゴリ選択後の価値を計算
+impl Rules {
+    /// used_hands の取りうる数（2^カテゴリ数）
+    pub fn used_hands_max(&self) -> usize {
+        1 << self.num_categories
+    }
+
+    #[inline]
+    pub fn is_upper(&self, cat: usize) -> bool {
+        category::is_upper(cat)
+    }
+
+    /// 得点ルールのチェックサム
+    /// 定数と、5個振りの全パターン × 全カテゴリの得点から計算する
+    pub fn rules_checksum(&self) -> u32 {
+        let mut hash = FNV_OFFSET;
+        for v in [
+            self.num_categories as u32,
+            UPPER_BONUS_THRESHOLD as u32,
+            self.upper_bonus_points as u32,
+        ] {
+            hash = fnv1a(&v.to_le_bytes(), hash);
+        }
+        for pp in dice_patterns::get_patterns(5) {
+            let scores: Vec<u8> = (0..self.num_categories)
+                .map(|cat| (self.score)(&pp.pattern, cat))
+                .collect();
+            hash = fnv1a(&scores, hash);
+        }
+        hash
+    }
+
+    /// YDP1 形式を展開する（到達不能なエントリは 0）
+    pub fn decode_table(&self, data: &[u8]) -> Result<Vec<f32>, DpTableError> {
+        if data.len() < TABLE_HEADER_LEN {
+            return Err(DpTableError::Truncated);
+        }
+        if &data[0..4] != TABLE_MAGIC {
+            return Err(DpTableError::BadMagic);
+        }
+        let rules = read_u32(data, 4);
+        let scale = read_u32(data, 8) as f32;
+        let entry_count = read_u32(data, 12) as usize;
+        let payload_checksum = read_u32(data, 16);
+        let payload = &data[TABLE_HEADER_LEN..];
+
+        let expected_rules = self.rules_checksum();
+        if rules != expected_rules {
+            return Err(DpTableError::RulesMismatch {
+                expected: expected_rules,
+                found: rules,
+            });
+        }
+        if fnv1a(payload, FNV_OFFSET) != payload_checksum {
+            return Err(DpTableError::PayloadChecksum);
+        }
+        // チェックサムは誰でも付け直せるので、値そのものも確かめる
+        if scale == 0.0 {
+            return Err(DpTableError::Corrupt);
+        }
+
+        let used_hands_max = self.used_hands_max();
+        let reachable: Vec<Vec<usize>> = (0..64).map(reachable_upper_sums).collect();
+        let mut table = vec![0.0f32; UPPER_SUM_MAX * used_hands_max];
+        let mut pos = 0;
+        let mut decoded = 0;
+
+        for used_hands in 0..used_hands_max {
+            let mut prev: i64 = 0;
+            for &upper_sum in &reachable[used_hands & 0x3F] {
+                // zigzag + LEB128
+                let mut z: u64 = 0;
+                let mut shift = 0;
+                loop {
+                    let b = *payload.get(pos).ok_or(DpTableError::Truncated)?;
+                    pos += 1;
+                    z |= ((b & 0x7F) as u64) << shift;
+                    if b & 0x80 == 0 {
+                        break;
+                    }
+                    shift += 7;
+                    if shift >= 63 {
+                        return Err(DpTableError::Corrupt);
+                    }
+                }
+                let delta = (z >> 1) as i64 ^ -((z & 1) as i64);
+                prev = prev.checked_add(delta).ok_or(DpTableError::Corrupt)?;
+                table[upper_sum * used_hands_max + used_hands] = prev as f32 / scale;
+                decoded += 1;
+            }
+        }
+
+        if pos != payload.len() || decoded != entry_count {
+            return Err(DpTableError::Truncated);
+        }
+        Ok(table)
+    }
+
+    /// 実行時にDPテーブルを読み込む（埋め込みを無効にしたときに使用）
+    pub fn load_table(&self, data: &[u8]) -> Result<(), DpTableError> {
+        let table = self.decode_table(data)?;
+        self.table
+            .set(table)
+            .map_err(|_| DpTableError::AlreadyLoaded)
+    }
+
+    /// DPテーブルが利用可能か
+    pub fn is_table_loaded(&self) -> bool {
+        self.table().is_some()
+    }
+
+    /// DPテーブルが利用可能か確かめる（読み込み前なら `NotLoaded`）
+    pub fn check_loaded(&self) -> Result<(), DpTableError> {
+        self.table().map(|_| ()).ok_or(DpTableError::NotLoaded)
+    }
+
+    fn table(&self) -> Option<&Vec<f32>> {
+        if let Some(table) = self.table.get() {
+            return Some(table);
+        }
+        let data = self.embedded?;
+        Some(self.table.get_or_init(|| {
+            self.decode_table(data)
+                .expect("embedded dp table must match the engine rules")
+        }))
+    }
+
+    /// DPテーブルから期待得点を取得（読み込み前に呼ばないこと。`check_loaded` 参照）
+    #[inline]
+    pub fn expected_score(&self, upper_sum: usize, used_hands: usize) -> f32 {
+        debug_assert!(upper_sum < UPPER_SUM_MAX);
+        debug_assert!(used_hands < self.used_hands_max());
+
+        let table = self
+            .table()
+            .unwrap_or_else(|| panic!("{} dp table is not loaded", self.name));
+        table[upper_sum * self.used_hands_max() + used_hands]
+    }
+
+    /// カテゴリ選択後の価値（即時スコア + ボーナス + 将来の期待値）
+    pub fn evaluate_category_choice(
+        &self,
+        current_upper_sum: usize,
+        current_used_hands: usize,
+        cat: usize,
+        immediate_score: u8,
+    ) -> f32 {
+        let new_used_hands = current_used_hands | (1 << cat);
+
+        if self.is_upper(cat) {
+            let new_upper_sum = (current_upper_sum + immediate_score as usize).min(UPPER_BONUS_THRESHOLD);
+            let bonus = if current_upper_sum < UPPER_BONUS_THRESHOLD && new_upper_sum >= UPPER_BONUS_THRESHOLD {
+                self.upper_bonus_points as f32
+            } else {
+                0.0
+            };
+            immediate_score as f32 + bonus + self.expected_score(new_upper_sum, new_used_hands)
+        } else {
+            immediate_score as f32 + self.expected_score(current_upper_sum, new_used_hands)
+        }
+    }
+}
+
+/// カテゴリ選択後の価値を計算（ヨット）
 ///
 /// 戻り値: 即時スコア + ボーナス + 将来の期待値
 pub fn evaluate_category_choice(
@@ -69,30 +425,21 @@ pub fn evaluate_category_choice(
     cat: usize,
     immediate_score: u8,
 ) -> f32 {
-    let new_used_hands = current_used_hands | (1 << cat);
-
-    if category::is_upper(cat) {
-        let new_upper_sum = (current_upper_sum + immediate_score as usize).min(UPPER_BONUS_THRESHOLD);
-        let bonus = if current_upper_sum < UPPER_BONUS_THRESHOLD && new_upper_sum >= UPPER_BONUS_THRESHOLD {
-            UPPER_BONUS_POINTS as f32
-        } else {
-            0.0
-        };
-        immediate_score as f32 + bonus + get_expected_score(new_upper_sum, new_used_hands)
-    } else {
-        immediate_score as f32 + get_expected_score(current_upper_sum, new_used_hands)
-    }
+    YACHT.evaluate_category_choice(current_upper_sum, current_used_hands, cat, immediate_score)
 }
 
 // =============================================================================
 // 出目パターンと確率テーブル
 // =============================================================================
 
-/// 出目パターン: 各目の個数 [1の個数, 2の個数, ..., 6の個数]
-pub type DicePattern = [u8; 6];
+/// ダイスの面の数の上限（d8 まで）
+pub const MAX_FACES: usize = 8;
+
+/// 出目パターン: 各目の個数 [1の個数, 2の個数, ..., 8の個数]（6面ダイスでは 7・8 は常に 0）
+pub type DicePattern = [u8; MAX_FACES];
 
 /// 出目パターンと確率のペア
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct PatternProbability {
     pub pattern: DicePattern,
     pub probability: f32,
@@ -105,70 +452,81 @@ pub struct PatternProbability {
 /// - PATTERNS_3: 3個振る → 56通り
 /// - PATTERNS_4: 4個振る → 126通り
 /// - PATTERNS_5: 5個振る → 252通り
+/// - PATTERNS_6: 6個振る → 462通り（マキシヤッツィー）
 pub mod dice_patterns {
     use super::*;
 
-    /// 階乗テーブル（0! ~ 5!）
-    const FACTORIAL: [u32; 6] = [1, 1, 2, 6, 24, 120];
+    /// 階乗テーブル（0! ~ 6!）
+    const FACTORIAL: [u32; 7] = [1, 1, 2, 6, 24, 120, 720];
 
-    /// 多項係数を計算: n! / (k1! * k2! * ... * k6!)
-    fn multinomial(counts: [u8; 6]) -> u32 {
-        let n = (counts[0] + counts[1] + counts[2] + counts[3] + counts[4] + counts[5]) as usize;
-        if n > 5 {
+    /// 多項係数を計算: n! / (k1! * k2! * ... * k8!)
+    pub(crate) fn multinomial(counts: &DicePattern) -> u32 {
+        let n = pattern_count(counts) as usize;
+        if n > 6 {
             return 0;
         }
-        let numerator = FACTORIAL[n];
-        let denominator = FACTORIAL[counts[0] as usize]
-            * FACTORIAL[counts[1] as usize]
-            * FACTORIAL[counts[2] as usize]
-            * FACTORIAL[counts[3] as usize]
-            * FACTORIAL[counts[4] as usize]
-            * FACTORIAL[counts[5] as usize];
-        numerator / denominator
+        let denominator: u32 = counts.iter().map(|&k| FACTORIAL[k as usize]).product();
+        FACTORIAL[n] / denominator
     }
 
-    /// パターンを生成するマクロ代わりの関数
-    fn generate_patterns(num_dice: u8) -> Vec<PatternProbability> {
-        let total_outcomes = 6_u32.pow(num_dice as u32);
+    /// num_dice 個振った時のパターンと確率を生成する
+    ///
+    /// probabilities は各目の出る確率（長さ = 面の数）。None なら6面の一様なダイス。
+    pub(crate) fn generate_patterns(
+        num_dice: u8,
+        probabilities: Option<&[f64]>,
+    ) -> Vec<PatternProbability> {
+        let faces = probabilities.map_or(6, |p| p.len());
         let mut patterns = Vec::new();
 
         // 全パターンを列挙
         fn recurse(
             dice_left: u8,
             face: usize,
+            faces: usize,
             current: &mut DicePattern,
-            patterns: &mut Vec<PatternProbability>,
-            total_outcomes: u32,
+            out: &mut Vec<DicePattern>,
         ) {
-            if face == 6 {
+            if face == faces {
                 if dice_left == 0 {
-                    let count = multinomial(*current);
-                    let prob = count as f32 / total_outcomes as f32;
-                    patterns.push(PatternProbability {
-                        pattern: *current,
-                        probability: prob,
-                    });
+                    out.push(*current);
                 }
                 return;
             }
             for k in 0..=dice_left {
                 current[face] = k;
-                recurse(dice_left - k, face + 1, current, patterns, total_outcomes);
+                recurse(dice_left - k, face + 1, faces, current, out);
             }
             current[face] = 0;
         }
 
-        let mut current = [0u8; 6];
-        recurse(num_dice, 0, &mut current, &mut patterns, total_outcomes);
+        let mut all = Vec::new();
+        recurse(num_dice, 0, faces, &mut [0u8; MAX_FACES], &mut all);
+        for pattern in all {
+            let count = multinomial(&pattern);
+            let probability = match probabilities {
+                // 一様な d6 は 6^n で割る（丸めを従来のテーブルと揃える）
+                None => count as f32 / 6_u32.pow(num_dice as u32) as f32,
+                Some(p) => {
+                    let weight: f64 = (0..faces).map(|f| p[f].powi(pattern[f] as i32)).product();
+                    (count as f64 * weight) as f32
+                }
+            };
+            patterns.push(PatternProbability {
+                pattern,
+                probability,
+            });
+        }
         patterns
     }
 
     lazy_static::lazy_static! {
-        pub static ref PATTERNS_1: Vec<PatternProbability> = generate_patterns(1);
-        pub static ref PATTERNS_2: Vec<PatternProbability> = generate_patterns(2);
-        pub static ref PATTERNS_3: Vec<PatternProbability> = generate_patterns(3);
-        pub static ref PATTERNS_4: Vec<PatternProbability> = generate_patterns(4);
-        pub static ref PATTERNS_5: Vec<PatternProbability> = generate_patterns(5);
+        pub static ref PATTERNS_1: Vec<PatternProbability> = generate_patterns(1, None);
+        pub static ref PATTERNS_2: Vec<PatternProbability> = generate_patterns(2, None);
+        pub static ref PATTERNS_3: Vec<PatternProbability> = generate_patterns(3, None);
+        pub static ref PATTERNS_4: Vec<PatternProbability> = generate_patterns(4, None);
+        pub static ref PATTERNS_5: Vec<PatternProbability> = generate_patterns(5, None);
+        pub static ref PATTERNS_6: Vec<PatternProbability> = generate_patterns(6, None);
     }
 
     pub fn get_patterns(num_dice: usize) -> &'static [PatternProbability] {
@@ -179,6 +537,7 @@ pub mod dice_patterns {
             3 => &PATTERNS_3,
             4 => &PATTERNS_4,
             5 => &PATTERNS_5,
+            6 => &PATTERNS_6,
             _ => &[],
         }
     }
@@ -198,7 +557,7 @@ pub fn enumerate_keep_patterns(current_dice: &DicePattern) -> Vec<DicePattern> {
         keep: &mut DicePattern,
         patterns: &mut Vec<DicePattern>,
     ) {
-        if face == 6 {
+        if face == MAX_FACES {
             patterns.push(*keep);
             return;
         }
@@ -209,33 +568,26 @@ pub fn enumerate_keep_patterns(current_dice: &DicePattern) -> Vec<DicePattern> {
         keep[face] = 0;
     }
 
-    let mut keep = [0u8; 6];
+    let mut keep = [0u8; MAX_FACES];
     recurse(0, current_dice, &mut keep, &mut patterns);
     patterns
 }
 
 /// 出目パターンを合成
 pub fn add_patterns(a: &DicePattern, b: &DicePattern) -> DicePattern {
-    [
-        a[0] + b[0],
-        a[1] + b[1],
-        a[2] + b[2],
-        a[3] + b[3],
-        a[4] + b[4],
-        a[5] + b[5],
-    ]
+    std::array::from_fn(|i| a[i] + b[i])
 }
 
 /// 出目パターンの個数合計
 pub fn pattern_count(p: &DicePattern) -> u8 {
-    p[0] + p[1] + p[2] + p[3] + p[4] + p[5]
+    p.iter().sum()
 }
 
 /// 出目配列を出目パターンに変換
 pub fn dice_to_pattern(dice: &[u8]) -> DicePattern {
-    let mut pattern = [0u8; 6];
+    let mut pattern = [0u8; MAX_FACES];
     for &d in dice {
-        if d >= 1 && d <= 6 {
+        if (1..=MAX_FACES as u8).contains(&d) {
             pattern[(d - 1) as usize] += 1;
         }
     }
@@ -244,7 +596,7 @@ pub fn dice_to_pattern(dice: &[u8]) -> DicePattern {
 
 /// 出目パターンの合計値（ピップ数）
 pub fn pattern_pips(p: &DicePattern) -> u8 {
-    p[0] * 1 + p[1] * 2 + p[2] * 3 + p[3] * 4 + p[4] * 5 + p[5] * 6
+    p.iter().zip(1..).map(|(&count, face)| count * face).sum()
 }
 
 // =============================================================================
@@ -254,45 +606,105 @@ pub fn pattern_pips(p: &DicePattern) -> u8 {
 /// 4連続があるかチェック（スモールストレート用）
 #[inline]
 fn has_small_straight(p: &DicePattern) -> bool {
-    (p[0] >= 1 && p[1] >= 1 && p[2] >= 1 && p[3] >= 1) ||  // 1-2-3-4
-    (p[1] >= 1 && p[2] >= 1 && p[3] >= 1 && p[4] >= 1) ||  // 2-3-4-5
-    (p[2] >= 1 && p[3] >= 1 && p[4] >= 1 && p[5] >= 1)     // 3-4-5-6
+    // 1-2-3-4, 2-3-4-5, 3-4-5-6
+    p.windows(4).any(|w| w.iter().all(|&c| c >= 1))
 }
 
 /// 5連続があるかチェック（ビッグストレート用）
 #[inline]
-fn has_big_straight(p: &DicePattern) -> bool {
-    (p[0] >= 1 && p[1] >= 1 && p[2] >= 1 && p[3] >= 1 && p[4] >= 1) ||  // 1-2-3-4-5
-    (p[1] >= 1 && p[2] >= 1 && p[3] >= 1 && p[4] >= 1 && p[5] >= 1)     // 2-3-4-5-6
+pub(crate) fn has_big_straight(p: &DicePattern) -> bool {
+    // 1-2-3-4-5, 2-3-4-5-6（8面ダイスでは 3-7, 4-8 も）
+    p.windows(5).any(|w| w.iter().all(|&c| c >= 1))
 }
 
 /// 出目パターンから得点を計算
 pub fn calculate_score(p: &DicePattern, cat: usize) -> u8 {
     match cat {
-        category::ONES => p[0] * 1,
+        category::ONES => p[0],
         category::TWOS => p[1] * 2,
         category::THREES => p[2] * 3,
         category::FOURS => p[3] * 4,
         category::FIVES => p[4] * 5,
         category::SIXES => p[5] * 6,
         category::FULL_HOUSE => {
-            let has_three = p.iter().any(|&c| c == 3);
-            let has_two = p.iter().any(|&c| c == 2);
+            let has_three = p.contains(&3);
+            let has_two = p.contains(&2);
             if has_three && has_two { pattern_pips(p) } else { 0 }
         }
-        category::FOUR_OF_A_KIND => {
-            if p.iter().any(|&c| c >= 4) { pattern_pips(p) } else { 0 }
-        }
-        category::LITTLE_STRAIGHT => {
-            if has_small_straight(p) { 15 } else { 0 }
-        }
-        category::BIG_STRAIGHT => {
-            if has_big_straight(p) { 30 } else { 0 }
-        }
+        category::FOUR_OF_A_KIND if p.iter().any(|&c| c >= 4) => pattern_pips(p),
+        category::LITTLE_STRAIGHT if has_small_straight(p) => 15,
+        category::BIG_STRAIGHT if has_big_straight(p) => 30,
         category::CHOICE => pattern_pips(p),
-        category::YACHT => {
-            if p.iter().any(|&c| c == 5) { 50 } else { 0 }
-        }
+        category::YACHT if p.contains(&5) => 50,
+        _ => 0,
+    }
+}
+
+/// n個以上ある最も大きい目（0-5、below より小さい目から探す）
+fn highest_of_a_kind(p: &DicePattern, n: u8, below: usize) -> Option<usize> {
+    (0..below).rev().find(|&face| p[face] >= n)
+}
+
+/// 出目パターンから北欧式ヤッツィーの得点を計算
+pub fn calculate_yatzy_score(p: &DicePattern, cat: usize) -> u8 {
+    use yatzy_category::*;
+    let of_a_kind = |n: u8| highest_of_a_kind(p, n, 6).map_or(0, |face| n * (face as u8 + 1));
+    match cat {
+        _ if category::is_upper(cat) => p[cat] * (cat as u8 + 1),
+        ONE_PAIR => of_a_kind(2),
+        TWO_PAIRS => match highest_of_a_kind(p, 2, 6) {
+            Some(high) => highest_of_a_kind(p, 2, high).map_or(0, |low| 2 * (high + low + 2) as u8),
+            None => 0,
+        },
+        THREE_OF_A_KIND => of_a_kind(3),
+        FOUR_OF_A_KIND => of_a_kind(4),
+        SMALL_STRAIGHT if p[..6] == [1, 1, 1, 1, 1, 0] => 15,
+        LARGE_STRAIGHT if p[..6] == [0, 1, 1, 1, 1, 1] => 20,
+        FULL_HOUSE if p.contains(&3) && p.contains(&2) => pattern_pips(p),
+        CHANCE => pattern_pips(p),
+        YATZY if p.contains(&5) => 50,
+        _ => 0,
+    }
+}
+
+/// 出目パターンからマキシヤッツィー（6個のダイス）の得点を計算
+pub fn calculate_maxi_score(p: &DicePattern, cat: usize) -> u8 {
+    use maxi_category::*;
+    let of_a_kind = |n: u8| highest_of_a_kind(p, n, 6).map_or(0, |face| n * (face as u8 + 1));
+    // n個の組と m個の組（異なる目）で最も高い組み合わせ
+    let two_sets = |n: u8, m: u8| {
+        (0..6)
+            .rev()
+            .filter(|&a| p[a] >= n)
+            .flat_map(|a| {
+                (0..6)
+                    .rev()
+                    .filter(move |&b| b != a && p[b] >= m)
+                    .map(move |b| (a, b))
+            })
+            .map(|(a, b)| n * (a as u8 + 1) + m * (b as u8 + 1))
+            .max()
+            .unwrap_or(0)
+    };
+    match cat {
+        _ if category::is_upper(cat) => p[cat] * (cat as u8 + 1),
+        ONE_PAIR => of_a_kind(2),
+        TWO_PAIRS => match highest_of_a_kind(p, 2, 6) {
+            Some(high) => highest_of_a_kind(p, 2, high).map_or(0, |low| 2 * (high + low + 2) as u8),
+            None => 0,
+        },
+        THREE_PAIRS if p.iter().filter(|&&c| c >= 2).count() == 3 => pattern_pips(p),
+        THREE_OF_A_KIND => of_a_kind(3),
+        FOUR_OF_A_KIND => of_a_kind(4),
+        FIVE_OF_A_KIND => of_a_kind(5),
+        SMALL_STRAIGHT if p[..5].iter().all(|&c| c >= 1) => 15,
+        LARGE_STRAIGHT if p[1..6].iter().all(|&c| c >= 1) => 20,
+        FULL_STRAIGHT if p[..6] == [1; 6] => 21,
+        FULL_HOUSE => two_sets(3, 2),
+        CASTLE if p.iter().filter(|&&c| c == 3).count() == 2 => pattern_pips(p),
+        TOWER => two_sets(4, 2),
+        CHANCE => pattern_pips(p),
+        MAXI_YATZY if p.contains(&6) => 100,
         _ => 0,
     }
 }
@@ -304,7 +716,7 @@ mod tests {
     #[test]
     fn test_initial_score() {
         let score = get_initial_expected_score();
-        assert!((score - 190.158733).abs() < 0.01);
+        assert!((score - 190.158_73).abs() < 0.01);
     }
 
     #[test]
@@ -314,12 +726,87 @@ mod tests {
         // 確率の合計は1.0
         let total: f32 = dice_patterns::PATTERNS_5.iter().map(|p| p.probability).sum();
         assert!((total - 1.0).abs() < 0.0001);
+
+        // 6個振る → 462パターン
+        assert_eq!(dice_patterns::PATTERNS_6.len(), 462);
+        let total: f32 = dice_patterns::PATTERNS_6
+            .iter()
+            .map(|p| p.probability)
+            .sum();
+        assert!((total - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_table_roundtrip_checks() {
+        let data = include_bytes!("dp_table.ydp");
+        let table = YACHT.decode_table(data).unwrap();
+        assert!((table[0] - 190.158_73).abs() < 0.001);
+
+        // 1バイトでも壊れていれば読み込まない
+        let mut corrupted = data.to_vec();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        assert_eq!(
+            YACHT.decode_table(&corrupted),
+            Err(DpTableError::PayloadChecksum)
+        );
+
+        // 異なるルールのテーブルは読み込まない
+        let mut other_rules = data.to_vec();
+        other_rules[4] ^= 1;
+        assert!(matches!(
+            YACHT.decode_table(&other_rules),
+            Err(DpTableError::RulesMismatch { .. })
+        ));
+
+        assert_eq!(
+            YACHT.decode_table(&data[..data.len() - 1]),
+            Err(DpTableError::PayloadChecksum)
+        );
+    }
+
+    /// data のヘッダーに payload を付け、チェックサムを付け直す
+    fn with_payload(data: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut table = data[..TABLE_HEADER_LEN].to_vec();
+        table[16..20].copy_from_slice(&fnv1a(payload, FNV_OFFSET).to_le_bytes());
+        table.extend_from_slice(payload);
+        table
+    }
+
+    fn leb128(mut z: u64, out: &mut Vec<u8>) {
+        while z >= 0x80 {
+            out.push(z as u8 | 0x80);
+            z >>= 7;
+        }
+        out.push(z as u8);
+    }
+
+    #[test]
+    fn test_table_rejects_corrupt_payloads() {
+        let data = include_bytes!("dp_table.ydp");
+        // 終わらない可変長整数
+        let table = with_payload(data, &[0xFF; 12]);
+        assert_eq!(YACHT.decode_table(&table), Err(DpTableError::Corrupt));
+
+        // 倍率 0
+        let mut zero_scale = data.to_vec();
+        zero_scale[8..12].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(YACHT.decode_table(&zero_scale), Err(DpTableError::Corrupt));
+
+        // 差分の累積が i64 を溢れる
+        let mut payload = vec![0; reachable_upper_sums(0).len()];
+        let delta = (1i64 << 62) - 1;
+        for _ in 0..3 {
+            leb128((delta as u64) << 1, &mut payload);
+        }
+        let table = with_payload(data, &payload);
+        assert_eq!(YACHT.decode_table(&table), Err(DpTableError::Corrupt));
     }
 
     #[test]
     fn test_dice_to_pattern() {
         let dice = [1, 1, 2, 3, 6];
         let pattern = dice_to_pattern(&dice);
-        assert_eq!(pattern, [2, 1, 1, 0, 0, 1]);
+        assert_eq!(pattern, [2, 1, 1, 0, 0, 1, 0, 0]);
     }
 }
@@ -0,0 +1,275 @@
+//! 強化学習用の環境（Gym 形式）
+//!
+//! `GameState` をそのまま使い、行動・観測・報酬を固定長の数値で扱えるようにする。
+//! 手番のプレイヤー（どの席でも）をエージェントが操作し、各手番の1回目のロールは自動で行う。
+//! `expected_score` / `action_value` で DPテーブルによる厳密な期待値と比較できる。
+
+use std::fmt;
+
+use crate::{dp_table, Category, GameState, YachtAI};
+
+/// 観測ベクトルの長さ
+///
+/// - 0..5: 各位置の出目 (1-6)
+/// - 5..10: 各位置のロック (0/1)
+/// - 10..16: 各目の個数
+/// - 16: 残りロール数
+/// - 17..29: 使用済みカテゴリ (0/1)
+/// - 29: 上段累計（63上限）
+pub const OBS_SIZE: usize = 30;
+
+/// 行動の総数（振り直し 31 通り + カテゴリ 12 通り）
+pub const NUM_ACTIONS: usize = 31 + dp_table::NUM_CATEGORIES;
+
+pub type Observation = [f32; OBS_SIZE];
+
+/// エージェントの行動
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// ビットマスク（bit i = i番目のダイス）でホールドして振り直す（全ホールドの 31 は不可）
+    Reroll(u8),
+    /// カテゴリを選んで得点する
+    Score(Category),
+}
+
+impl Action {
+    /// 0..NUM_ACTIONS の整数から行動へ
+    pub fn from_index(index: usize) -> Option<Action> {
+        if index < 31 {
+            Some(Action::Reroll(index as u8))
+        } else {
+            Category::from_index(index - 31).map(Action::Score)
+        }
+    }
+
+    /// 行動から 0..NUM_ACTIONS の整数へ
+    pub fn index(&self) -> usize {
+        match *self {
+            Action::Reroll(mask) => mask as usize,
+            Action::Score(category) => 31 + category as usize,
+        }
+    }
+}
+
+/// 不正な行動
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IllegalAction(pub Action);
+
+impl fmt::Display for IllegalAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "illegal action: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for IllegalAction {}
+
+/// Gym 形式の環境
+#[derive(Clone, Debug)]
+pub struct YachtEnv {
+    game: GameState,
+    ai: YachtAI,
+}
+
+impl YachtEnv {
+    pub fn new(seed: u64) -> YachtEnv {
+        let mut env = YachtEnv {
+            game: GameState::new(),
+            ai: YachtAI::new(),
+        };
+        env.reset(seed);
+        env
+    }
+
+    /// 新しいゲームを始め、最初の観測を返す
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game = GameState::with_hold_rule(self.game.hold_rule());
+        self.game.set_seed(seed);
+        self.game.roll().expect("a new game can always roll");
+        self.observation()
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    /// 手番のプレイヤー
+    pub fn current_seat(&self) -> u8 {
+        self.game.current_player
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.game.game_over
+    }
+
+    /// 現在の観測
+    pub fn observation(&self) -> Observation {
+        let mut obs = [0.0f32; OBS_SIZE];
+        let board = self.game.current_board();
+
+        for i in 0..5 {
+            obs[i] = self.game.dice().values[i] as f32;
+            obs[5 + i] = if self.game.dice().locked[i] { 1.0 } else { 0.0 };
+        }
+        for (face, &count) in dp_table::dice_to_pattern(&self.game.dice().values)
+            .iter()
+            .enumerate()
+        {
+            obs[10 + face] = count as f32;
+        }
+        obs[16] = self.game.rolls_left() as f32;
+        let used_hands = board.used_hands_mask();
+        for cat in 0..dp_table::NUM_CATEGORIES {
+            obs[17 + cat] = ((used_hands >> cat) & 1) as f32;
+        }
+        obs[29] = board.upper_sum_capped() as f32;
+        obs
+    }
+
+    /// 現在とれる行動の一覧
+    pub fn legal_actions(&self) -> Vec<Action> {
+        (0..NUM_ACTIONS)
+            .filter_map(Action::from_index)
+            .filter(|&a| self.is_legal(a))
+            .collect()
+    }
+
+    pub fn is_legal(&self, action: Action) -> bool {
+        if self.game.game_over {
+            return false;
+        }
+        match action {
+            Action::Reroll(mask) => {
+                mask < 31
+                    && self.game.rolls_left() > 0
+                    && (0..5).all(|i| !self.game.dice().locked[i] || (mask >> i) & 1 == 1)
+            }
+            Action::Score(category) => !self.game.current_board().is_used(category),
+        }
+    }
+
+    /// 行動を実行し (観測, 報酬, 終了) を返す
+    /// 報酬は行動した席の合計点数の増加分（上段ボーナスを含む）
+    pub fn step(&mut self, action: Action) -> Result<(Observation, f32, bool), IllegalAction> {
+        if !self.is_legal(action) {
+            return Err(IllegalAction(action));
+        }
+
+        let reward = match action {
+            Action::Reroll(mask) => {
+                let holds = std::array::from_fn(|i| (mask >> i) & 1 == 1);
+                self.ai.apply_holds(&mut self.game, &holds);
+                self.game
+                    .roll()
+                    .expect("legal rerolls always have a roll left");
+                0.0
+            }
+            Action::Score(category) => {
+                let seat = self.game.current_player;
+                let before = self.game.board(seat).total();
+                self.game
+                    .select(category)
+                    .expect("legal categories are always unused");
+                let after = self.game.board(seat).total();
+                // 次の手番の1回目のロール
+                if !self.game.is_game_over() {
+                    self.game.roll().expect("a new turn can always roll");
+                }
+                (after - before) as f32
+            }
+        };
+
+        Ok((self.observation(), reward, self.game.game_over))
+    }
+
+    /// 手番の席が現在の状態から最適に続けた場合の最終合計点数の期待値
+    pub fn expected_score(&self) -> f32 {
+        self.ai.live_expected_score(&self.game)
+    }
+
+    /// 行動をとった後に最適に続けた場合の最終合計点数の期待値（行動価値）
+    /// 不正な行動なら None
+    pub fn action_value(&self, action: Action) -> Option<f32> {
+        if !self.is_legal(action) {
+            return None;
+        }
+        let board = self.game.current_board();
+        let current_total = board.total() as f32;
+        let upper_sum = board.upper_sum_capped();
+        let used_hands = board.used_hands_mask();
+
+        let future = match action {
+            Action::Reroll(mask) => {
+                let held = std::array::from_fn(|i| (mask >> i) & 1 == 1);
+                self.ai.held_future_value(
+                    &self.game.dice().values,
+                    &held,
+                    self.game.rolls_left(),
+                    upper_sum,
+                    used_hands,
+                    self.game.hold_rule(),
+                )
+            }
+            Action::Score(category) => {
+                let score = crate::calculate_score(&self.game.dice().values, category);
+                dp_table::evaluate_category_choice(upper_sum, used_hands, category as usize, score)
+            }
+        };
+        Some(current_total + future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_index_roundtrip() {
+        for index in 0..NUM_ACTIONS {
+            assert_eq!(Action::from_index(index).unwrap().index(), index);
+        }
+        assert!(Action::from_index(NUM_ACTIONS).is_none());
+    }
+
+    #[test]
+    fn test_episode() {
+        let mut env = YachtEnv::new(42);
+        let mut total_reward = 0.0;
+        let mut steps = 0;
+
+        loop {
+            let actions = env.legal_actions();
+            assert!(!actions.is_empty());
+            // 常に最初の空きカテゴリで得点する
+            let action = *actions
+                .iter()
+                .find(|a| matches!(a, Action::Score(_)))
+                .unwrap();
+            assert!(env.action_value(action).unwrap() <= env.expected_score() + 0.001);
+            let (_, reward, done) = env.step(action).unwrap();
+            total_reward += reward;
+            steps += 1;
+            if done {
+                break;
+            }
+        }
+
+        assert_eq!(steps, 24);
+        let game = env.game();
+        assert_eq!(
+            total_reward,
+            (game.board(0).total() + game.board(1).total()) as f32
+        );
+        assert!(env.step(Action::Reroll(0)).is_err());
+    }
+
+    #[test]
+    fn test_reset_is_deterministic() {
+        let mut a = YachtEnv::new(7);
+        let mut b = YachtEnv::new(7);
+        assert_eq!(a.observation(), b.observation());
+        let (obs_a, _, _) = a.step(Action::Reroll(0b00011)).unwrap();
+        let (obs_b, _, _) = b.step(Action::Reroll(0b00011)).unwrap();
+        assert_eq!(obs_a, obs_b);
+        assert_eq!(a.reset(7), b.reset(7));
+    }
+}
@@ -0,0 +1,386 @@
+//! コミット・リビールによる検証可能なダイス
+//!
+//! 1. サーバーはゲーム前に秘密のサーバーシードを決め、その SHA-256（コミットメント）を公開する
+//! 2. 各クライアントが任意の文字列（エントロピー）を送る
+//! 3. ゲームのシード = SHA-256("yacht-fair-v1" || サーバーシード || 各エントロピー（u32 LE の長さ + UTF-8）)
+//! 4. k 回目（ゲーム全体で 0 から数える）のロールの出目 = `roll_values(シード, k)`。
+//!    SHA-256(シード || k (u64 LE) || c (u32 LE)) を c = 0, 1, ... の順に並べたバイト列から
+//!    252 未満のバイト b を先頭から5つ取り、b % 6 + 1 を各位置の出目とする（ホールドした位置は使わない）
+//! 5. ゲーム後にサーバーシードを公開し、誰でも `verify` で全ロールを検証できる
+//!
+//! サーバーはコミット後にシードを変えられず、クライアントのエントロピーを事前に知らないので
+//! 出目を操作できない。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{GameEvent, GameState};
+
+/// シードのバイト長
+pub const SEED_LEN: usize = 32;
+
+pub type Seed = [u8; SEED_LEN];
+
+const DOMAIN: &[u8] = b"yacht-fair-v1";
+
+/// 検証に失敗した理由
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FairError {
+    /// サーバーシードまたはコミットメントが 32 バイトの hex ではない
+    BadHex,
+    /// サーバーシードのハッシュがコミットメントと一致しない
+    CommitmentMismatch,
+    /// k 回目のロールの出目がシードから導いた値と一致しない
+    RollMismatch { roll_index: u64 },
+    /// 記録の event_index 番目が手番の流れとつながらない（振る前の出目・ホールド・記入した出目の食い違い）
+    BrokenChain { event_index: usize },
+}
+
+impl fmt::Display for FairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FairError::BadHex => f.write_str("seed is not 32 bytes of hex"),
+            FairError::CommitmentMismatch => f.write_str("server seed does not match commitment"),
+            FairError::RollMismatch { roll_index } => {
+                write!(f, "roll {} does not match the seed", roll_index)
+            }
+            FairError::BrokenChain { event_index } => {
+                write!(
+                    f,
+                    "event {} does not follow from the previous roll",
+                    event_index
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FairError {}
+
+/// サーバーシードのコミットメント（hex）
+pub fn commitment(server_seed: &Seed) -> String {
+    hex::encode(Sha256::digest(server_seed))
+}
+
+/// サーバーシードとクライアントのエントロピーからゲームのシードを作る
+pub fn combine_seeds<S: AsRef<str>>(server_seed: &Seed, client_entropy: &[S]) -> Seed {
+    let mut hasher = Sha256::new();
+    hasher.update(DOMAIN);
+    hasher.update(server_seed);
+    for entropy in client_entropy {
+        let bytes = entropy.as_ref().as_bytes();
+        hasher.update((bytes.len() as u32).to_le_bytes());
+        hasher.update(bytes);
+    }
+    hasher.finalize().into()
+}
+
+/// k 回目のロールで各位置に出る目（N 個のダイス。先頭の5個はダイスの個数によらない）
+pub fn roll_values<const N: usize>(seed: &Seed, roll_index: u64) -> [u8; N] {
+    let mut values = [0u8; N];
+    let mut filled = 0;
+    let mut counter = 0u32;
+    while filled < N {
+        let block = Sha256::new()
+            .chain_update(seed)
+            .chain_update(roll_index.to_le_bytes())
+            .chain_update(counter.to_le_bytes())
+            .finalize();
+        // 252 = 6 * 42 未満だけを使って偏りをなくす
+        for &b in block.iter().filter(|&&b| b < 252) {
+            if filled == N {
+                break;
+            }
+            values[filled] = b % 6 + 1;
+            filled += 1;
+        }
+        counter += 1;
+    }
+    values
+}
+
+fn decode_seed(hex_seed: &str) -> Result<Seed, FairError> {
+    let bytes = hex::decode(hex_seed).map_err(|_| FairError::BadHex)?;
+    bytes.try_into().map_err(|_| FairError::BadHex)
+}
+
+/// サーバー側のシード管理
+#[derive(Clone, Debug)]
+pub struct FairDice {
+    server_seed: Seed,
+    client_entropy: Vec<String>,
+}
+
+impl FairDice {
+    /// OS の乱数でサーバーシードを決める
+    pub fn new() -> FairDice {
+        let mut server_seed = [0u8; SEED_LEN];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut server_seed);
+        FairDice::from_server_seed(server_seed)
+    }
+
+    pub fn from_server_seed(server_seed: Seed) -> FairDice {
+        FairDice {
+            server_seed,
+            client_entropy: Vec::new(),
+        }
+    }
+
+    /// ゲーム前に公開するコミットメント
+    pub fn commitment(&self) -> String {
+        commitment(&self.server_seed)
+    }
+
+    pub fn add_client_entropy(&mut self, entropy: &str) {
+        self.client_entropy.push(entropy.to_string());
+    }
+
+    pub fn client_entropy(&self) -> &[String] {
+        &self.client_entropy
+    }
+
+    /// ゲームのシード（ゲーム中は秘密）
+    pub fn seed(&self) -> Seed {
+        combine_seeds(&self.server_seed, &self.client_entropy)
+    }
+
+    /// ゲーム後に公開するサーバーシード（hex）
+    pub fn reveal(&self) -> String {
+        hex::encode(self.server_seed)
+    }
+
+    /// 検証用の記録
+    pub fn record(&self, game: &GameState) -> FairRecord {
+        FairRecord {
+            commitment: self.commitment(),
+            server_seed: self.reveal(),
+            client_entropy: self.client_entropy.clone(),
+            history: game.history().to_vec(),
+        }
+    }
+}
+
+impl Default for FairDice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ゲーム後に公開する検証用の記録
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FairRecord {
+    /// ゲーム前に公開したコミットメント（hex）
+    pub commitment: String,
+    /// ゲーム後に公開したサーバーシード（hex）
+    pub server_seed: String,
+    /// 各クライアントのエントロピー（送った順）
+    pub client_entropy: Vec<String>,
+    pub history: Vec<GameEvent>,
+}
+
+/// 記録の全ロールがコミットメントとエントロピーから導いた出目どおりか検証する
+///
+/// ホールドしたダイスの値をすり替えられないよう、手番の流れもたどる: 手番の1回目のロールは何もホールドしない、
+/// 2回目以降のロールの振る前の出目は直前のロールの出目、振り直しをやめた・記入した出目は最後のロールの出目。
+pub fn verify(record: &FairRecord) -> Result<(), FairError> {
+    let server_seed = decode_seed(&record.server_seed)?;
+    if commitment(&server_seed) != record.commitment.to_ascii_lowercase() {
+        return Err(FairError::CommitmentMismatch);
+    }
+    let seed = combine_seeds(&server_seed, &record.client_entropy);
+
+    let mut roll_index = 0u64;
+    // 手番中の直前のロールの出目
+    let mut last: Option<[u8; 5]> = None;
+    for (event_index, event) in record.history.iter().enumerate() {
+        let broken = Err(FairError::BrokenChain { event_index });
+        match event {
+            GameEvent::Roll {
+                rolls_left,
+                held,
+                before,
+                after,
+                ..
+            } => {
+                let chained = if *rolls_left == 3 {
+                    held.iter().all(|&h| !h)
+                } else {
+                    last == Some(*before)
+                };
+                if !chained {
+                    return broken;
+                }
+                let values: [u8; 5] = roll_values(&seed, roll_index);
+                let ok = (0..5).all(|i| after[i] == if held[i] { before[i] } else { values[i] });
+                if !ok {
+                    return Err(FairError::RollMismatch { roll_index });
+                }
+                roll_index += 1;
+                last = Some(*after);
+            }
+            GameEvent::Stand { dice, .. } if last != Some(*dice) => return broken,
+            GameEvent::Stand { .. } | GameEvent::Announce { .. } => {}
+            GameEvent::Score { dice, .. } => {
+                if last != Some(*dice) {
+                    return broken;
+                }
+                last = None;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::play::Strategy;
+    use crate::{Category, YachtAI};
+
+    #[test]
+    fn test_roll_values() {
+        let seed = combine_seeds(&[7; SEED_LEN], &["alice", "bob"]);
+        let values: [u8; 5] = roll_values(&seed, 0);
+        assert!(values.iter().all(|v| (1..=6).contains(v)));
+        assert_eq!(values, roll_values(&seed, 0));
+        assert_ne!(
+            (0..8).map(|k| roll_values(&seed, k)).collect::<Vec<_>>(),
+            vec![values; 8]
+        );
+        // 6個のダイスでも先頭の5個は同じ
+        assert_eq!(roll_values::<6>(&seed, 0)[..5], values);
+        // エントロピーの区切りも結果に効く
+        assert_ne!(seed, combine_seeds(&[7; SEED_LEN], &["aliceb", "ob"]));
+    }
+
+    #[test]
+    fn test_verify_game() {
+        let mut fair = FairDice::from_server_seed([42; SEED_LEN]);
+        let commitment = fair.commitment();
+        fair.add_client_entropy("alice");
+        fair.add_client_entropy("bob");
+
+        let mut game = GameState::new();
+        game.set_fair_seed(fair.seed());
+        let ai = YachtAI::new();
+        while !game.is_game_over() {
+            ai.play_turn(&mut game).unwrap();
+        }
+
+        let record = fair.record(&game);
+        assert_eq!(record.commitment, commitment);
+        assert_eq!(verify(&record), Ok(()));
+
+        // シードの差し替え、エントロピーの改ざん、出目の改ざんはどれも検出できる
+        let mut other = record.clone();
+        other.server_seed = hex::encode([43; SEED_LEN]);
+        assert_eq!(verify(&other), Err(FairError::CommitmentMismatch));
+        other.server_seed = "zz".to_string();
+        assert_eq!(verify(&other), Err(FairError::BadHex));
+
+        let mut other = record.clone();
+        other.client_entropy[1] = "mallory".to_string();
+        assert!(matches!(
+            verify(&other),
+            Err(FairError::RollMismatch { .. })
+        ));
+
+        let mut other = record.clone();
+        let (index, after) = other
+            .history
+            .iter_mut()
+            .enumerate()
+            .find_map(|(i, e)| match e {
+                GameEvent::Roll { seat: 1, after, .. } => Some((i, after)),
+                _ => None,
+            })
+            .unwrap();
+        after[0] = after[0] % 6 + 1;
+        let roll_index = record.history[..index]
+            .iter()
+            .filter(|e| matches!(e, GameEvent::Roll { .. }))
+            .count() as u64;
+        assert_eq!(verify(&other), Err(FairError::RollMismatch { roll_index }));
+    }
+
+    /// 条件を満たす最初のイベントの位置
+    fn find_event(history: &[GameEvent], f: impl Fn(&GameEvent) -> bool) -> usize {
+        history.iter().position(f).unwrap()
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let fair = FairDice::from_server_seed([9; SEED_LEN]);
+        let mut game = GameState::new();
+        game.set_fair_seed(fair.seed());
+        let ai = YachtAI::new();
+        while !game.is_game_over() {
+            ai.play_turn(&mut game).unwrap();
+        }
+        let record = fair.record(&game);
+        assert_eq!(verify(&record), Ok(()));
+
+        // ホールドしたダイスの値をすり替える（振る前と振った後の両方を書き換えても検出できる）
+        let mut other = record.clone();
+        let index = find_event(
+            &other.history,
+            |e| matches!(e, GameEvent::Roll { rolls_left, held, .. } if *rolls_left < 3 && held.contains(&true)),
+        );
+        if let GameEvent::Roll {
+            held,
+            before,
+            after,
+            ..
+        } = &mut other.history[index]
+        {
+            let die = held.iter().position(|&h| h).unwrap();
+            before[die] = before[die] % 6 + 1;
+            after[die] = before[die];
+        }
+        assert_eq!(
+            verify(&other),
+            Err(FairError::BrokenChain { event_index: index })
+        );
+
+        // 手番の1回目のロールでホールドしたことにする
+        let mut other = record.clone();
+        let index = find_event(&other.history, |e| {
+            matches!(e, GameEvent::Roll { rolls_left: 3, .. })
+        });
+        if let GameEvent::Roll { held, .. } = &mut other.history[index] {
+            held[0] = true;
+        }
+        assert_eq!(
+            verify(&other),
+            Err(FairError::BrokenChain { event_index: index })
+        );
+
+        // 最後のロールと違う出目で記入する
+        let mut other = record.clone();
+        let index = find_event(&other.history, |e| matches!(e, GameEvent::Score { .. }));
+        if let GameEvent::Score { dice, .. } = &mut other.history[index] {
+            dice[0] = dice[0] % 6 + 1;
+        }
+        assert_eq!(
+            verify(&other),
+            Err(FairError::BrokenChain { event_index: index })
+        );
+    }
+
+    #[test]
+    fn test_held_dice_keep_values() {
+        let mut game = GameState::new();
+        game.set_fair_seed([1; SEED_LEN]);
+        game.roll().unwrap();
+        let first = game.dice().values();
+        assert_eq!(first, roll_values(&[1; SEED_LEN], 0));
+        game.set_hold(2, true).unwrap();
+        game.roll().unwrap();
+        let second: [u8; 5] = roll_values(&[1; SEED_LEN], 1);
+        assert_eq!(game.dice().values()[2], first[2]);
+        assert_eq!(game.dice().values()[0], second[0]);
+        game.select(Category::Choice).unwrap();
+    }
+}
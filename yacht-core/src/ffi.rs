@@ -0,0 +1,843 @@
+//! C ABI（`ffi` フィーチャー）
+//!
+//! ネイティブのゲームクライアントから使うための C API。ヘッダーは `include/yacht_core.h`
+//! （`cargo build --features ffi` で cbindgen が生成する）。
+//!
+//! - `YachtGame` / `YachtAi` は不透明なハンドルで、`*_new` で作成し `*_free` で解放する
+//! - 関数は `YachtStatus` を返し、結果は出力引数に書き込む
+//! - カテゴリは 0-11、席は 0 = プレイヤー / 1 = AI
+//! - ヨット以外のルールは `yacht_game_new_with_ruleset`（ハウスルールは `yacht_game_new_custom`）で作り、
+//!   欄を列と行で指定する（`yacht_game_select_cell`）
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr};
+use std::ptr;
+use std::sync::Arc;
+
+use crate::custom::CustomRuleset;
+use crate::dice_model::DiceModel;
+use crate::play::{RulesetAI, Strategy};
+use crate::{
+    calculate_score, yatzy, Category, Cell, DpTableError, GameError, GameState, HoldDecision,
+    HoldRule, Ruleset, YachtAI,
+};
+
+/// エラーコード
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YachtStatus {
+    Ok = 0,
+    /// ハンドルまたは出力引数が NULL
+    NullPointer = 1,
+    /// カテゴリ・席・インデックスなどが範囲外
+    InvalidArgument = 2,
+    /// 現在の状態ではできない操作（振れない、使用済みのカテゴリなど）
+    InvalidState = 3,
+}
+
+/// ゲームのハンドル
+pub struct YachtGame(GameState);
+
+/// AIのハンドル
+pub struct YachtAi {
+    yacht: YachtAI,
+    /// ヨットとヘネラル以外のルールのAI（最後に遊んだルールのものを使い回す）
+    other: RefCell<Option<RulesetAI>>,
+}
+
+/// カテゴリ選択の候補
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct YachtCategoryChoice {
+    pub category: u8,
+    /// 即時得点
+    pub score: u8,
+    /// 最終的な合計点数の期待値
+    pub expected: f32,
+    /// 最善の候補との期待値の差
+    pub loss: f32,
+    /// 他の候補と期待値が同じ
+    pub tied: bool,
+}
+
+/// キープの候補
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct YachtHoldChoice {
+    /// ホールドするダイス（0/1）
+    pub holds: [u8; 5],
+    /// 振り直さずに今の出目で得点する
+    pub stop: bool,
+    /// 最終的な合計点数の期待値
+    pub expected: f32,
+    /// 最善の候補との期待値の差
+    pub loss: f32,
+    /// 他の候補と期待値が同じ
+    pub tied: bool,
+}
+
+macro_rules! try_ref {
+    ($ptr:expr) => {
+        match $ptr.as_ref() {
+            Some(r) => r,
+            None => return YachtStatus::NullPointer,
+        }
+    };
+}
+
+macro_rules! try_mut {
+    ($ptr:expr) => {
+        match $ptr.as_mut() {
+            Some(r) => r,
+            None => return YachtStatus::NullPointer,
+        }
+    };
+}
+
+fn status(result: Result<(), GameError>) -> YachtStatus {
+    match result {
+        Ok(()) => YachtStatus::Ok,
+        Err(
+            GameError::InvalidDie
+            | GameError::InvalidDieValue
+            | GameError::HeldDieChanged
+            | GameError::CategoryUnavailable
+            | GameError::UnsupportedDice,
+        ) => YachtStatus::InvalidArgument,
+        Err(_) => YachtStatus::InvalidState,
+    }
+}
+
+/// エラーコードの説明（静的な NUL 終端文字列）
+#[no_mangle]
+pub extern "C" fn yacht_status_message(status: YachtStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        YachtStatus::Ok => b"ok\0",
+        YachtStatus::NullPointer => b"null pointer\0",
+        YachtStatus::InvalidArgument => b"invalid argument\0",
+        YachtStatus::InvalidState => b"invalid state\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+/// 出目とカテゴリから得点を計算
+///
+/// # Safety
+/// `dice` は5要素の配列、`out_score` は書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_calculate_score(
+    dice: *const u8,
+    category: u8,
+    out_score: *mut u8,
+) -> YachtStatus {
+    if dice.is_null() {
+        return YachtStatus::NullPointer;
+    }
+    let out_score = try_mut!(out_score);
+    let values: [u8; 5] = ptr::read(dice as *const [u8; 5]);
+    if values.iter().any(|&v| !(1..=6).contains(&v)) {
+        return YachtStatus::InvalidArgument;
+    }
+    match Category::from_index(category as usize) {
+        Some(c) => {
+            *out_score = calculate_score(&values, c);
+            YachtStatus::Ok
+        }
+        None => YachtStatus::InvalidArgument,
+    }
+}
+
+/// ヤッツィーのDPテーブル（YDP1 形式）を読み込む
+/// embed-yatzy-table フィーチャーなしのビルドでは、ヤッツィーをAIに打たせる前に呼ぶ
+/// （壊れたデータは InvalidArgument、読み込み済みなら InvalidState）
+///
+/// # Safety
+/// `data` は len バイトの読み込み可能な配列であること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_load_yatzy_table(data: *const u8, len: usize) -> YachtStatus {
+    if data.is_null() {
+        return YachtStatus::NullPointer;
+    }
+    match yatzy::load_table(std::slice::from_raw_parts(data, len)) {
+        Ok(()) => YachtStatus::Ok,
+        Err(DpTableError::AlreadyLoaded) => YachtStatus::InvalidState,
+        Err(_) => YachtStatus::InvalidArgument,
+    }
+}
+
+// ========== Game ==========
+
+/// ゲームを作成（hold_rule: 0 = Free, 1 = Locking）
+/// 不正な hold_rule なら NULL
+#[no_mangle]
+pub extern "C" fn yacht_game_new(hold_rule: u8) -> *mut YachtGame {
+    let hold_rule = match hold_rule {
+        0 => HoldRule::Free,
+        1 => HoldRule::Locking,
+        _ => return ptr::null_mut(),
+    };
+    Box::into_raw(Box::new(YachtGame(GameState::with_hold_rule(hold_rule))))
+}
+
+/// ルールを指定してゲームを作成
+/// （ruleset: 0 = ヨット, 1 = ヘネラル, 2 = トリプルヨット, 3 = ヤンブ, 4 = ヤッツィー。solo なら1人用）
+/// 不正な ruleset なら NULL
+#[no_mangle]
+pub extern "C" fn yacht_game_new_with_ruleset(ruleset: u8, solo: bool) -> *mut YachtGame {
+    match Ruleset::ALL.get(ruleset as usize) {
+        Some(ruleset) => new_game(ruleset.clone(), solo),
+        None => ptr::null_mut(),
+    }
+}
+
+/// ハウスルール（`custom` の JSON 定義、NUL 終端の UTF-8）のゲームを作成（solo なら1人用）
+/// 定義が不正なら NULL
+///
+/// # Safety
+/// `rules_json` は NULL か NUL 終端の文字列であること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_new_custom(
+    rules_json: *const c_char,
+    solo: bool,
+) -> *mut YachtGame {
+    if rules_json.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(json) = CStr::from_ptr(rules_json).to_str() else {
+        return ptr::null_mut();
+    };
+    match CustomRuleset::from_json(json) {
+        Ok(rules) => new_game(Ruleset::Custom(Arc::new(rules)), solo),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+fn new_game(ruleset: Ruleset, solo: bool) -> *mut YachtGame {
+    let game = if solo {
+        GameState::solo(ruleset)
+    } else {
+        GameState::with_ruleset(ruleset)
+    };
+    Box::into_raw(Box::new(YachtGame(game)))
+}
+
+/// ゲームを解放（NULL は何もしない）
+///
+/// # Safety
+/// `game` は `yacht_game_new` が返したポインタで、まだ解放していないこと
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_free(game: *mut YachtGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// 以降のロールをシードから決定的に生成する
+///
+/// # Safety
+/// `game` は有効なハンドルであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_set_seed(game: *mut YachtGame, seed: u64) -> YachtStatus {
+    try_mut!(game).0.set_seed(seed);
+    YachtStatus::Ok
+}
+
+/// 以降のロールで振るダイスを目ごとの重み（weights[0] が 1 の目、2-8 面）で設定する
+/// （面の数や重みが不正、または標準以外のダイスで遊べないルールなら InvalidArgument）
+///
+/// # Safety
+/// `game` は有効なハンドル、`weights` は len 要素の配列であること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_set_dice_weights(
+    game: *mut YachtGame,
+    weights: *const f64,
+    len: usize,
+) -> YachtStatus {
+    let game = try_mut!(game);
+    if weights.is_null() {
+        return YachtStatus::NullPointer;
+    }
+    match DiceModel::weighted(std::slice::from_raw_parts(weights, len)) {
+        Ok(model) => status(game.0.set_dice_model(model)),
+        Err(_) => YachtStatus::InvalidArgument,
+    }
+}
+
+/// サイコロを振る（残りロールがなければ InvalidState）
+///
+/// # Safety
+/// `game` は有効なハンドルであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_roll(game: *mut YachtGame) -> YachtStatus {
+    status(try_mut!(game).0.roll())
+}
+
+/// 実物のダイスで振った出目を入力する
+/// 出目が 1-6 でない、またはホールドしたダイスの出目が変わっていれば InvalidArgument
+///
+/// # Safety
+/// `game` は有効なハンドル、`values` は 5 要素の配列であること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_enter_roll(
+    game: *mut YachtGame,
+    values: *const u8,
+) -> YachtStatus {
+    let game = try_mut!(game);
+    let values = try_ref!(values as *const [u8; 5]);
+    status(game.0.enter_roll(*values))
+}
+
+/// 残りのロールを使わずに振り直しをやめる
+///
+/// # Safety
+/// `game` は有効なハンドルであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_stand(game: *mut YachtGame) -> YachtStatus {
+    status(try_mut!(game).0.stand())
+}
+
+/// ダイスのホールドを切り替える
+///
+/// # Safety
+/// `game` は有効なハンドルであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_toggle_hold(game: *mut YachtGame, index: u8) -> YachtStatus {
+    status(try_mut!(game).0.toggle_hold(index as usize))
+}
+
+/// カテゴリを選んで得点し、手番を交代する
+///
+/// # Safety
+/// `game` は有効なハンドルであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_select_category(
+    game: *mut YachtGame,
+    category: u8,
+) -> YachtStatus {
+    let game = try_mut!(game);
+    match Category::from_index(category as usize) {
+        Some(c) => status(game.0.select(c).map(|_| ())),
+        None => YachtStatus::InvalidArgument,
+    }
+}
+
+/// 欄（列・行）を選んで得点し、手番を交代する。得点が不要なら out_score は NULL でよい
+///
+/// # Safety
+/// `game` は有効なハンドル、`out_score` は NULL か書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_select_cell(
+    game: *mut YachtGame,
+    column: u8,
+    row: u8,
+    out_score: *mut u8,
+) -> YachtStatus {
+    let game = try_mut!(game);
+    match game.0.select_cell(Cell::new(column as usize, row as usize)) {
+        Ok(score) => {
+            if let Some(out) = out_score.as_mut() {
+                *out = score;
+            }
+            YachtStatus::Ok
+        }
+        Err(e) => status(Err(e)),
+    }
+}
+
+/// この手番で記入する行を宣言する（宣言の列があるルールの1回目のロールの後）
+///
+/// # Safety
+/// `game` は有効なハンドルであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_announce(game: *mut YachtGame, row: u8) -> YachtStatus {
+    status(try_mut!(game).0.announce(row as usize))
+}
+
+/// 現在の出目（5要素）
+///
+/// # Safety
+/// `game` は有効なハンドル、`out_dice` は5要素の書き込み可能な配列であること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_get_dice(
+    game: *const YachtGame,
+    out_dice: *mut u8,
+) -> YachtStatus {
+    let game = try_ref!(game);
+    if out_dice.is_null() {
+        return YachtStatus::NullPointer;
+    }
+    ptr::copy_nonoverlapping(game.0.dice().values.as_ptr(), out_dice, 5);
+    YachtStatus::Ok
+}
+
+/// ホールドとロックの状態（各5要素、0/1）。不要な方は NULL でよい
+///
+/// # Safety
+/// `game` は有効なハンドル、出力は NULL か5要素の書き込み可能な配列であること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_get_holds(
+    game: *const YachtGame,
+    out_holds: *mut u8,
+    out_locks: *mut u8,
+) -> YachtStatus {
+    let game = try_ref!(game);
+    if !out_holds.is_null() {
+        ptr::copy_nonoverlapping(game.0.dice().held.map(u8::from).as_ptr(), out_holds, 5);
+    }
+    if !out_locks.is_null() {
+        ptr::copy_nonoverlapping(game.0.dice().locked.map(u8::from).as_ptr(), out_locks, 5);
+    }
+    YachtStatus::Ok
+}
+
+/// 残りロール数・手番の席・ゲーム終了。不要な出力は NULL でよい
+///
+/// # Safety
+/// `game` は有効なハンドル、出力は NULL か書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_get_turn(
+    game: *const YachtGame,
+    out_rolls_left: *mut u8,
+    out_current_player: *mut u8,
+    out_game_over: *mut bool,
+) -> YachtStatus {
+    let game = try_ref!(game);
+    if let Some(out) = out_rolls_left.as_mut() {
+        *out = game.0.rolls_left();
+    }
+    if let Some(out) = out_current_player.as_mut() {
+        *out = game.0.current_player;
+    }
+    if let Some(out) = out_game_over.as_mut() {
+        *out = game.0.game_over;
+    }
+    YachtStatus::Ok
+}
+
+/// 得点表の1カテゴリ（未使用なら out_used = false, out_score = 0）
+///
+/// # Safety
+/// `game` は有効なハンドル、出力は書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_get_score(
+    game: *const YachtGame,
+    seat: u8,
+    category: u8,
+    out_score: *mut u8,
+    out_used: *mut bool,
+) -> YachtStatus {
+    let game = try_ref!(game);
+    let out_score = try_mut!(out_score);
+    let out_used = try_mut!(out_used);
+    let category = match Category::from_index(category as usize) {
+        Some(c) if seat <= 1 => c,
+        _ => return YachtStatus::InvalidArgument,
+    };
+    let score = game.0.board(seat).score(category);
+    *out_used = score.is_some();
+    *out_score = score.unwrap_or(0);
+    YachtStatus::Ok
+}
+
+/// 得点表の1欄（未記入なら out_used = false, out_score = 0）
+///
+/// # Safety
+/// `game` は有効なハンドル、出力は書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_get_cell(
+    game: *const YachtGame,
+    seat: u8,
+    column: u8,
+    row: u8,
+    out_score: *mut u8,
+    out_used: *mut bool,
+) -> YachtStatus {
+    let game = try_ref!(game);
+    let out_score = try_mut!(out_score);
+    let out_used = try_mut!(out_used);
+    let cell = Cell::new(column as usize, row as usize);
+    if seat > 1 || !game.0.ruleset().has_cell(cell) {
+        return YachtStatus::InvalidArgument;
+    }
+    let score = game.0.board(seat).cell(cell);
+    *out_used = score.is_some();
+    *out_score = score.unwrap_or(0);
+    YachtStatus::Ok
+}
+
+/// 得点表の集計。不要な出力は NULL でよい
+///
+/// # Safety
+/// `game` は有効なハンドル、出力は NULL か書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_game_get_totals(
+    game: *const YachtGame,
+    seat: u8,
+    out_upper_total: *mut u16,
+    out_upper_bonus: *mut u16,
+    out_total: *mut u16,
+) -> YachtStatus {
+    let game = try_ref!(game);
+    if seat > 1 {
+        return YachtStatus::InvalidArgument;
+    }
+    let board = game.0.board(seat);
+    if let Some(out) = out_upper_total.as_mut() {
+        *out = board.upper_total();
+    }
+    if let Some(out) = out_upper_bonus.as_mut() {
+        *out = board.upper_bonus();
+    }
+    if let Some(out) = out_total.as_mut() {
+        *out = board.total();
+    }
+    YachtStatus::Ok
+}
+
+// ========== AI ==========
+
+/// AIを作成
+#[no_mangle]
+pub extern "C" fn yacht_ai_new() -> *mut YachtAi {
+    Box::into_raw(Box::new(YachtAi {
+        yacht: YachtAI::new(),
+        other: RefCell::new(None),
+    }))
+}
+
+/// AIを解放（NULL は何もしない）
+///
+/// # Safety
+/// `ai` は `yacht_ai_new` が返したポインタで、まだ解放していないこと
+#[no_mangle]
+pub unsafe extern "C" fn yacht_ai_free(ai: *mut YachtAi) {
+    if !ai.is_null() {
+        drop(Box::from_raw(ai));
+    }
+}
+
+/// 推奨するホールド（5要素、0/1）と、振り直しをやめるべきか
+///
+/// # Safety
+/// `ai` / `game` は有効なハンドル、`out_holds` は5要素の配列、`out_stop` は書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_ai_hold_decision(
+    ai: *const YachtAi,
+    game: *const YachtGame,
+    out_holds: *mut u8,
+    out_stop: *mut bool,
+) -> YachtStatus {
+    let ai = try_ref!(ai);
+    let game = try_ref!(game);
+    let out_stop = try_mut!(out_stop);
+    if out_holds.is_null() {
+        return YachtStatus::NullPointer;
+    }
+    if game.0.game_over || game.0.rolls_left() == 0 || game.0.rolls_left() == 3 {
+        return YachtStatus::InvalidState;
+    }
+    let (holds, stop) = match ai.yacht.decide_holds(&game.0) {
+        HoldDecision::Stop => ([true; 5], true),
+        HoldDecision::Reroll(holds) => (holds, false),
+    };
+    ptr::copy_nonoverlapping(holds.map(u8::from).as_ptr(), out_holds, 5);
+    *out_stop = stop;
+    YachtStatus::Ok
+}
+
+/// 推奨するカテゴリ（決まらなければ `InvalidState`）
+///
+/// # Safety
+/// `ai` / `game` は有効なハンドル、`out_category` は書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_ai_category_decision(
+    ai: *const YachtAi,
+    game: *const YachtGame,
+    out_category: *mut u8,
+) -> YachtStatus {
+    let ai = try_ref!(ai);
+    let game = try_ref!(game);
+    let out_category = try_mut!(out_category);
+    if game.0.game_over || game.0.rolls_left() == 3 {
+        return YachtStatus::InvalidState;
+    }
+    let Some(category) = ai.yacht.decide_category(&game.0) else {
+        return YachtStatus::InvalidState;
+    };
+    *out_category = category as u8;
+    YachtStatus::Ok
+}
+
+/// 手番の席のターンをAIが最後まで進める（ヨット以外のルールはそのルールのAIで進める。
+/// ヤッツィーのDPテーブルを読み込む前は InvalidState）
+///
+/// # Safety
+/// `ai` / `game` は有効なハンドルであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_ai_play_turn(
+    ai: *const YachtAi,
+    game: *mut YachtGame,
+) -> YachtStatus {
+    let ai = try_ref!(ai);
+    let game = try_mut!(game);
+    if game.0.game_over {
+        return YachtStatus::InvalidState;
+    }
+    let result = match game.0.ruleset() {
+        Ruleset::Yacht | Ruleset::Generala => ai.yacht.play_turn(&mut game.0),
+        _ => {
+            let mut other = ai.other.borrow_mut();
+            let strategy = match other.take() {
+                Some(strategy) if strategy.check_game(&game.0).is_ok() => strategy,
+                // ハウスルールはここで期待値を計算する
+                _ => match RulesetAI::for_game(&game.0) {
+                    Ok(strategy) => strategy,
+                    Err(_) => return YachtStatus::InvalidState,
+                },
+            };
+            let result = strategy.play_turn(&mut game.0);
+            *other = Some(strategy);
+            result
+        }
+    };
+    status(result.map(|_| ()))
+}
+
+/// カテゴリ選択の候補を期待値の高い順に最大 capacity 件書き込み、件数を out_len に返す
+///
+/// # Safety
+/// `ai` / `game` は有効なハンドル、`out_choices` は capacity 要素の配列、`out_len` は書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_ai_top_category_choices(
+    ai: *const YachtAi,
+    game: *const YachtGame,
+    out_choices: *mut YachtCategoryChoice,
+    capacity: usize,
+    out_len: *mut usize,
+) -> YachtStatus {
+    let ai = try_ref!(ai);
+    let game = try_ref!(game);
+    let out_len = try_mut!(out_len);
+    if out_choices.is_null() && capacity > 0 {
+        return YachtStatus::NullPointer;
+    }
+    let choices = if capacity == 0 {
+        Vec::new()
+    } else {
+        ai.yacht.category_choices(&game.0, capacity)
+    };
+    for (i, c) in choices.iter().enumerate() {
+        out_choices.add(i).write(YachtCategoryChoice {
+            category: c.category as u8,
+            score: c.score,
+            expected: c.expected,
+            loss: c.loss,
+            tied: c.tied,
+        });
+    }
+    *out_len = choices.len();
+    YachtStatus::Ok
+}
+
+/// キープの候補を期待値の高い順に最大 capacity 件書き込み、件数を out_len に返す
+/// （手番の最初に振る前・振り切った後・ゲーム終了後は InvalidState）
+///
+/// # Safety
+/// `ai` / `game` は有効なハンドル、`out_choices` は capacity 要素の配列、`out_len` は書き込み可能なポインタであること
+#[no_mangle]
+pub unsafe extern "C" fn yacht_ai_top_hold_choices(
+    ai: *const YachtAi,
+    game: *const YachtGame,
+    out_choices: *mut YachtHoldChoice,
+    capacity: usize,
+    out_len: *mut usize,
+) -> YachtStatus {
+    let ai = try_ref!(ai);
+    let game = try_ref!(game);
+    let out_len = try_mut!(out_len);
+    if out_choices.is_null() && capacity > 0 {
+        return YachtStatus::NullPointer;
+    }
+    if game.0.game_over || game.0.rolls_left() == 0 || game.0.rolls_left() == 3 {
+        return YachtStatus::InvalidState;
+    }
+    let choices = if capacity == 0 {
+        Vec::new()
+    } else {
+        ai.yacht.hold_choices(&game.0, capacity)
+    };
+    for (i, c) in choices.iter().enumerate() {
+        out_choices.add(i).write(YachtHoldChoice {
+            holds: c.holds.map(u8::from),
+            stop: c.stop,
+            expected: c.expected,
+            loss: c.loss,
+            tied: c.tied,
+        });
+    }
+    *out_len = choices.len();
+    YachtStatus::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi_game() {
+        unsafe {
+            let game = yacht_game_new(1);
+            let ai = yacht_ai_new();
+            assert_eq!(yacht_game_set_seed(game, 3), YachtStatus::Ok);
+            assert_eq!(
+                yacht_game_select_category(game, 0),
+                YachtStatus::InvalidState
+            );
+            assert_eq!(yacht_game_roll(game), YachtStatus::Ok);
+
+            let mut choices = [YachtHoldChoice {
+                holds: [0; 5],
+                stop: false,
+                expected: 0.0,
+                loss: 0.0,
+                tied: false,
+            }; 4];
+            let mut len = 0;
+            assert_eq!(
+                yacht_ai_top_hold_choices(ai, game, choices.as_mut_ptr(), choices.len(), &mut len),
+                YachtStatus::Ok
+            );
+            assert_eq!(len, 4);
+            assert_eq!(choices[0].loss, 0.0);
+            assert_eq!(yacht_game_stand(game), YachtStatus::Ok);
+            assert_eq!(
+                yacht_ai_top_hold_choices(ai, game, choices.as_mut_ptr(), choices.len(), &mut len),
+                YachtStatus::InvalidState
+            );
+
+            let mut category = 0;
+            assert_eq!(
+                yacht_ai_category_decision(ai, game, &mut category),
+                YachtStatus::Ok
+            );
+            assert_eq!(yacht_game_select_category(game, category), YachtStatus::Ok);
+            assert_eq!(
+                yacht_game_select_category(game, 12),
+                YachtStatus::InvalidArgument
+            );
+
+            let (mut score, mut used) = (0, false);
+            assert_eq!(
+                yacht_game_get_score(game, 0, category, &mut score, &mut used),
+                YachtStatus::Ok
+            );
+            assert!(used);
+
+            let mut game_over = false;
+            while !game_over {
+                assert_eq!(yacht_ai_play_turn(ai, game), YachtStatus::Ok);
+                yacht_game_get_turn(game, ptr::null_mut(), ptr::null_mut(), &mut game_over);
+            }
+            assert_eq!(yacht_ai_play_turn(ai, game), YachtStatus::InvalidState);
+            assert_eq!(yacht_game_roll(ptr::null_mut()), YachtStatus::NullPointer);
+
+            yacht_ai_free(ai);
+            yacht_game_free(game);
+        }
+    }
+
+    #[test]
+    fn test_ffi_rulesets() {
+        unsafe {
+            assert!(yacht_game_new_with_ruleset(5, false).is_null());
+            let game = yacht_game_new_with_ruleset(3, true);
+            let ai = yacht_ai_new();
+            yacht_game_set_seed(game, 4);
+            assert_eq!(yacht_game_roll(game), YachtStatus::Ok);
+            // ヤンブの宣言の列（3）は宣言してから記入する
+            assert_eq!(
+                yacht_game_select_cell(game, 3, 0, ptr::null_mut()),
+                YachtStatus::InvalidState
+            );
+            assert_eq!(yacht_game_announce(game, 12), YachtStatus::Ok);
+            let mut score = 0;
+            assert_eq!(
+                yacht_game_select_cell(game, 3, 12, &mut score),
+                YachtStatus::Ok
+            );
+            let (mut cell, mut used) = (0, false);
+            assert_eq!(
+                yacht_game_get_cell(game, 0, 3, 12, &mut cell, &mut used),
+                YachtStatus::Ok
+            );
+            assert!(used);
+            assert_eq!(cell, score);
+
+            let mut game_over = false;
+            while !game_over {
+                assert_eq!(yacht_ai_play_turn(ai, game), YachtStatus::Ok);
+                yacht_game_get_turn(game, ptr::null_mut(), ptr::null_mut(), &mut game_over);
+            }
+            yacht_ai_free(ai);
+            yacht_game_free(game);
+
+            let empty = b"{\"categories\": []}\0";
+            assert!(yacht_game_new_custom(empty.as_ptr() as *const c_char, true).is_null());
+            let chance = b"{\"categories\": [{\"name\": \"Chance\", \"score\": \"sum\"}]}\0";
+            let game = yacht_game_new_custom(chance.as_ptr() as *const c_char, true);
+            assert_eq!(
+                yacht_game_set_dice_weights(game, [1.0].as_ptr(), 1),
+                YachtStatus::InvalidArgument
+            );
+            // d4 なら 20点が上限
+            let d4 = [1.0; 4];
+            assert_eq!(
+                yacht_game_set_dice_weights(game, d4.as_ptr(), d4.len()),
+                YachtStatus::Ok
+            );
+            let ai = yacht_ai_new();
+            assert_eq!(yacht_ai_play_turn(ai, game), YachtStatus::Ok);
+            let (mut score, mut used) = (0, false);
+            yacht_game_get_cell(game, 0, 0, 0, &mut score, &mut used);
+            assert!(used && (5..=20).contains(&score));
+            yacht_game_get_turn(game, ptr::null_mut(), ptr::null_mut(), &mut game_over);
+            assert!(game_over);
+            yacht_ai_free(ai);
+            yacht_game_free(game);
+        }
+    }
+
+    #[test]
+    fn test_header_up_to_date() {
+        assert!(
+            include_str!(concat!(env!("OUT_DIR"), "/yacht_core.h"))
+                == include_str!("../include/yacht_core.h"),
+            "include/yacht_core.h is stale: run tests/c/run.sh --update-header"
+        );
+    }
+
+    #[test]
+    fn test_ffi_enter_roll() {
+        unsafe {
+            let game = yacht_game_new(0);
+            let bad = [1, 2, 3, 4, 7];
+            assert_eq!(
+                yacht_game_enter_roll(game, bad.as_ptr()),
+                YachtStatus::InvalidArgument
+            );
+            assert_eq!(
+                yacht_game_enter_roll(game, ptr::null()),
+                YachtStatus::NullPointer
+            );
+            let values = [3, 3, 3, 1, 2];
+            assert_eq!(
+                yacht_game_enter_roll(game, values.as_ptr()),
+                YachtStatus::Ok
+            );
+            let mut dice = [0u8; 5];
+            yacht_game_get_dice(game, dice.as_mut_ptr());
+            assert_eq!(dice, values);
+            yacht_game_free(game);
+        }
+    }
+}
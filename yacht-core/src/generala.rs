@@ -0,0 +1,406 @@
+//! ヘネラル（Generala）
+//!
+//! 南米で遊ばれるルールで、カテゴリはヨットの `Category` の一部を読み替えて使う:
+//! 1-6の目、エスカレラ（`BigStraight`、1-5 / 2-6 / 3-6+1 のストレート）、フル（`FullHouse`）、
+//! ポーカー（`FourOfAKind`）、ヘネラル（`Yacht`、5個同じ目）。`LittleStraight` と `Choice` は使わず、
+//! 上段ボーナスもない。
+//!
+//! エスカレラ・フル・ポーカーは手番の1回目のロールで揃う（セルビーダ）と5点多い。
+//! 1回目のロールでヘネラルが揃うと、その手番の記入でゲームに即勝利する（`GameState::winner`）。
+//! 状態は使用済みカテゴリだけ（上段ボーナスがない）なので、期待値のテーブルは初回に計算する。
+
+use std::sync::OnceLock;
+
+use crate::dp_table::{self, DicePattern};
+use crate::play::Strategy;
+use crate::solver::{self, keep_to_holds, Leaf, TurnSolver};
+use crate::{Category, Cell, GameError, GameState, HoldDecision, Ruleset};
+
+/// ヘネラルで使うカテゴリ
+pub const CATEGORIES: [Category; 10] = [
+    Category::Ones,
+    Category::Twos,
+    Category::Threes,
+    Category::Fours,
+    Category::Fives,
+    Category::Sixes,
+    Category::BigStraight,
+    Category::FullHouse,
+    Category::FourOfAKind,
+    Category::Yacht,
+];
+
+/// セルビーダ（1回目のロール）の加点
+const SERVED_BONUS: u8 = 5;
+
+/// ヘネラルでの表示名
+pub fn category_name(category: Category) -> &'static str {
+    match category {
+        Category::BigStraight => "Straight",
+        Category::FullHouse => "Full",
+        Category::FourOfAKind => "Poker",
+        Category::Yacht => "Generala",
+        _ => category.name(),
+    }
+}
+
+/// ヘネラルでの日本語の表示名
+pub fn category_name_ja(category: Category) -> &'static str {
+    match category {
+        Category::BigStraight => "エスカレラ",
+        Category::FullHouse => "フル",
+        Category::FourOfAKind => "ポーカー",
+        Category::Yacht => "ヘネラル",
+        _ => category.name_ja(),
+    }
+}
+
+/// 出目パターンからヘネラルの得点を計算（first_roll: 手番の1回目のロールの出目か）
+fn pattern_score(p: &DicePattern, category: Category, first_roll: bool) -> u8 {
+    let served = if first_roll { SERVED_BONUS } else { 0 };
+    match category {
+        Category::Ones
+        | Category::Twos
+        | Category::Threes
+        | Category::Fours
+        | Category::Fives
+        | Category::Sixes => {
+            let face = category as usize;
+            p[face] * (face as u8 + 1)
+        }
+        // 1-5 か 2-6、または 3-6 と 1
+        Category::BigStraight
+            if dp_table::has_big_straight(p) || [0, 2, 3, 4, 5].iter().all(|&f| p[f] == 1) =>
+        {
+            20 + served
+        }
+        Category::FullHouse if p.contains(&3) && p.contains(&2) => 30 + served,
+        Category::FourOfAKind if p.iter().any(|&c| c >= 4) => 40 + served,
+        Category::Yacht if p.contains(&5) => 50,
+        _ => 0,
+    }
+}
+
+/// 得点計算（サイコロ値配列版、ヘネラルで使わないカテゴリは 0）
+pub fn calculate_generala_score(dice: &[u8; 5], category: Category, first_roll: bool) -> u8 {
+    pattern_score(&dp_table::dice_to_pattern(dice), category, first_roll)
+}
+
+/// 1回目のロールで揃ったヘネラル（即勝利）か
+pub fn is_served_generala(dice: &[u8; 5], first_roll: bool) -> bool {
+    first_roll && dice.iter().all(|&d| d == dice[0])
+}
+
+fn category_mask() -> usize {
+    CATEGORIES.iter().fold(0, |mask, &c| mask | 1 << c as usize)
+}
+
+/// 使用済みカテゴリ（`ScoreBoard::used_hands_mask`）ごとの残りの期待得点
+///
+/// 即勝利は手番の最初のロールだけで決まり、どの判断にも影響しないので、普通の得点として数える。
+fn table() -> &'static [f32] {
+    static TABLE: OnceLock<Vec<f32>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let all = category_mask();
+        let mut table = vec![0.0f32; all + 1];
+        for used in solver::used_sets_descending(all) {
+            let leaf = GeneralaLeaf {
+                table: &table,
+                used_hands: used,
+            };
+            table[used] = TurnSolver::new(leaf).turn_value(3);
+        }
+        table
+    })
+}
+
+/// 手番の開始時点から最適に続けた場合の残りの期待得点
+pub fn expected_score(used_hands: usize) -> f32 {
+    table().get(used_hands).copied().unwrap_or(0.0)
+}
+
+struct GeneralaLeaf<'a> {
+    table: &'a [f32],
+    used_hands: usize,
+}
+
+impl GeneralaLeaf<'_> {
+    fn best(&self, dice: &DicePattern, first_roll: bool) -> Option<(usize, f32)> {
+        let mut best: Option<(usize, f32)> = None;
+        for cat in CATEGORIES.iter().map(|&c| c as usize) {
+            if (self.used_hands >> cat) & 1 == 1 {
+                continue;
+            }
+            let category = Category::from_index(cat)?;
+            let score = pattern_score(dice, category, first_roll) as f32;
+            let value = score + self.table[self.used_hands | 1 << cat];
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((cat, value));
+            }
+        }
+        best
+    }
+}
+
+impl Leaf for GeneralaLeaf<'_> {
+    fn num_dice(&self) -> usize {
+        5
+    }
+
+    fn best_category(&self, dice: &DicePattern, rolls_left: u8) -> Option<(usize, f32)> {
+        // 手番の3回のうち残りが2回 = 1回目のロールの出目
+        self.best(dice, rolls_left == 2)
+    }
+}
+
+/// 使用済みカテゴリを固定した1手番の期待値（ヒントと運・実力の分解に使う）
+///
+/// 値は全て記入前の残りの期待得点で、`GeneralaAI` と同じくホールドは自由ルールを前提とする。
+pub(crate) struct GeneralaTurn {
+    solver: TurnSolver<GeneralaLeaf<'static>>,
+    used_hands: usize,
+}
+
+impl GeneralaTurn {
+    pub fn new(used_hands: usize) -> GeneralaTurn {
+        GeneralaTurn {
+            solver: TurnSolver::new(GeneralaLeaf {
+                table: table(),
+                used_hands,
+            }),
+            used_hands,
+        }
+    }
+
+    /// category に score 点を記入した場合の価値
+    pub fn category_value(&self, category: Category, score: u8) -> f32 {
+        score as f32 + table()[self.used_hands | 1 << category as usize]
+    }
+
+    /// 空いているカテゴリごとの (カテゴリ, 得点, 記入した場合の価値)
+    pub fn category_values(&self, dice: &[u8; 5], first_roll: bool) -> Vec<(Category, u8, f32)> {
+        CATEGORIES
+            .iter()
+            .filter(|&&c| (self.used_hands >> c as usize) & 1 == 0)
+            .map(|&c| {
+                let score = calculate_generala_score(dice, c, first_roll);
+                (c, score, self.category_value(c, score))
+            })
+            .collect()
+    }
+
+    /// 今の出目で最良のカテゴリに記入する価値
+    pub fn stand_value(&self, dice: &DicePattern, first_roll: bool) -> f32 {
+        GeneralaLeaf {
+            table: table(),
+            used_hands: self.used_hands,
+        }
+        .best(dice, first_roll)
+        .map_or(0.0, |(_, v)| v)
+    }
+
+    /// 手番中（あと rolls_left 回振れる）の状態から最適に続けた場合の価値
+    /// first_roll: 今の出目が手番の1回目のロールか（rolls_left が 0 のときだけ使う）
+    pub fn live_value(&mut self, dice: &DicePattern, rolls_left: u8, first_roll: bool) -> f32 {
+        match rolls_left {
+            3 => table()[self.used_hands],
+            0 => self.stand_value(dice, first_roll),
+            _ => self.solver.value_after_roll(dice, rolls_left),
+        }
+    }
+
+    /// keep を残して振る価値（全てキープなら振らずに記入する）
+    pub fn keep_value(&mut self, keep: &DicePattern, rolls_left: u8) -> f32 {
+        if dp_table::pattern_count(keep) == 5 {
+            self.stand_value(keep, rolls_left == 2)
+        } else {
+            self.solver.value_of_keep(keep, rolls_left)
+        }
+    }
+}
+
+/// ヘネラルの最適AI（ホールドは自由ルールを前提とする）
+#[derive(Clone, Debug, Default)]
+pub struct GeneralaAI {}
+
+impl GeneralaAI {
+    pub fn new() -> GeneralaAI {
+        GeneralaAI {}
+    }
+
+    fn leaf(game: &GameState) -> GeneralaLeaf<'static> {
+        GeneralaLeaf {
+            table: table(),
+            used_hands: game.current_board().used_hands_mask(),
+        }
+    }
+
+    /// どのダイスをホールドして振り直すか、振り直さずに得点するかを決める
+    pub fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        let dice = game.dice().values();
+        let pattern = dp_table::dice_to_pattern(&dice);
+        let mut solver = TurnSolver::new(Self::leaf(game));
+        match solver.best_keep(&pattern, game.rolls_left()) {
+            Some((keep, _)) => HoldDecision::Reroll(keep_to_holds(&dice, &keep)),
+            None => HoldDecision::Stop,
+        }
+    }
+
+    /// 現在の出目を記入するカテゴリを決める（セルビーダの加点も考える）
+    pub fn decide_category(&self, game: &GameState) -> Option<Category> {
+        let pattern = dp_table::dice_to_pattern(&game.dice().values());
+        let (cat, _) = Self::leaf(game).best(&pattern, game.rolls_this_turn() == 1)?;
+        Category::from_index(cat)
+    }
+}
+
+impl Strategy<GameState> for GeneralaAI {
+    fn check_game(&self, game: &GameState) -> Result<(), GameError> {
+        game.check_ruleset(Ruleset::Generala)
+    }
+
+    fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        GeneralaAI::decide_holds(self, game)
+    }
+
+    fn decide_cell(&self, game: &GameState) -> Option<Cell> {
+        self.decide_category(game).map(Cell::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::YachtAI;
+
+    #[test]
+    fn test_generala_scores() {
+        assert_eq!(
+            calculate_generala_score(&[3, 4, 5, 6, 1], Category::BigStraight, false),
+            20
+        );
+        assert_eq!(
+            calculate_generala_score(&[3, 4, 5, 6, 1], Category::BigStraight, true),
+            25
+        );
+        assert_eq!(
+            calculate_generala_score(&[2, 3, 4, 5, 6], Category::BigStraight, false),
+            20
+        );
+        assert_eq!(
+            calculate_generala_score(&[1, 2, 4, 5, 6], Category::BigStraight, false),
+            0
+        );
+        assert_eq!(
+            calculate_generala_score(&[2, 2, 5, 5, 5], Category::FullHouse, true),
+            35
+        );
+        assert_eq!(
+            calculate_generala_score(&[5, 5, 5, 5, 5], Category::FourOfAKind, false),
+            40
+        );
+        assert_eq!(
+            calculate_generala_score(&[5, 5, 5, 5, 5], Category::Yacht, true),
+            50
+        );
+        assert_eq!(
+            calculate_generala_score(&[5, 5, 5, 5, 1], Category::Fives, true),
+            20
+        );
+        assert!(is_served_generala(&[4; 5], true));
+        assert!(!is_served_generala(&[4; 5], false));
+    }
+
+    #[test]
+    fn test_served_scoring_in_game() {
+        let mut game = GameState::with_ruleset(Ruleset::Generala);
+        game.enter_roll([2, 2, 5, 5, 5]).unwrap();
+        assert_eq!(game.rolls_this_turn(), 1);
+        assert_eq!(game.potential_score(Category::FullHouse), 35);
+        game.stand().unwrap();
+        // 振り直しをやめても1回目の出目のまま
+        assert_eq!(game.potential_score(Category::FullHouse), 35);
+        assert_eq!(
+            game.select(Category::Choice),
+            Err(GameError::CategoryUnavailable)
+        );
+        assert_eq!(game.select(Category::FullHouse), Ok(35));
+
+        game.enter_roll([1, 2, 3, 3, 3]).unwrap();
+        game.enter_roll([2, 2, 3, 3, 3]).unwrap();
+        assert_eq!(game.select(Category::FullHouse), Ok(30));
+        assert!(!game.is_game_over());
+        assert_eq!(game.board(0).upper_bonus(), 0);
+    }
+
+    #[test]
+    fn test_served_generala_wins() {
+        let mut game = GameState::with_ruleset(Ruleset::Generala);
+        game.enter_roll([1, 2, 3, 4, 6]).unwrap();
+        game.select(Category::Ones).unwrap();
+        // 2回目のロールで揃ったヘネラルは普通の 50点
+        game.enter_roll([6, 6, 6, 6, 1]).unwrap();
+        game.enter_roll([6, 6, 6, 6, 6]).unwrap();
+        game.select(Category::Yacht).unwrap();
+        assert_eq!(game.winner(), None);
+
+        game.enter_roll([3; 5]).unwrap();
+        game.select(Category::Threes).unwrap();
+        assert!(game.is_game_over());
+        assert_eq!(game.winner(), Some(0));
+        assert_eq!(game.roll(), Err(GameError::GameOver));
+    }
+
+    #[test]
+    fn test_ai_values_served_hands() {
+        let expected = expected_score(0);
+        assert!(expected > 150.0 && expected < 250.0, "{}", expected);
+        assert!(table().iter().all(|v| v.is_finite()));
+
+        let ai = GeneralaAI::new();
+        // 1回目で揃ったフルは振り直さずに加点を取る
+        let mut game = GameState::with_ruleset(Ruleset::Generala);
+        game.enter_roll([2, 2, 6, 6, 6]).unwrap();
+        assert_eq!(ai.decide_holds(&game), HoldDecision::Stop);
+        assert_eq!(ai.decide_category(&game), Some(Category::FullHouse));
+
+        let mut game = GameState::with_ruleset(Ruleset::Generala);
+        game.set_seed(5);
+        while !game.is_game_over() {
+            ai.play_turn(&mut game).unwrap();
+        }
+        assert!(game.board(0).is_complete() || game.winner().is_some());
+        assert_eq!(
+            ai.play_turn(&mut GameState::new()).unwrap_err(),
+            GameError::CategoryUnavailable
+        );
+    }
+
+    #[test]
+    fn test_yacht_ai_plays_generala() {
+        let ai = YachtAI::new();
+        let mut game = GameState::with_ruleset(Ruleset::Generala);
+        game.enter_roll([2, 2, 6, 6, 6]).unwrap();
+        assert_eq!(ai.decide_holds(&game), HoldDecision::Stop);
+        assert_eq!(ai.decide_category(&game), Some(Category::FullHouse));
+
+        // ヒントもヘネラルの得点と期待値で出す
+        let choices = ai.category_choices(&game, 0);
+        assert_eq!(choices.len(), CATEGORIES.len());
+        assert_eq!(choices[0].category, Category::FullHouse);
+        assert_eq!(choices[0].score, 35);
+        assert!((ai.live_expected_score(&game) - choices[0].expected).abs() < 1e-3);
+        assert!(ai.hold_choices(&game, 1)[0].stop);
+        game.enter_roll([2, 2, 6, 6, 1]).unwrap();
+        game.enter_roll([2, 2, 6, 6, 3]).unwrap();
+        assert_eq!(game.rolls_left(), 0);
+        assert!(ai.hold_choices(&game, 0).is_empty());
+
+        let mut game = GameState::with_ruleset(Ruleset::Generala);
+        game.set_seed(5);
+        while !game.is_game_over() {
+            let log = ai.play_turn(&mut game).unwrap();
+            assert!(CATEGORIES.iter().any(|&c| Cell::from(c) == log.cell));
+        }
+    }
+}
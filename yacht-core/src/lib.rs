@@ -1,11 +1,48 @@
-use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 use rand::Rng;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
 
+mod ai_turn;
+pub mod assist;
+pub mod audit;
+pub mod custom;
+pub mod dice_model;
 mod dp_table;
+pub mod env;
+pub mod fair;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod generala;
+pub mod maxi;
+pub mod play;
+#[cfg(feature = "python")]
+mod python;
+pub mod scorekeeper;
+#[cfg(feature = "server")]
+pub mod server;
+mod solver;
+pub mod triple;
+pub mod turn;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod yamb;
+pub mod yatzy;
+
+pub use ai_turn::{AiStep, AiStepKind, AiTurn};
+pub use dp_table::DpTableError;
+pub use play::{TurnLog, TurnRoll};
+pub use turn::Turn;
+
+use dice_model::DiceModel;
+use generala::{GeneralaAI, GeneralaTurn};
+use turn::RollRecord;
 
 // ヨットの役（カテゴリ）
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Category {
     Ones = 0,
@@ -45,121 +82,601 @@ impl Category {
     pub fn from_index(index: usize) -> Option<Category> {
         Self::ALL.get(index).copied()
     }
+
+    /// 英語の表示名
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Ones => "Ones",
+            Category::Twos => "Twos",
+            Category::Threes => "Threes",
+            Category::Fours => "Fours",
+            Category::Fives => "Fives",
+            Category::Sixes => "Sixes",
+            Category::FullHouse => "Full House",
+            Category::FourOfAKind => "Four of a Kind",
+            Category::LittleStraight => "Little Straight",
+            Category::BigStraight => "Big Straight",
+            Category::Choice => "Choice",
+            Category::Yacht => "Yacht",
+        }
+    }
+
+    /// 日本語の表示名
+    pub fn name_ja(self) -> &'static str {
+        match self {
+            Category::Ones => "1の目",
+            Category::Twos => "2の目",
+            Category::Threes => "3の目",
+            Category::Fours => "4の目",
+            Category::Fives => "5の目",
+            Category::Sixes => "6の目",
+            Category::FullHouse => "フルハウス",
+            Category::FourOfAKind => "フォーオブアカインド",
+            Category::LittleStraight => "スモールストレート",
+            Category::BigStraight => "ビッグストレート",
+            Category::Choice => "チョイス",
+            Category::Yacht => "ヨット",
+        }
+    }
+}
+
+// ホールドのルール
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HoldRule {
+    /// 標準ルール: ロールごとに自由にホールドし直せる
+    Free = 0,
+    /// ハウスルール: ホールドしたまま振ったダイスはその手番の間ロックされる
+    #[default]
+    Locking = 1,
+}
+
+/// 得点表の欄（列と行）
+///
+/// 1列のルールの列は 0。ヨット・ヘネラル・トリプルヨットの行は `Category` の番号。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "CellRepr")]
+pub struct Cell {
+    pub column: usize,
+    pub row: usize,
+}
+
+impl Cell {
+    pub fn new(column: usize, row: usize) -> Cell {
+        Cell { column, row }
+    }
+}
+
+impl From<Category> for Cell {
+    fn from(category: Category) -> Cell {
+        Cell::new(0, category as usize)
+    }
+}
+
+/// 欄の JSON（以前の記録のカテゴリ名だけの欄も読む）
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CellRepr {
+    Cell { column: usize, row: usize },
+    Category(Category),
+}
+
+impl From<CellRepr> for Cell {
+    fn from(repr: CellRepr) -> Cell {
+        match repr {
+            CellRepr::Cell { column, row } => Cell::new(column, row),
+            CellRepr::Category(category) => Cell::from(category),
+        }
+    }
+}
+
+/// 得点ルール（得点表は列 × 行。ルールごとに使う欄、記入順、得点計算が異なる）
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ruleset {
+    /// ヨット（12カテゴリ、上段ボーナス35点）
+    #[default]
+    Yacht,
+    /// ヘネラル（10カテゴリ、1回目のロールの役に加点、`generala` 参照）
+    Generala,
+    /// トリプルヨット（ヨットの12カテゴリ × 重みの違う3列、`triple` 参照）
+    Triple,
+    /// ヤンブ（13行 × 記入順の違う4列、宣言の列あり、`yamb` 参照）
+    Yamb,
+    /// 北欧式ヤッツィー（15カテゴリ、上段ボーナス50点、`yatzy` 参照）
+    Yatzy,
+    /// ハウスルール（実行時に定義したカテゴリ、`custom` 参照）
+    Custom(Arc<custom::CustomRuleset>),
+}
+
+impl Ruleset {
+    pub const ALL: [Ruleset; 5] = [
+        Ruleset::Yacht,
+        Ruleset::Generala,
+        Ruleset::Triple,
+        Ruleset::Yamb,
+        Ruleset::Yatzy,
+    ];
+
+    /// バインディングで使う名前（"yacht" / "generala" / "triple" / "yamb" / "yatzy" / "custom"）
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ruleset::Yacht => "yacht",
+            Ruleset::Generala => "generala",
+            Ruleset::Triple => "triple",
+            Ruleset::Yamb => "yamb",
+            Ruleset::Yatzy => "yatzy",
+            Ruleset::Custom(_) => "custom",
+        }
+    }
+
+    /// 名前の決まったルール（ハウスルールは含まない）
+    pub fn from_name(name: &str) -> Option<Ruleset> {
+        Self::ALL.into_iter().find(|r| r.name() == name)
+    }
+
+    /// 得点表の列の数
+    pub fn columns(&self) -> usize {
+        match self {
+            Ruleset::Yacht | Ruleset::Generala | Ruleset::Yatzy | Ruleset::Custom(_) => 1,
+            Ruleset::Triple => triple::NUM_COLUMNS,
+            Ruleset::Yamb => yamb::NUM_COLUMNS,
+        }
+    }
+
+    /// 得点表の行の数（このルールで使わない行も含む）
+    pub fn rows(&self) -> usize {
+        match self {
+            Ruleset::Yacht | Ruleset::Generala | Ruleset::Triple => Category::ALL.len(),
+            Ruleset::Yamb => yamb::NUM_ROWS,
+            Ruleset::Yatzy => yatzy::YatzyCategory::ALL.len(),
+            Ruleset::Custom(rules) => rules.categories.len(),
+        }
+    }
+
+    /// このルールで使うカテゴリ（行が `Category` でないルールは空）
+    pub fn categories(&self) -> &'static [Category] {
+        match self {
+            Ruleset::Yacht | Ruleset::Triple => &Category::ALL,
+            Ruleset::Generala => &generala::CATEGORIES,
+            Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => &[],
+        }
+    }
+
+    pub fn has_category(&self, category: Category) -> bool {
+        self.categories().contains(&category)
+    }
+
+    /// このルールで使う欄か
+    pub fn has_cell(&self, cell: Cell) -> bool {
+        cell.column < self.columns()
+            && match self {
+                Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => cell.row < self.rows(),
+                _ => Category::from_index(cell.row).is_some_and(|c| self.has_category(c)),
+            }
+    }
+
+    /// 行の英語の表示名
+    pub fn row_name(&self, row: usize) -> &str {
+        match self {
+            Ruleset::Yacht | Ruleset::Triple => {
+                Category::from_index(row).map_or("", Category::name)
+            }
+            Ruleset::Generala => Category::from_index(row).map_or("", generala::category_name),
+            Ruleset::Yamb => yamb::YambRow::from_index(row).map_or("", yamb::YambRow::name),
+            Ruleset::Yatzy => {
+                yatzy::YatzyCategory::from_index(row).map_or("", yatzy::YatzyCategory::name)
+            }
+            Ruleset::Custom(rules) => rules.categories.get(row).map_or("", |c| c.name.as_str()),
+        }
+    }
+
+    /// 手番の roll_number 回目（1 から数える）のロールの出目で得点を計算する
+    pub fn score(&self, dice: &[u8; 5], category: Category, roll_number: u8) -> u8 {
+        self.score_row(dice, category as usize, roll_number)
+    }
+
+    /// 行 row に記入する得点（どの列でも同じ。列の重みは合計でかける）
+    pub fn score_row(&self, dice: &[u8; 5], row: usize, roll_number: u8) -> u8 {
+        match self {
+            Ruleset::Yacht | Ruleset::Triple => {
+                Category::from_index(row).map_or(0, |c| calculate_score(dice, c))
+            }
+            Ruleset::Generala => Category::from_index(row).map_or(0, |c| {
+                generala::calculate_generala_score(dice, c, roll_number == 1)
+            }),
+            Ruleset::Yamb => yamb::YambRow::from_index(row)
+                .map_or(0, |r| yamb::calculate_yamb_score(dice, r, roll_number)),
+            Ruleset::Yatzy => yatzy::YatzyCategory::from_index(row)
+                .map_or(0, |c| yatzy::calculate_yatzy_score(dice, c)),
+            Ruleset::Custom(rules) => rules.score(dice, row).unwrap_or(0),
+        }
+    }
+
+    /// 記入した時点でゲームに即勝利する出目か
+    pub fn is_instant_win(&self, dice: &[u8; 5], roll_number: u8) -> bool {
+        match self {
+            Ruleset::Generala => generala::is_served_generala(dice, roll_number == 1),
+            _ => false,
+        }
+    }
+
+    /// このルールの標準のホールド（ヨットはロック、それ以外は自由）
+    pub fn default_hold_rule(&self) -> HoldRule {
+        match self {
+            Ruleset::Yacht => HoldRule::Locking,
+            _ => HoldRule::Free,
+        }
+    }
+
+    /// model のダイスで遊べるか（標準以外のダイスはハウスルールだけ）
+    pub fn supports_dice(&self, model: &DiceModel) -> bool {
+        model.is_standard() || matches!(self, Ruleset::Custom(_))
+    }
+
+    /// 宣言してから記入する列
+    pub fn announce_column(&self) -> Option<usize> {
+        match self {
+            Ruleset::Yamb => Some(yamb::YambColumn::Announce as usize),
+            _ => None,
+        }
+    }
+
+    /// 上段（1-6の目、ハウスルールは `upper` のカテゴリ）の行か
+    fn is_upper(&self, row: usize) -> bool {
+        match self {
+            Ruleset::Custom(rules) => rules.categories.get(row).is_some_and(|c| c.upper),
+            _ => row < 6,
+        }
+    }
+
+    /// 期待値の状態に使う上段累計の上限（ボーナスの閾値）
+    fn upper_sum_cap(&self) -> usize {
+        match self {
+            Ruleset::Custom(rules) => rules.upper_bonus.map_or(0, |b| b.threshold as usize),
+            _ => dp_table::UPPER_BONUS_THRESHOLD,
+        }
+    }
+
+    /// 列の上段の合計（cells はその列の行ごとの得点）
+    fn upper_total(&self, cells: &[Option<u8>]) -> u16 {
+        cells
+            .iter()
+            .enumerate()
+            .filter(|&(row, _)| self.is_upper(row))
+            .filter_map(|(_, s)| *s)
+            .map(u16::from)
+            .sum()
+    }
+
+    /// 上段の合計に対するボーナス
+    fn upper_bonus(&self, upper_total: u16) -> u16 {
+        let (threshold, points) = match self {
+            Ruleset::Yacht | Ruleset::Triple => (
+                dp_table::UPPER_BONUS_THRESHOLD as u16,
+                dp_table::UPPER_BONUS_POINTS as u16,
+            ),
+            Ruleset::Generala => return 0,
+            Ruleset::Yamb => (yamb::UPPER_BONUS_THRESHOLD, yamb::UPPER_BONUS_POINTS),
+            Ruleset::Yatzy => (
+                dp_table::UPPER_BONUS_THRESHOLD as u16,
+                dp_table::yatzy_category::UPPER_BONUS_POINTS as u16,
+            ),
+            Ruleset::Custom(rules) => match rules.upper_bonus {
+                Some(bonus) => (bonus.threshold as u16, bonus.points as u16),
+                None => return 0,
+            },
+        };
+        if upper_total >= threshold {
+            points
+        } else {
+            0
+        }
+    }
+
+    /// 列の上段以外の得点（ヤンブは中段の計算を含む）
+    fn lower_total(&self, cells: &[Option<u8>]) -> u16 {
+        match self {
+            Ruleset::Yamb => yamb::middle_total(cells) + yamb::lower_total(cells),
+            _ => cells
+                .iter()
+                .enumerate()
+                .filter(|&(row, _)| !self.is_upper(row))
+                .filter_map(|(_, s)| *s)
+                .map(u16::from)
+                .sum(),
+        }
+    }
+
+    /// 列の合計（重みのある列は重みをかける）
+    fn column_total(&self, column: usize, cells: &[Option<u8>]) -> u16 {
+        let upper = self.upper_total(cells);
+        let weight = match self {
+            Ruleset::Triple => triple::COLUMN_WEIGHTS[column],
+            _ => 1,
+        };
+        (upper + self.upper_bonus(upper) + self.lower_total(cells)) * weight
+    }
+
+    /// 列の記入順で row が今埋められるか（cells はその列の行ごとの得点）
+    fn is_open(&self, column: usize, row: usize, cells: &[Option<u8>]) -> bool {
+        match self {
+            Ruleset::Yamb => yamb::is_open(column, row, cells),
+            _ => true,
+        }
+    }
+}
+
+/// ゲーム操作のエラー
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameError {
+    /// ゲームは終了している
+    GameOver,
+    /// この手番ではもう振れない
+    NoRollsLeft,
+    /// この手番ではまだ振っていない
+    NotRolled,
+    /// そのカテゴリは使用済み
+    CategoryUsed,
+    /// ロックされたダイスはホールドを外せない
+    DieLocked,
+    /// ダイスのインデックスが範囲外
+    InvalidDie,
+    /// 入力した出目がダイスの目にない（d6 なら 1-6 ではない）
+    InvalidDieValue,
+    /// ホールドしたダイスの出目が変わっている
+    HeldDieChanged,
+    /// そのカテゴリはこのルールでは使わない
+    CategoryUnavailable,
+    /// 列の記入順でまだ埋められない欄
+    OutOfOrder,
+    /// 宣言していない（宣言の列に記入する、または宣言の列しか残っていないのに振る）
+    NotAnnounced,
+    /// 宣言した欄以外に記入しようとした
+    AnnouncementMismatch,
+    /// 宣言できるのは手番の1回目のロールの直後だけ
+    AnnounceTooLate,
+    /// AI が記入する欄を決められなかった（ゲームの途中で空いた欄が見つからない）
+    NoCellChosen,
+    /// このルールは標準の d6 でしか遊べない（DPテーブル・AI を d6 で事前計算している）
+    UnsupportedDice,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            GameError::GameOver => "game is over",
+            GameError::NoRollsLeft => "no rolls left this turn",
+            GameError::NotRolled => "dice have not been rolled this turn",
+            GameError::CategoryUsed => "category is already used",
+            GameError::DieLocked => "die is locked",
+            GameError::InvalidDie => "die index out of range",
+            GameError::InvalidDieValue => "die value is not a face of the dice",
+            GameError::HeldDieChanged => "held die value changed",
+            GameError::CategoryUnavailable => "category is not used in these rules",
+            GameError::OutOfOrder => "cell is not open yet in this column's order",
+            GameError::NotAnnounced => "no cell has been announced this turn",
+            GameError::AnnouncementMismatch => "must score the announced cell",
+            GameError::AnnounceTooLate => "can only announce right after the first roll",
+            GameError::NoCellChosen => "the AI could not choose a cell to score",
+            GameError::UnsupportedDice => "these rules are only played with standard dice",
+        };
+        f.write_str(message)
+    }
 }
 
-// サイコロの状態
-#[wasm_bindgen]
+impl std::error::Error for GameError {}
+
+// サイコロの状態（N はダイスの個数。マキシヤッツィーは6個）
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Dice {
-    values: [u8; 5],
-    held: [bool; 5],
-    locked: [bool; 5],  // ロール時に確定したキープ（解除不可）
+pub struct Dice<const N: usize = 5> {
+    #[serde(with = "serde_array")]
+    values: [u8; N],
+    #[serde(with = "serde_array")]
+    held: [bool; N],
+    #[serde(with = "serde_array")]
+    locked: [bool; N], // ロール時に確定したキープ（解除不可）
+    hold_rule: HoldRule,
 }
 
-#[wasm_bindgen]
-impl Dice {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Dice {
+impl<const N: usize> Dice<N> {
+    pub fn new() -> Dice<N> {
+        Dice::with_hold_rule(HoldRule::default())
+    }
+
+    pub fn with_hold_rule(hold_rule: HoldRule) -> Dice<N> {
         Dice {
-            values: [1, 1, 1, 1, 1],
-            held: [false; 5],
-            locked: [false; 5],
+            values: [1; N],
+            held: [false; N],
+            locked: [false; N],
+            hold_rule,
         }
     }
 
+    pub fn hold_rule(&self) -> HoldRule {
+        self.hold_rule
+    }
+
     pub fn roll(&mut self) {
-        let mut rng = rand::thread_rng();
-        // ロール時にheldをlockedに確定
-        for i in 0..5 {
-            if self.held[i] {
-                self.locked[i] = true;
+        self.roll_with(&mut rand::thread_rng());
+    }
+
+    /// 指定した乱数生成器で振る
+    pub fn roll_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.roll_model(DiceModel::standard(), rng);
+    }
+
+    /// model のダイス（d8 や偏ったダイス）として振る
+    pub fn roll_model<R: Rng + ?Sized>(&mut self, model: &DiceModel, rng: &mut R) {
+        self.lock_held();
+        for i in 0..N {
+            if !self.held[i] {
+                self.values[i] = model.roll(rng);
             }
         }
-        for i in 0..5 {
+    }
+
+    /// 決まった出目で振る（ホールドしていない位置 i に fresh[i] が出る）
+    pub fn roll_from(&mut self, fresh: [u8; N]) {
+        self.lock_held();
+        for (i, value) in fresh.into_iter().enumerate() {
             if !self.held[i] {
-                self.values[i] = rng.gen_range(1..=6);
+                self.values[i] = value;
+            }
+        }
+    }
+
+    // ロックするルールではロール時にheldをlockedに確定
+    fn lock_held(&mut self) {
+        if self.hold_rule == HoldRule::Locking {
+            for i in 0..N {
+                if self.held[i] {
+                    self.locked[i] = true;
+                }
             }
         }
     }
 
     pub fn set_hold(&mut self, index: usize, hold: bool) {
-        if index < 5 {
+        if index < N {
             self.held[index] = hold;
         }
     }
 
     pub fn toggle_hold(&mut self, index: usize) {
-        if index < 5 && !self.locked[index] {
+        if index < N && !self.locked[index] {
             self.held[index] = !self.held[index];
         }
     }
 
     pub fn reset_holds(&mut self) {
-        self.held = [false; 5];
-        self.locked = [false; 5];
+        self.held = [false; N];
+        self.locked = [false; N];
     }
 
     pub fn is_locked(&self, index: usize) -> bool {
         self.locked[index]
     }
 
-    pub fn get_locks(&self) -> Vec<u8> {
-        self.locked.iter().map(|&l| if l { 1 } else { 0 }).collect()
+    pub fn is_held(&self, index: usize) -> bool {
+        self.held[index]
     }
 
-    pub fn get_value(&self, index: usize) -> u8 {
-        self.values[index]
+    pub fn values(&self) -> [u8; N] {
+        self.values
     }
 
-    pub fn get_values(&self) -> Vec<u8> {
-        self.values.to_vec()
+    pub fn held(&self) -> [bool; N] {
+        self.held
     }
 
-    pub fn is_held(&self, index: usize) -> bool {
-        self.held[index]
+    pub fn locked(&self) -> [bool; N] {
+        self.locked
     }
 
-    pub fn get_holds(&self) -> Vec<u8> {
-        self.held.iter().map(|&h| if h { 1 } else { 0 }).collect()
-    }
-
-    pub fn set_values(&mut self, values: Vec<u8>) {
-        for (i, v) in values.iter().enumerate() {
-            if i < 5 {
-                self.values[i] = *v;
-            }
-        }
+    pub fn set_values(&mut self, values: [u8; N]) {
+        self.values = values;
     }
 }
 
-impl Default for Dice {
+impl<const N: usize> Default for Dice<N> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// 長さが型で決まる配列の読み書き（serde の derive は任意長の配列を扱えないので列として扱う）
+mod serde_array {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, T, const N: usize>(
+        values: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serializer.collect_seq(values)
+    }
+
+    pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let values = Vec::<T>::deserialize(deserializer)?;
+        let len = values.len();
+        values
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(len, &format!("{N} values").as_str()))
+    }
+}
+
 /// 得点計算（サイコロ値配列版）
 pub fn calculate_score(dice: &[u8; 5], category: Category) -> u8 {
     let pattern = dp_table::dice_to_pattern(dice);
     dp_table::calculate_score(&pattern, category as usize)
 }
 
-// プレイヤーのスコアボード
-#[wasm_bindgen]
+/// 記録から読んだ出目が d6 の目か（書き換えられた記録を再生しない）
+pub(crate) fn is_valid_dice(dice: &[u8; 5]) -> bool {
+    dice.iter().all(|d| (1..=6).contains(d))
+}
+
+// プレイヤーのスコアボード（列 × 行。1列のルールは列 0 だけ）
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "RawScoreBoard")]
 pub struct ScoreBoard {
-    scores: [Option<u8>; 12],
+    /// 列ごとに行を並べた得点（1列のルールの JSON は従来どおり行の配列）
+    scores: Vec<Option<u8>>,
+    #[serde(default)]
+    ruleset: Ruleset,
+}
+
+/// 欄の数を確かめる前の得点表の JSON
+#[derive(Deserialize)]
+struct RawScoreBoard {
+    scores: Vec<Option<u8>>,
+    #[serde(default)]
+    ruleset: Ruleset,
+}
+
+impl TryFrom<RawScoreBoard> for ScoreBoard {
+    type Error = String;
+
+    fn try_from(raw: RawScoreBoard) -> Result<ScoreBoard, String> {
+        let expected = raw.ruleset.columns() * raw.ruleset.rows();
+        if raw.scores.len() != expected {
+            return Err(format!(
+                "expected {expected} scores, found {}",
+                raw.scores.len()
+            ));
+        }
+        Ok(ScoreBoard {
+            scores: raw.scores,
+            ruleset: raw.ruleset,
+        })
+    }
 }
 
-#[wasm_bindgen]
 impl ScoreBoard {
-    #[wasm_bindgen(constructor)]
     pub fn new() -> ScoreBoard {
+        ScoreBoard::with_ruleset(Ruleset::Yacht)
+    }
+
+    pub fn with_ruleset(ruleset: Ruleset) -> ScoreBoard {
         ScoreBoard {
-            scores: [None; 12],
+            scores: vec![None; ruleset.columns() * ruleset.rows()],
+            ruleset,
         }
     }
 
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+
+    /// 未使用のカテゴリ（列 0）に得点を記入する（使用済みなら false）
     pub fn set_score(&mut self, category: Category, score: u8) -> bool {
         let idx = category as usize;
         if self.scores[idx].is_none() {
@@ -170,75 +687,162 @@ impl ScoreBoard {
         }
     }
 
-    pub fn get_score(&self, category: Category) -> i16 {
-        self.scores[category as usize].map(|s| s as i16).unwrap_or(-1)
+    /// 記入済みの得点を消して返す（未使用なら None）
+    pub fn clear_score(&mut self, category: Category) -> Option<u8> {
+        self.scores[category as usize].take()
+    }
+
+    /// 記入済みの得点（未使用なら None）
+    pub fn score(&self, category: Category) -> Option<u8> {
+        self.scores[category as usize]
     }
 
     pub fn is_used(&self, category: Category) -> bool {
         self.scores[category as usize].is_some()
     }
 
-    pub fn get_upper_total(&self) -> u16 {
-        let mut total: u16 = 0;
-        for cat in [Category::Ones, Category::Twos, Category::Threes,
-                    Category::Fours, Category::Fives, Category::Sixes] {
-            if let Some(s) = self.scores[cat as usize] {
-                total += s as u16;
-            }
+    /// 欄に記入済みの得点（未使用と範囲外の欄は None）
+    pub fn cell(&self, cell: Cell) -> Option<u8> {
+        if cell.column >= self.ruleset.columns() || cell.row >= self.ruleset.rows() {
+            return None;
         }
-        total
+        self.scores[cell.column * self.ruleset.rows() + cell.row]
     }
 
-    pub fn get_upper_bonus(&self) -> u16 {
-        if self.get_upper_total() >= dp_table::UPPER_BONUS_THRESHOLD as u16 {
-            dp_table::UPPER_BONUS_POINTS as u16
-        } else {
-            0
-        }
+    /// 列の行ごとの得点
+    pub fn column(&self, column: usize) -> &[Option<u8>] {
+        let rows = self.ruleset.rows();
+        &self.scores[column * rows..(column + 1) * rows]
     }
 
-    pub fn get_lower_total(&self) -> u16 {
-        let mut total: u16 = 0;
-        for cat in [Category::FullHouse, Category::FourOfAKind, Category::LittleStraight,
-                    Category::BigStraight, Category::Choice, Category::Yacht] {
-            if let Some(s) = self.scores[cat as usize] {
-                total += s as u16;
-            }
+    /// 列の記入順で今埋められる欄か（使用済みとルールで使わない欄は false）
+    pub fn is_open(&self, cell: Cell) -> bool {
+        self.ruleset.has_cell(cell)
+            && self.cell(cell).is_none()
+            && self
+                .ruleset
+                .is_open(cell.column, cell.row, self.column(cell.column))
+    }
+
+    /// 記入順を守って欄に得点を記入する
+    pub fn set_cell(&mut self, cell: Cell, score: u8) -> Result<(), GameError> {
+        if !self.ruleset.has_cell(cell) {
+            return Err(GameError::CategoryUnavailable);
         }
-        total
+        if self.cell(cell).is_some() {
+            return Err(GameError::CategoryUsed);
+        }
+        if !self.is_open(cell) {
+            return Err(GameError::OutOfOrder);
+        }
+        self.scores[cell.column * self.ruleset.rows() + cell.row] = Some(score);
+        Ok(())
+    }
+
+    /// 列 0 の上段の合計（列ごとの合計は `column_total`）
+    pub fn upper_total(&self) -> u16 {
+        self.column_upper_total(0)
+    }
+
+    /// 列の上段の合計
+    pub fn column_upper_total(&self, column: usize) -> u16 {
+        self.ruleset.upper_total(self.column(column))
+    }
+
+    /// 列 0 の上段ボーナス
+    pub fn upper_bonus(&self) -> u16 {
+        self.ruleset.upper_bonus(self.upper_total())
+    }
+
+    /// 列 0 の上段以外の得点
+    pub fn lower_total(&self) -> u16 {
+        self.ruleset.lower_total(self.column(0))
+    }
+
+    /// 列の合計（重みのある列は重みをかけた得点）
+    pub fn column_total(&self, column: usize) -> u16 {
+        self.ruleset.column_total(column, self.column(column))
     }
 
-    pub fn get_total(&self) -> u16 {
-        self.get_upper_total() + self.get_upper_bonus() + self.get_lower_total()
+    pub fn total(&self) -> u16 {
+        (0..self.ruleset.columns())
+            .map(|c| self.column_total(c))
+            .sum()
     }
 
-    pub fn available_categories(&self) -> Vec<u8> {
-        self.scores
+    /// 列 0 の未使用のカテゴリ（ルールで使わないカテゴリは含まない）
+    pub fn available_categories(&self) -> Vec<Category> {
+        self.ruleset
+            .categories()
             .iter()
-            .enumerate()
-            .filter(|(_, s)| s.is_none())
-            .map(|(i, _)| i as u8)
+            .copied()
+            .filter(|&c| !self.is_used(c))
+            .collect()
+    }
+
+    /// 今埋められる欄（宣言の列を含む）
+    pub fn available_cells(&self) -> Vec<Cell> {
+        let rows = self.ruleset.rows();
+        (0..self.ruleset.columns())
+            .flat_map(|c| (0..rows).map(move |r| Cell::new(c, r)))
+            .filter(|&cell| self.is_open(cell))
             .collect()
     }
 
     pub fn is_complete(&self) -> bool {
-        self.scores.iter().all(|s| s.is_some())
+        let rows = self.ruleset.rows();
+        (0..self.ruleset.columns())
+            .flat_map(|c| (0..rows).map(move |r| Cell::new(c, r)))
+            .all(|cell| !self.ruleset.has_cell(cell) || self.cell(cell).is_some())
+    }
+
+    /// 宣言の列以外に埋められる欄がない（1回目のロールの後に宣言しなければならない）
+    pub fn must_announce(&self) -> bool {
+        let Some(announce) = self.ruleset.announce_column() else {
+            return false;
+        };
+        let cells = self.available_cells();
+        !cells.is_empty() && cells.iter().all(|c| c.column == announce)
+    }
+
+    /// 列の (上段スコアの累計（63上限）, 使用済みの行のビットマスク)
+    pub(crate) fn column_state(&self, column: usize) -> (usize, usize) {
+        let cells = self.column(column);
+        let upper_sum =
+            (self.ruleset.upper_total(cells) as usize).min(self.ruleset.upper_sum_cap());
+        let used = cells
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_some())
+            .fold(0, |mask, (i, _)| mask | 1 << i);
+        (upper_sum, used)
     }
 
     /// 使用済みカテゴリのビットマスクを取得
-    pub fn used_hands_mask(&self) -> usize {
-        let mut mask = 0usize;
-        for (i, s) in self.scores.iter().enumerate() {
-            if s.is_some() {
-                mask |= 1 << i;
-            }
-        }
-        mask
+    pub(crate) fn used_hands_mask(&self) -> usize {
+        self.column_state(0).1
     }
 
-    /// 上段スコアの累計を取得（63上限）
-    pub fn upper_sum_capped(&self) -> usize {
-        (self.get_upper_total() as usize).min(dp_table::UPPER_BONUS_THRESHOLD)
+    /// 上段スコアの累計を取得（ボーナスの閾値が上限。ヨットは63）
+    pub(crate) fn upper_sum_capped(&self) -> usize {
+        self.column_state(0).0
+    }
+
+    /// 手番の開始時点から最適に続けた場合の最終合計点数の期待値
+    /// （期待値の表があるヨット・ヘネラル・ヤッツィーだけ。ほかのルールとヤッツィーの表を読み込む前は None）
+    pub fn expected_score(&self) -> Option<f32> {
+        let future = match self.ruleset {
+            Ruleset::Yacht => {
+                dp_table::get_expected_score(self.upper_sum_capped(), self.used_hands_mask())
+            }
+            Ruleset::Generala => generala::expected_score(self.used_hands_mask()),
+            Ruleset::Yatzy => {
+                dp_table::YATZY.check_loaded().ok()?;
+                dp_table::YATZY.expected_score(self.upper_sum_capped(), self.used_hands_mask())
+            }
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Custom(_) => return None,
+        };
+        Some(self.total() as f32 + future)
     }
 }
 
@@ -248,106 +852,378 @@ impl Default for ScoreBoard {
     }
 }
 
+// ゲームの記録（1手ごと）
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// ロール: 振る前のホールドと出目、振った後の出目
+    Roll {
+        seat: u8,
+        rolls_left: u8,
+        held: [bool; 5],
+        before: [u8; 5],
+        after: [u8; 5],
+    },
+    /// 残りのロールを使わずに振り直しをやめる
+    Stand {
+        seat: u8,
+        rolls_left: u8,
+        dice: [u8; 5],
+    },
+    /// 記入（以前の記録の `category` は列 0 の欄として読む）
+    Score {
+        seat: u8,
+        rolls_left: u8,
+        dice: [u8; 5],
+        #[serde(alias = "category")]
+        cell: Cell,
+        score: u8,
+    },
+    /// 宣言の列に記入する行の宣言
+    Announce { seat: u8, row: usize },
+}
+
 // ゲーム状態
-#[wasm_bindgen]
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "RawGameState")]
 pub struct GameState {
-    dice: Dice,
+    /// ダイスと残りロール（JSON では `dice` と `rolls_left` のまま）
+    #[serde(flatten)]
+    turn: Turn,
     player_score: ScoreBoard,
     ai_score: ScoreBoard,
     current_player: u8, // 0 = player, 1 = AI
-    rolls_left: u8,
     game_over: bool,
+    history: Vec<GameEvent>,
+    #[serde(default)]
+    ruleset: Ruleset,
+    /// 即勝利の条件を満たした席
+    #[serde(default)]
+    instant_winner: Option<u8>,
+    /// この手番で宣言した行
+    #[serde(default)]
+    announced: Option<usize>,
+    /// 1人用（席 0 だけが手番を持つ）
+    #[serde(default)]
+    solo: bool,
+}
+
+/// ルールとダイスの組を確かめる前のゲーム状態の JSON
+#[derive(Deserialize)]
+struct RawGameState {
+    #[serde(flatten)]
+    turn: Turn,
+    player_score: ScoreBoard,
+    ai_score: ScoreBoard,
+    current_player: u8,
+    game_over: bool,
+    history: Vec<GameEvent>,
+    #[serde(default)]
+    ruleset: Ruleset,
+    #[serde(default)]
+    instant_winner: Option<u8>,
+    #[serde(default)]
+    announced: Option<usize>,
+    #[serde(default)]
+    solo: bool,
+}
+
+impl TryFrom<RawGameState> for GameState {
+    type Error = String;
+
+    fn try_from(raw: RawGameState) -> Result<GameState, String> {
+        if !raw.ruleset.supports_dice(raw.turn.dice_model()) {
+            return Err(GameError::UnsupportedDice.to_string());
+        }
+        Ok(GameState {
+            turn: raw.turn,
+            player_score: raw.player_score,
+            ai_score: raw.ai_score,
+            current_player: raw.current_player,
+            game_over: raw.game_over,
+            history: raw.history,
+            ruleset: raw.ruleset,
+            instant_winner: raw.instant_winner,
+            announced: raw.announced,
+            solo: raw.solo,
+        })
+    }
 }
 
-#[wasm_bindgen]
 impl GameState {
-    #[wasm_bindgen(constructor)]
     pub fn new() -> GameState {
+        GameState::with_hold_rule(HoldRule::default())
+    }
+
+    pub fn with_hold_rule(hold_rule: HoldRule) -> GameState {
+        GameState::with_rules(Ruleset::Yacht, hold_rule)
+    }
+
+    /// 得点ルールを指定する（ホールドはそのルールの標準。ヨットはロック、それ以外は自由）
+    pub fn with_ruleset(ruleset: Ruleset) -> GameState {
+        let hold_rule = ruleset.default_hold_rule();
+        GameState::with_rules(ruleset, hold_rule)
+    }
+
+    /// 1人用のゲーム（ホールドはそのルールの標準）
+    pub fn solo(ruleset: Ruleset) -> GameState {
         GameState {
-            dice: Dice::new(),
-            player_score: ScoreBoard::new(),
-            ai_score: ScoreBoard::new(),
+            solo: true,
+            ..GameState::with_ruleset(ruleset)
+        }
+    }
+
+    pub fn with_rules(ruleset: Ruleset, hold_rule: HoldRule) -> GameState {
+        GameState {
+            turn: Turn::new(hold_rule),
+            player_score: ScoreBoard::with_ruleset(ruleset.clone()),
+            ai_score: ScoreBoard::with_ruleset(ruleset.clone()),
             current_player: 0,
-            rolls_left: 3,
             game_over: false,
+            history: Vec::new(),
+            ruleset,
+            instant_winner: None,
+            announced: None,
+            solo: false,
         }
     }
 
-    pub fn roll_dice(&mut self) -> bool {
-        if self.rolls_left > 0 && !self.game_over {
-            self.dice.roll();
-            self.rolls_left -= 1;
-            true
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+
+    /// 以降のロールをシードから決定的に生成する
+    pub fn set_seed(&mut self, seed: u64) {
+        self.turn.set_seed(seed);
+    }
+
+    /// 以降のロールを検証可能なダイスのシードから導く（`set_seed` より優先。ダイスは標準の d6 に戻す）
+    pub fn set_fair_seed(&mut self, seed: fair::Seed) {
+        self.turn.set_fair_seed(seed);
+    }
+
+    /// 以降のロールで振るダイス（d4・d8 や偏ったダイス）を設定する
+    ///
+    /// 標準以外のダイスで遊べるのはハウスルールだけ（AI がダイスに合わせて期待値を計算する）。
+    /// DPテーブルや d6 の AI で遊ぶほかのルールは `GameError::UnsupportedDice`。
+    pub fn set_dice_model(&mut self, model: DiceModel) -> Result<(), GameError> {
+        if !self.ruleset.supports_dice(&model) {
+            return Err(GameError::UnsupportedDice);
+        }
+        self.turn.set_dice_model(model);
+        Ok(())
+    }
+
+    pub fn dice_model(&self) -> &DiceModel {
+        self.turn.dice_model()
+    }
+
+    /// ホールドしていないダイスを振る
+    pub fn roll(&mut self) -> Result<(), GameError> {
+        self.check_not_over()?;
+        self.check_announced()?;
+        let record = self.turn.roll()?;
+        self.record_roll(record);
+        Ok(())
+    }
+
+    /// 実物のダイスで振った出目を入力する（テーブルで遊ぶときの補助用）
+    /// 残りロールの数え方は `roll` と同じで、ホールドしたダイスは今の出目のままであること
+    pub fn enter_roll(&mut self, values: [u8; 5]) -> Result<(), GameError> {
+        self.check_not_over()?;
+        self.check_announced()?;
+        let record = self.turn.enter_roll(values)?;
+        self.record_roll(record);
+        Ok(())
+    }
+
+    fn check_not_over(&self) -> Result<(), GameError> {
+        if self.game_over {
+            Err(GameError::GameOver)
         } else {
-            false
+            Ok(())
         }
     }
 
-    pub fn toggle_hold(&mut self, index: usize) {
-        if self.rolls_left < 3 && self.rolls_left > 0 {
-            self.dice.toggle_hold(index);
+    /// 宣言の列しか残っていなければ、振り直す前に宣言が必要
+    fn check_announced(&self) -> Result<(), GameError> {
+        if self.rolls_this_turn() == 1
+            && self.announced.is_none()
+            && self.current_board().must_announce()
+        {
+            Err(GameError::NotAnnounced)
+        } else {
+            Ok(())
         }
     }
 
-    pub fn get_dice_locks(&self) -> Vec<u8> {
-        self.dice.get_locks()
+    fn record_roll(&mut self, record: RollRecord) {
+        self.history.push(GameEvent::Roll {
+            seat: self.current_player,
+            rolls_left: record.rolls_left,
+            held: record.held,
+            before: record.before,
+            after: record.after,
+        });
+    }
+
+    /// 残りのロールを使わずに振り直しをやめる（この後はカテゴリ選択のみ）
+    pub fn stand(&mut self) -> Result<(), GameError> {
+        self.check_not_over()?;
+        let rolls_left = self.turn.stand()?;
+        self.history.push(GameEvent::Stand {
+            seat: self.current_player,
+            rolls_left,
+            dice: self.turn.dice().values,
+        });
+        Ok(())
+    }
+
+    /// ダイスのホールドを設定する（ロックされたダイスは外せない）
+    pub fn set_hold(&mut self, index: usize, hold: bool) -> Result<(), GameError> {
+        self.check_not_over()?;
+        self.turn.set_hold(index, hold)
+    }
+
+    pub fn toggle_hold(&mut self, index: usize) -> Result<(), GameError> {
+        self.check_not_over()?;
+        self.turn.toggle_hold(index)
+    }
+
+    pub fn hold_rule(&self) -> HoldRule {
+        self.turn.hold_rule()
     }
 
     pub fn reset_holds(&mut self) {
-        self.dice.reset_holds();
+        self.turn.reset_holds();
     }
 
-    pub fn select_category(&mut self, category_index: usize) -> bool {
-        if self.game_over || self.rolls_left == 3 {
-            return false;
-        }
+    /// 現在の出目でカテゴリ（列 0）に得点し、手番を交代する（得点を返す）
+    pub fn select(&mut self, category: Category) -> Result<u8, GameError> {
+        self.select_cell(Cell::from(category))
+    }
 
-        let category = match Category::from_index(category_index) {
-            Some(c) => c,
-            None => return false,
-        };
+    /// 現在の出目で欄に得点し、手番を交代する（得点を返す）
+    /// 宣言した手番は宣言した欄にだけ、宣言の列には宣言した手番にだけ記入できる
+    pub fn select_cell(&mut self, cell: Cell) -> Result<u8, GameError> {
+        self.check_not_over()?;
+        self.turn.check_rolled()?;
+        if !self.ruleset.has_cell(cell) {
+            return Err(GameError::CategoryUnavailable);
+        }
+        if let Some(column) = self.ruleset.announce_column() {
+            match self.announced {
+                Some(row) if cell != Cell::new(column, row) => {
+                    return Err(GameError::AnnouncementMismatch);
+                }
+                None if cell.column == column => return Err(GameError::NotAnnounced),
+                _ => {}
+            }
+        }
 
-        let dice_values: [u8; 5] = self.dice.values;
-        let score = calculate_score(&dice_values, category);
+        let dice_values: [u8; 5] = self.turn.dice().values;
+        let roll_number = self.rolls_this_turn();
+        let score = self.ruleset.score_row(&dice_values, cell.row, roll_number);
+        let instant_win = self.ruleset.is_instant_win(&dice_values, roll_number);
 
-        let success = if self.current_player == 0 {
-            self.player_score.set_score(category, score)
+        let board = if self.current_player == 0 {
+            &mut self.player_score
         } else {
-            self.ai_score.set_score(category, score)
+            &mut self.ai_score
         };
+        board.set_cell(cell, score)?;
+
+        self.history.push(GameEvent::Score {
+            seat: self.current_player,
+            rolls_left: self.turn.rolls_left(),
+            dice: dice_values,
+            cell,
+            score,
+        });
+        self.end_turn(instant_win);
+        Ok(score)
+    }
+
+    /// 宣言の列に記入する行を宣言する（手番の1回目のロールの直後だけ）
+    pub fn announce(&mut self, row: usize) -> Result<(), GameError> {
+        self.check_not_over()?;
+        self.turn.check_rolled()?;
+        let column = self
+            .ruleset
+            .announce_column()
+            .ok_or(GameError::CategoryUnavailable)?;
+        if self.rolls_this_turn() > 1 || self.announced.is_some() {
+            return Err(GameError::AnnounceTooLate);
+        }
+        let cell = Cell::new(column, row);
+        if !self.ruleset.has_cell(cell) {
+            return Err(GameError::CategoryUnavailable);
+        }
+        if self.current_board().cell(cell).is_some() {
+            return Err(GameError::CategoryUsed);
+        }
+        self.announced = Some(row);
+        self.history.push(GameEvent::Announce {
+            seat: self.current_player,
+            row,
+        });
+        Ok(())
+    }
+
+    /// この手番で宣言した行
+    pub fn announcement(&self) -> Option<usize> {
+        self.announced
+    }
 
-        if success {
-            self.end_turn();
+    /// この手番で振った回数（`stand` の後も振った回数のまま）
+    pub fn rolls_this_turn(&self) -> u8 {
+        self.history
+            .iter()
+            .rev()
+            .take_while(|e| !matches!(e, GameEvent::Score { .. }))
+            .filter(|e| matches!(e, GameEvent::Roll { .. }))
+            .count() as u8
+    }
+
+    /// 指定した席（0 = プレイヤー, 1 = AI）の得点表
+    pub fn board(&self, seat: u8) -> &ScoreBoard {
+        if seat == 0 {
+            &self.player_score
+        } else {
+            &self.ai_score
         }
+    }
 
-        success
+    /// 手番の席の得点表
+    pub fn current_board(&self) -> &ScoreBoard {
+        self.board(self.current_player)
     }
 
-    fn end_turn(&mut self) {
-        self.dice.reset_holds();
-        self.rolls_left = 3;
+    fn end_turn(&mut self, instant_win: bool) {
+        self.turn.end();
+        self.announced = None;
 
-        if self.player_score.is_complete() && self.ai_score.is_complete() {
+        if instant_win {
+            self.instant_winner = Some(self.current_player);
+            self.game_over = true;
+        } else if self.solo {
+            self.game_over = self.player_score.is_complete();
+        } else if self.player_score.is_complete() && self.ai_score.is_complete() {
             self.game_over = true;
         } else {
             self.current_player = 1 - self.current_player;
         }
     }
 
-    pub fn get_dice_values(&self) -> Vec<u8> {
-        self.dice.get_values()
-    }
-
-    pub fn get_dice_holds(&self) -> Vec<u8> {
-        self.dice.get_holds()
+    pub fn dice(&self) -> &Dice {
+        self.turn.dice()
     }
 
-    pub fn get_rolls_left(&self) -> u8 {
-        self.rolls_left
+    pub fn rolls_left(&self) -> u8 {
+        self.turn.rolls_left()
     }
 
-    pub fn get_current_player(&self) -> u8 {
+    /// 手番の席（0 = プレイヤー, 1 = AI）
+    pub fn current_player(&self) -> u8 {
         self.current_player
     }
 
@@ -355,197 +1231,287 @@ impl GameState {
         self.game_over
     }
 
-    pub fn get_player_score(&self, category_index: usize) -> i16 {
-        Category::from_index(category_index)
-            .map(|c| self.player_score.get_score(c))
-            .unwrap_or(-1)
+    /// 勝った席（ゲーム中と引き分け、1人用は None）。即勝利があればその席
+    pub fn winner(&self) -> Option<u8> {
+        if let Some(seat) = self.instant_winner {
+            return Some(seat);
+        }
+        if !self.game_over || self.solo {
+            return None;
+        }
+        let (player, ai) = (self.player_score.total(), self.ai_score.total());
+        match player.cmp(&ai) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
     }
 
-    pub fn get_ai_score(&self, category_index: usize) -> i16 {
-        Category::from_index(category_index)
-            .map(|c| self.ai_score.get_score(c))
-            .unwrap_or(-1)
+    /// 現在の出目でそのカテゴリを選んだ場合の得点
+    pub fn potential_score(&self, category: Category) -> u8 {
+        self.potential_cell_score(Cell::from(category))
     }
 
-    pub fn get_player_total(&self) -> u16 {
-        self.player_score.get_total()
+    /// 現在の出目でその欄に記入した場合の得点（列の重みをかける前）
+    pub fn potential_cell_score(&self, cell: Cell) -> u8 {
+        self.ruleset
+            .score_row(&self.turn.dice().values, cell.row, self.rolls_this_turn())
     }
 
-    pub fn get_ai_total(&self) -> u16 {
-        self.ai_score.get_total()
+    /// 1人用のゲームか
+    pub fn is_solo(&self) -> bool {
+        self.solo
     }
 
-    pub fn get_player_upper_total(&self) -> u16 {
-        self.player_score.get_upper_total()
+    /// ruleset のゲームか（ルールごとのAIが遊べるか。違えば `CategoryUnavailable`）
+    pub(crate) fn check_ruleset(&self, ruleset: Ruleset) -> Result<(), GameError> {
+        if self.ruleset == ruleset {
+            Ok(())
+        } else {
+            Err(GameError::CategoryUnavailable)
+        }
     }
 
-    pub fn get_ai_upper_total(&self) -> u16 {
-        self.ai_score.get_upper_total()
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
     }
 
-    pub fn get_player_upper_bonus(&self) -> u16 {
-        self.player_score.get_upper_bonus()
+    /// これまでの全ロールとカテゴリ選択の記録
+    pub fn history(&self) -> &[GameEvent] {
+        &self.history
     }
+}
 
-    pub fn get_ai_upper_bonus(&self) -> u16 {
-        self.ai_score.get_upper_bonus()
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    pub fn get_available_categories(&self) -> Vec<u8> {
-        if self.current_player == 0 {
-            self.player_score.available_categories()
-        } else {
-            self.ai_score.available_categories()
-        }
+impl play::Game for GameState {
+    type Cell = Cell;
+
+    fn is_game_over(&self) -> bool {
+        GameState::is_game_over(self)
     }
 
-    pub fn get_potential_score(&self, category_index: usize) -> u8 {
-        Category::from_index(category_index)
-            .map(|c| calculate_score(&self.dice.values, c))
-            .unwrap_or(0)
+    fn dice(&self) -> &Dice {
+        GameState::dice(self)
     }
 
-    pub fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap_or_default()
+    fn rolls_left(&self) -> u8 {
+        GameState::rolls_left(self)
     }
 
-    /// AI用: 現在のAIの上段累計スコア（63上限）
-    pub fn ai_upper_sum_capped(&self) -> usize {
-        self.ai_score.upper_sum_capped()
+    fn roll(&mut self) -> Result<(), GameError> {
+        GameState::roll(self)
     }
 
-    /// AI用: AIの使用済みカテゴリマスク
-    pub fn ai_used_hands_mask(&self) -> usize {
-        self.ai_score.used_hands_mask()
+    fn set_hold(&mut self, index: usize, hold: bool) -> Result<(), GameError> {
+        GameState::set_hold(self, index, hold)
     }
 
-    /// プレイヤー用: 上段累計スコア（63上限）
-    pub fn player_upper_sum_capped(&self) -> usize {
-        self.player_score.upper_sum_capped()
+    fn stand(&mut self) -> Result<(), GameError> {
+        GameState::stand(self)
     }
 
-    /// プレイヤー用: 使用済みカテゴリマスク
-    pub fn player_used_hands_mask(&self) -> usize {
-        self.player_score.used_hands_mask()
+    fn announce(&mut self, row: usize) -> Result<(), GameError> {
+        GameState::announce(self, row)
     }
-}
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self::new()
+    fn select(&mut self, cell: Cell) -> Result<u8, GameError> {
+        self.select_cell(cell)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        GameState::set_seed(self, seed)
+    }
+
+    fn total(&self) -> u16 {
+        self.board(0).total()
     }
 }
 
 // ========== AI Engine (DPテーブルベース) ==========
 
-#[wasm_bindgen]
+/// ホールドの判断結果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HoldDecision<const N: usize = 5> {
+    /// 振り直さずに今の出目で得点する
+    Stop,
+    /// ホールドして振り直す
+    Reroll([bool; N]),
+}
+
+/// 最終振り前のキープごとの期待値メモ
+/// 同じキープは異なる出目から何度も現れるので使い回す（同一の upper_sum / used_hands の間のみ有効）
+type FinalRollMemo = HashMap<dp_table::DicePattern, f32>;
+
+/// カテゴリ選択の候補
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CategoryChoice {
+    pub category: Category,
+    /// 即時得点
+    pub score: u8,
+    /// 最終的な合計点数の期待値
+    pub expected: f32,
+    /// 最善の候補との期待値の差
+    pub loss: f32,
+    /// 他の候補と期待値が（誤差の範囲で）同じ
+    pub tied: bool,
+}
+
+/// キープの候補
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct HoldChoice {
+    /// ホールドするダイス
+    pub holds: [bool; 5],
+    /// 振り直さずに今の出目で得点する（holds は全て true）
+    pub stop: bool,
+    /// 最終的な合計点数の期待値
+    pub expected: f32,
+    /// 最善の候補との期待値の差
+    pub loss: f32,
+    /// 他の候補と期待値が（誤差の範囲で）同じ
+    pub tied: bool,
+}
+
+/// 運と実力の内訳
+/// baseline + luck - skill_loss = 最後の行動直後の期待値（ゲーム終了時は final_score と一致）
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct LuckSkillBreakdown {
+    /// ゲーム開始時の期待得点
+    pub baseline: f32,
+    /// 出目による期待値の変化の合計
+    pub luck: f32,
+    /// 最適でない判断で失った期待値の合計
+    pub skill_loss: f32,
+    /// 現在の合計点数
+    pub final_score: u16,
+}
+
+/// ヨットとヘネラルのAI（ほかのルールはそれぞれのモジュールのAIを使う）
+#[derive(Clone, Debug)]
 pub struct YachtAI {}
 
-#[wasm_bindgen]
 impl YachtAI {
-    #[wasm_bindgen(constructor)]
     pub fn new() -> YachtAI {
         YachtAI {}
     }
 
-    /// AIの手番を実行（ロールとカテゴリ選択を含む）
-    pub fn play_turn(&self, game: &mut GameState) -> String {
-        let mut actions = Vec::new();
-
-        // 最初のロール
-        game.roll_dice();
-        actions.push(format!("Roll: {:?}", game.get_dice_values()));
+    /// このAIが扱えるルールか
+    pub fn supports(ruleset: &Ruleset) -> bool {
+        matches!(ruleset, Ruleset::Yacht | Ruleset::Generala)
+    }
 
-        // 2回目のロール判断
-        if game.get_rolls_left() > 0 {
-            let holds = self.decide_holds(game);
-            for (i, hold) in holds.iter().enumerate() {
-                if *hold && !game.dice.is_held(i) {
-                    game.toggle_hold(i);
-                }
+    /// ホールド状態を指定どおりに合わせる（ロック済みのダイスはそのまま）
+    fn apply_holds(&self, game: &mut GameState, holds: &[bool; 5]) {
+        for (i, &hold) in holds.iter().enumerate() {
+            if !game.dice().is_locked(i) {
+                let _ = game.set_hold(i, hold);
             }
-            game.roll_dice();
-            actions.push(format!("Hold: {:?}, Roll: {:?}", holds, game.get_dice_values()));
         }
+    }
 
-        // 3回目のロール判断
-        if game.get_rolls_left() > 0 {
-            let holds = self.decide_holds(game);
-            for (i, hold) in holds.iter().enumerate() {
-                if *hold {
-                    game.toggle_hold(i);
-                }
+    /// どのサイコロを保持するか、振り直さずに得点するかを決定（DPテーブルベース）
+    /// 手番プレイヤーのスコアボードを基準に判断する（扱えないルールは振り直さない）
+    pub fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        match game.ruleset {
+            Ruleset::Yacht => {}
+            Ruleset::Generala => return GeneralaAI::new().decide_holds(game),
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => {
+                return HoldDecision::Stop
             }
-            game.roll_dice();
-            actions.push(format!("Hold: {:?}, Roll: {:?}", holds, game.get_dice_values()));
         }
-
-        // カテゴリ選択
-        let category = self.decide_category(game);
-        game.select_category(category);
-        actions.push(format!("Selected category: {}", category));
-
-        actions.join("\n")
-    }
-
-    /// AIが選ぶべきホールドパターンを取得（JS用）
-    pub fn get_holds_decision(&self, game: &GameState) -> Vec<u8> {
-        self.decide_holds(game).iter().map(|&h| if h { 1 } else { 0 }).collect()
-    }
-
-    /// AIが選ぶべきカテゴリを取得（JS用）
-    pub fn get_category_decision(&self, game: &GameState) -> usize {
-        self.decide_category(game)
+        let dice = game.dice().values;
+        let locked = game.dice().locked;
+        let rolls_left = game.rolls_left();
+        let board = game.current_board();
+        let upper_sum = board.upper_sum_capped();
+        let used_hands = board.used_hands_mask();
+        let hold_rule = game.hold_rule();
+
+        // 今の出目で得点する価値と、最良の振り直しの期待値を比べる
+        let score_now =
+            self.best_category_value(&dp_table::dice_to_pattern(&dice), upper_sum, used_hands);
+        match self.find_best_keep(
+            &dice, &locked, rolls_left, upper_sum, used_hands, hold_rule, false,
+        ) {
+            // キープパターンからホールド配列を復元
+            Some((keep, reroll)) if reroll >= score_now => {
+                HoldDecision::Reroll(self.pattern_to_holds(&dice, &keep, &locked))
+            }
+            _ => HoldDecision::Stop,
+        }
     }
 
-    /// どのサイコロを保持するか決定（DPテーブルベース）
-    fn decide_holds(&self, game: &GameState) -> Vec<bool> {
-        let dice = game.get_dice_values();
-        let locks = game.get_dice_locks();
-        let rolls_left = game.get_rolls_left();
-        let upper_sum = game.ai_upper_sum_capped();
-        let used_hands = game.ai_used_hands_mask();
-
+    /// ロックを守るキープの中で期待値が最大のものを探す
+    /// include_keep_all: 全てキープする（＝振り直さない）パターンも候補に含めるか
+    /// 戻り値: (キープパターン, 将来の期待値)
+    #[allow(clippy::too_many_arguments)]
+    fn find_best_keep(
+        &self,
+        dice: &[u8],
+        locked: &[bool],
+        rolls_left: u8,
+        upper_sum: usize,
+        used_hands: usize,
+        hold_rule: HoldRule,
+        include_keep_all: bool,
+    ) -> Option<(dp_table::DicePattern, f32)> {
         // ロックされたダイスのパターンを計算
-        let locked: Vec<bool> = locks.iter().map(|&l| l == 1).collect();
-        let lock_pattern = self.dice_to_lock_pattern(&dice, &locked);
+        let lock_pattern = self.dice_to_lock_pattern(dice, locked);
 
-        let current_pattern = dp_table::dice_to_pattern(&dice);
+        let current_pattern = dp_table::dice_to_pattern(dice);
         let keep_patterns = dp_table::enumerate_keep_patterns(&current_pattern);
 
-        let mut best_holds = vec![false; 5];
-        let mut best_expected = f32::NEG_INFINITY;
+        let mut best: Option<(dp_table::DicePattern, f32)> = None;
+        let mut memo = FinalRollMemo::new();
 
         for keep in &keep_patterns {
             // ロックされたダイスを含まないパターンはスキップ
             // （各面でロック数以上をキープしている必要がある）
-            let valid = (0..6).all(|i| keep[i] >= lock_pattern[i]);
-            if !valid {
+            let valid = (0..dp_table::MAX_FACES).all(|i| keep[i] >= lock_pattern[i]);
+            if !valid || (!include_keep_all && dp_table::pattern_count(keep) == 5) {
                 continue;
             }
 
-            let expected = if rolls_left == 1 {
-                self.evaluate_final_roll(keep, upper_sum, used_hands)
-            } else {
-                // rolls_left == 2: 2回振り直し可能
-                self.evaluate_two_rolls(keep, upper_sum, used_hands)
-            };
-
-            if expected > best_expected {
-                best_expected = expected;
-                // キープパターンからホールド配列を復元
-                best_holds = self.pattern_to_holds(&dice, keep, &locked);
+            let expected = self.evaluate_keep(
+                keep, rolls_left, upper_sum, used_hands, hold_rule, &mut memo,
+            );
+            if best.is_none_or(|(_, b)| expected > b) {
+                best = Some((*keep, expected));
             }
         }
 
-        best_holds
+        best
+    }
+
+    /// キープして残りロール数分振り直した場合の期待値
+    fn evaluate_keep(
+        &self,
+        keep: &dp_table::DicePattern,
+        rolls_left: u8,
+        upper_sum: usize,
+        used_hands: usize,
+        hold_rule: HoldRule,
+        memo: &mut FinalRollMemo,
+    ) -> f32 {
+        if rolls_left == 1 {
+            self.evaluate_final_roll(keep, upper_sum, used_hands)
+        } else {
+            // rolls_left == 2: 2回振り直し可能
+            self.evaluate_two_rolls(keep, upper_sum, used_hands, hold_rule, memo)
+        }
     }
 
     /// ロックされたダイスのパターンを計算
     fn dice_to_lock_pattern(&self, dice: &[u8], locks: &[bool]) -> dp_table::DicePattern {
-        let mut pattern = [0u8; 6];
+        let mut pattern = [0u8; dp_table::MAX_FACES];
         for (i, &d) in dice.iter().enumerate() {
-            if locks.get(i).copied().unwrap_or(false) && d >= 1 && d <= 6 {
+            if locks.get(i).copied().unwrap_or(false) && (1..=6).contains(&d) {
                 pattern[(d - 1) as usize] += 1;
             }
         }
@@ -577,11 +1543,14 @@ impl YachtAI {
     }
 
     /// 2回振り直しの期待値
+    /// HoldRule::Locking では、今回キープしたダイスは最終振りでもキープしたままになる
     fn evaluate_two_rolls(
         &self,
         keep: &dp_table::DicePattern,
         upper_sum: usize,
         used_hands: usize,
+        hold_rule: HoldRule,
+        memo: &mut FinalRollMemo,
     ) -> f32 {
         let num_reroll = 5 - dp_table::pattern_count(keep) as usize;
         if num_reroll == 0 {
@@ -590,11 +1559,16 @@ impl YachtAI {
 
         let patterns = dp_table::dice_patterns::get_patterns(num_reroll);
         let mut total = 0.0f32;
+        let min_keep = match hold_rule {
+            HoldRule::Free => [0u8; dp_table::MAX_FACES],
+            HoldRule::Locking => *keep,
+        };
 
         for pp in patterns {
             let after_roll1 = dp_table::add_patterns(keep, &pp.pattern);
             // この出目から最適なキープを選んで、さらに1回振る
-            let best_keep_value = self.find_best_keep_for_final(&after_roll1, upper_sum, used_hands);
+            let best_keep_value =
+                self.find_best_keep_for_final(&after_roll1, &min_keep, upper_sum, used_hands, memo);
             total += pp.probability * best_keep_value;
         }
 
@@ -602,17 +1576,25 @@ impl YachtAI {
     }
 
     /// 最終振り前の最適キープ期待値
+    /// min_keep: 各面で最低限キープしなければならない個数（ロック済みのダイス）
     fn find_best_keep_for_final(
         &self,
         dice_pattern: &dp_table::DicePattern,
+        min_keep: &dp_table::DicePattern,
         upper_sum: usize,
         used_hands: usize,
+        memo: &mut FinalRollMemo,
     ) -> f32 {
         let keep_patterns = dp_table::enumerate_keep_patterns(dice_pattern);
         let mut best = f32::NEG_INFINITY;
 
         for keep in &keep_patterns {
-            let value = self.evaluate_final_roll(keep, upper_sum, used_hands);
+            if (0..dp_table::MAX_FACES).any(|i| keep[i] < min_keep[i]) {
+                continue;
+            }
+            let value = *memo
+                .entry(*keep)
+                .or_insert_with(|| self.evaluate_final_roll(keep, upper_sum, used_hands));
             if value > best {
                 best = value;
             }
@@ -644,16 +1626,146 @@ impl YachtAI {
         best
     }
 
+    /// 手番中の状態から最適に続けた場合の将来の期待値
+    fn live_future_value(
+        &self,
+        dice: &[u8; 5],
+        locked: &[bool; 5],
+        rolls_left: u8,
+        upper_sum: usize,
+        used_hands: usize,
+        hold_rule: HoldRule,
+    ) -> f32 {
+        match rolls_left {
+            // まだ振っていない: 手番開始時点のDP値
+            3 => dp_table::get_expected_score(upper_sum, used_hands),
+            // もう振れない: 最良カテゴリを選ぶだけ
+            0 => self.best_category_value(&dp_table::dice_to_pattern(dice), upper_sum, used_hands),
+            _ => {
+                let (_, expected) = self
+                    .find_best_keep(
+                        dice, locked, rolls_left, upper_sum, used_hands, hold_rule, true,
+                    )
+                    .expect("keeping every die is always a valid keep");
+                expected
+            }
+        }
+    }
+
+    /// ホールド中のダイスをキープして振り直す場合の将来の期待値
+    fn held_future_value(
+        &self,
+        dice: &[u8; 5],
+        held: &[bool; 5],
+        rolls_left: u8,
+        upper_sum: usize,
+        used_hands: usize,
+        hold_rule: HoldRule,
+    ) -> f32 {
+        let kept: Vec<u8> = (0..5).filter(|&i| held[i]).map(|i| dice[i]).collect();
+        let keep = dp_table::dice_to_pattern(&kept);
+        self.evaluate_keep(
+            &keep,
+            rolls_left,
+            upper_sum,
+            used_hands,
+            hold_rule,
+            &mut FinalRollMemo::new(),
+        )
+    }
+
+    /// live_future_value を得点表のルールで求める
+    /// first_roll: 今の出目が手番の1回目のロールか（ヘネラルのセルビーダ用）
+    #[allow(clippy::too_many_arguments)]
+    fn board_live_value(
+        &self,
+        board: &ScoreBoard,
+        dice: &[u8; 5],
+        locked: &[bool; 5],
+        rolls_left: u8,
+        first_roll: bool,
+        hold_rule: HoldRule,
+    ) -> f32 {
+        let (upper_sum, used_hands) = (board.upper_sum_capped(), board.used_hands_mask());
+        match board.ruleset() {
+            Ruleset::Yacht => {
+                self.live_future_value(dice, locked, rolls_left, upper_sum, used_hands, hold_rule)
+            }
+            Ruleset::Generala => GeneralaTurn::new(used_hands).live_value(
+                &dp_table::dice_to_pattern(dice),
+                rolls_left,
+                first_roll,
+            ),
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => 0.0,
+        }
+    }
+
+    /// held_future_value を得点表のルールで求める
+    fn board_held_value(
+        &self,
+        board: &ScoreBoard,
+        dice: &[u8; 5],
+        held: &[bool; 5],
+        rolls_left: u8,
+        hold_rule: HoldRule,
+    ) -> f32 {
+        let (upper_sum, used_hands) = (board.upper_sum_capped(), board.used_hands_mask());
+        match board.ruleset() {
+            Ruleset::Yacht => {
+                self.held_future_value(dice, held, rolls_left, upper_sum, used_hands, hold_rule)
+            }
+            Ruleset::Generala => {
+                let kept: Vec<u8> = (0..5).filter(|&i| held[i]).map(|i| dice[i]).collect();
+                GeneralaTurn::new(used_hands)
+                    .keep_value(&dp_table::dice_to_pattern(&kept), rolls_left)
+            }
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => 0.0,
+        }
+    }
+
+    /// 今の出目で最良のカテゴリに記入する将来の期待値（得点表のルールで求める）
+    fn board_stand_value(&self, board: &ScoreBoard, dice: &[u8; 5], first_roll: bool) -> f32 {
+        let pattern = dp_table::dice_to_pattern(dice);
+        let used_hands = board.used_hands_mask();
+        match board.ruleset() {
+            Ruleset::Yacht => {
+                self.best_category_value(&pattern, board.upper_sum_capped(), used_hands)
+            }
+            Ruleset::Generala => GeneralaTurn::new(used_hands).stand_value(&pattern, first_roll),
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => 0.0,
+        }
+    }
+
+    /// カテゴリに記入した場合の将来の期待値（得点表のルールで求める）
+    fn board_category_value(&self, board: &ScoreBoard, category: Category, score: u8) -> f32 {
+        let used_hands = board.used_hands_mask();
+        match board.ruleset() {
+            Ruleset::Yacht => dp_table::evaluate_category_choice(
+                board.upper_sum_capped(),
+                used_hands,
+                category as usize,
+                score,
+            ),
+            Ruleset::Generala => GeneralaTurn::new(used_hands).category_value(category, score),
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => 0.0,
+        }
+    }
+
     /// キープパターンからホールド配列を復元
     /// locks: ロックされたダイス（同じ目が複数ある場合にロック済みを優先キープ）
-    fn pattern_to_holds(&self, dice: &[u8], keep: &dp_table::DicePattern, locks: &[bool]) -> Vec<bool> {
-        let mut holds = vec![false; 5];
+    fn pattern_to_holds(
+        &self,
+        dice: &[u8; 5],
+        keep: &dp_table::DicePattern,
+        locks: &[bool; 5],
+    ) -> [bool; 5] {
+        let mut holds = [false; 5];
         let mut remaining = *keep;
 
         // 第1パス: ロックされたダイスを優先的にキープ
         for (i, &d) in dice.iter().enumerate() {
             let face = (d - 1) as usize;
-            if locks.get(i).copied().unwrap_or(false) && remaining[face] > 0 {
+            if locks[i] && remaining[face] > 0 {
                 holds[i] = true;
                 remaining[face] -= 1;
             }
@@ -662,7 +1774,7 @@ impl YachtAI {
         // 第2パス: 残りのキープ枠をロックされていないダイスに割り当て
         for (i, &d) in dice.iter().enumerate() {
             let face = (d - 1) as usize;
-            if !locks.get(i).copied().unwrap_or(false) && remaining[face] > 0 {
+            if !locks[i] && remaining[face] > 0 {
                 holds[i] = true;
                 remaining[face] -= 1;
             }
@@ -671,15 +1783,20 @@ impl YachtAI {
         holds
     }
 
-    /// カテゴリを選択（DPテーブルベース）
-    fn decide_category(&self, game: &GameState) -> usize {
-        let dice = game.get_dice_values();
-        let upper_sum = game.ai_upper_sum_capped();
-        let used_hands = game.ai_used_hands_mask();
+    /// カテゴリを選択（DPテーブルベース。空きがないか扱えないルールなら None）
+    pub fn decide_category(&self, game: &GameState) -> Option<Category> {
+        match game.ruleset {
+            Ruleset::Yacht => {}
+            Ruleset::Generala => return GeneralaAI::new().decide_category(game),
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => return None,
+        }
+        let dice = game.dice().values;
+        let board = game.current_board();
+        let upper_sum = board.upper_sum_capped();
+        let used_hands = board.used_hands_mask();
         let pattern = dp_table::dice_to_pattern(&dice);
 
-        let mut best_category = 0;
-        let mut best_value = f32::NEG_INFINITY;
+        let mut best: Option<(usize, f32)> = None;
 
         for cat_idx in 0..dp_table::NUM_CATEGORIES {
             if (used_hands >> cat_idx) & 1 == 1 {
@@ -688,78 +1805,96 @@ impl YachtAI {
             let score = dp_table::calculate_score(&pattern, cat_idx);
             let value = dp_table::evaluate_category_choice(upper_sum, used_hands, cat_idx, score);
 
-            if value > best_value {
-                best_value = value;
-                best_category = cat_idx;
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((cat_idx, value));
             }
         }
 
-        best_category
+        Category::from_index(best?.0)
     }
 
     // ========== プレイヤー向け推奨機能 ==========
 
-    /// プレイヤー向け: カテゴリ選択の上位3つを取得
-    /// 戻り値: JSON配列 [{"category": index, "score": immediate, "expected": value}, ...]
-    /// expected は最終的な合計点数の期待値
-    pub fn get_top_category_choices(&self, game: &GameState) -> String {
-        let dice = game.get_dice_values();
-        let upper_sum = game.player_upper_sum_capped();
-        let used_hands = game.player_used_hands_mask();
-        let current_total = game.get_player_total() as f32;
+    /// プレイヤー向け: カテゴリ選択の上位n個を取得（n = 0 なら全候補）
+    /// expected は最終的な合計点数の期待値、loss は最善の候補との差
+    pub fn category_choices(&self, game: &GameState, n: usize) -> Vec<CategoryChoice> {
+        let dice = game.dice().values;
+        let upper_sum = game.player_score.upper_sum_capped();
+        let used_hands = game.player_score.used_hands_mask();
+        let current_total = game.player_score.total() as f32;
         let pattern = dp_table::dice_to_pattern(&dice);
 
-        let mut choices: Vec<(usize, u8, f32)> = Vec::new();
-
-        for cat_idx in 0..dp_table::NUM_CATEGORIES {
-            if (used_hands >> cat_idx) & 1 == 1 {
-                continue;
+        // (カテゴリ, 得点, 将来の期待値)
+        let candidates: Vec<(Category, u8, f32)> = match game.ruleset {
+            Ruleset::Yacht => (0..dp_table::NUM_CATEGORIES)
+                .filter(|&cat_idx| (used_hands >> cat_idx) & 1 == 0)
+                .map(|cat_idx| {
+                    let score = dp_table::calculate_score(&pattern, cat_idx);
+                    let future_value = dp_table::evaluate_category_choice(upper_sum, used_hands, cat_idx, score);
+                    (Category::ALL[cat_idx], score, future_value)
+                })
+                .collect(),
+            Ruleset::Generala => {
+                GeneralaTurn::new(used_hands).category_values(&dice, game.rolls_this_turn() == 1)
             }
-            let score = dp_table::calculate_score(&pattern, cat_idx);
-            let future_value = dp_table::evaluate_category_choice(upper_sum, used_hands, cat_idx, score);
-            // 現在の合計 + 将来の期待値 = 最終的な合計点数の期待値
-            let total_expected = current_total + future_value;
-            choices.push((cat_idx, score, total_expected));
-        }
-
-        // 期待値でソート（降順）
-        choices.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
-
-        // 上位3つを取得
-        let top3: Vec<_> = choices.into_iter().take(3).collect();
+            Ruleset::Triple | Ruleset::Yamb | Ruleset::Yatzy | Ruleset::Custom(_) => Vec::new(),
+        };
 
-        // JSON形式で返す
-        let json_array: Vec<String> = top3
-            .iter()
-            .map(|(cat, score, expected)| {
-                format!(
-                    r#"{{"category":{},"score":{},"expected":{:.1}}}"#,
-                    cat, score, expected
-                )
+        let mut choices: Vec<CategoryChoice> = candidates
+            .into_iter()
+            .map(|(category, score, future_value)| CategoryChoice {
+                category,
+                score,
+                // 現在の合計 + 将来の期待値 = 最終的な合計点数の期待値
+                expected: current_total + future_value,
+                loss: 0.0,
+                tied: false,
             })
             .collect();
 
-        format!("[{}]", json_array.join(","))
-    }
+        // 期待値でソート（降順）
+        choices.sort_by(|a, b| b.expected.partial_cmp(&a.expected).unwrap());
 
-    /// プレイヤー向け: キープパターンの上位3つを取得
-    /// 戻り値: JSON配列 [{"holds": [0,1,1,0,1], "expected": value}, ...]
-    /// expected は最終的な合計点数の期待値
-    pub fn get_top_hold_choices(&self, game: &GameState) -> String {
-        let dice = game.get_dice_values();
-        let locks = game.get_dice_locks();
-        let rolls_left = game.get_rolls_left();
-        let upper_sum = game.player_upper_sum_capped();
-        let used_hands = game.player_used_hands_mask();
-        let current_total = game.get_player_total() as f32;
+        let expected: Vec<f32> = choices.iter().map(|c| c.expected).collect();
+        for (choice, (loss, tied)) in choices.iter_mut().zip(rank_expected(&expected)) {
+            choice.loss = loss;
+            choice.tied = tied;
+        }
 
+        // 上位n個を取得
+        if n > 0 {
+            choices.truncate(n);
+        }
+        choices
+    }
+
+    /// プレイヤー向け: キープパターンの上位n個を取得（n = 0 なら全候補）
+    /// expected は最終的な合計点数の期待値、loss は最善の候補との差
+    /// 同じ目の組をキープするホールドは1つにまとめ、全てキープは「振らずに得点」(stop) として扱う
+    /// 手番の最初に振る前・振り切った後・ゲーム終了後と、扱えないルールは候補がないので空
+    pub fn hold_choices(&self, game: &GameState, n: usize) -> Vec<HoldChoice> {
+        if game.rolls_left() == 3
+            || game.rolls_left() == 0
+            || game.game_over
+            || !Self::supports(&game.ruleset)
+        {
+            return Vec::new();
+        }
+        let dice = game.dice().values;
         // ロックされたダイスは必ずキープ
-        let locked: Vec<bool> = locks.iter().map(|&l| l == 1).collect();
+        let locked = game.dice().locked;
+        let rolls_left = game.rolls_left();
+        let upper_sum = game.player_score.upper_sum_capped();
+        let used_hands = game.player_score.used_hands_mask();
+        let current_total = game.player_score.total() as f32;
 
         let current_pattern = dp_table::dice_to_pattern(&dice);
         let keep_patterns = dp_table::enumerate_keep_patterns(&current_pattern);
 
-        let mut choices: Vec<(Vec<bool>, f32)> = Vec::new();
+        let mut choices: Vec<(dp_table::DicePattern, [bool; 5], f32)> = Vec::new();
+        let mut memo = FinalRollMemo::new();
+        let mut generala =
+            (game.ruleset == Ruleset::Generala).then(|| GeneralaTurn::new(used_hands));
 
         for keep in &keep_patterns {
             let holds = self.pattern_to_holds(&dice, keep, &locked);
@@ -770,54 +1905,206 @@ impl YachtAI {
                 continue;
             }
 
-            let future_expected = if rolls_left == 1 {
-                self.evaluate_final_roll(keep, upper_sum, used_hands)
-            } else {
-                self.evaluate_two_rolls(keep, upper_sum, used_hands)
+            let future_expected = match generala.as_mut() {
+                Some(turn) => turn.keep_value(keep, rolls_left),
+                None => self.evaluate_keep(
+                    keep,
+                    rolls_left,
+                    upper_sum,
+                    used_hands,
+                    game.hold_rule(),
+                    &mut memo,
+                ),
             };
 
             // 現在の合計 + 将来の期待値 = 最終的な合計点数の期待値
             let total_expected = current_total + future_expected;
-            choices.push((holds, total_expected));
+            choices.push((*keep, holds, total_expected));
         }
 
         // 期待値でソート（降順）
-        choices.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-        // 上位3つを取得（重複を除去）
-        let mut top3: Vec<(Vec<bool>, f32)> = Vec::new();
-        let mut seen: std::collections::HashSet<Vec<bool>> = std::collections::HashSet::new();
-        for (holds, expected) in choices {
-            if !seen.contains(&holds) {
-                seen.insert(holds.clone());
-                top3.push((holds, expected));
-                if top3.len() >= 3 {
-                    break;
-                }
-            }
-        }
+        choices.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
 
-        // JSON形式で返す
-        let json_array: Vec<String> = top3
-            .iter()
-            .map(|(holds, expected)| {
-                let holds_str: Vec<String> = holds.iter().map(|&h| if h { "1".to_string() } else { "0".to_string() }).collect();
-                format!(
-                    r#"{{"holds":[{}],"expected":{:.1}}}"#,
-                    holds_str.join(","),
-                    expected
-                )
+        // キープする目の組（多重集合）で重複を除去
+        let mut seen: std::collections::HashSet<dp_table::DicePattern> =
+            std::collections::HashSet::new();
+        choices.retain(|(keep, _, _)| seen.insert(*keep));
+
+        let expected: Vec<f32> = choices.iter().map(|c| c.2).collect();
+        let mut ranked: Vec<HoldChoice> = choices
+            .into_iter()
+            .zip(rank_expected(&expected))
+            .map(|((keep, holds, expected), (loss, tied))| HoldChoice {
+                holds,
+                // 全てキープ = 振り直さずに得点する
+                stop: dp_table::pattern_count(&keep) == 5,
+                expected,
+                loss,
+                tied,
             })
             .collect();
 
-        format!("[{}]", json_array.join(","))
+        // 上位n個を取得
+        if n > 0 {
+            ranked.truncate(n);
+        }
+        ranked
     }
 
     /// プレイヤー向け: 現在の状態からの総合期待値を取得
-    pub fn get_player_expected_score(&self, game: &GameState) -> f32 {
-        let upper_sum = game.player_upper_sum_capped();
-        let used_hands = game.player_used_hands_mask();
-        dp_table::get_expected_score(upper_sum, used_hands)
+    pub fn player_expected_score(&self, game: &GameState) -> f32 {
+        let board = &game.player_score;
+        board
+            .expected_score()
+            .map_or(0.0, |e| e - board.total() as f32)
+    }
+
+    /// 手番中の期待値: 現在の出目・ロック・残りロール数から最適に続けた場合の
+    /// 手番プレイヤーの最終的な合計点数の期待値（扱えないルールは現在の合計点数）
+    pub fn live_expected_score(&self, game: &GameState) -> f32 {
+        let board = game.current_board();
+        let current_total = board.total() as f32;
+        current_total
+            + self.board_live_value(
+                board,
+                &game.dice().values,
+                &game.dice().locked,
+                game.rolls_left(),
+                game.rolls_this_turn() == 1,
+                game.dice().hold_rule,
+            )
+    }
+
+    /// 現在のホールドのまま次に振る場合の最終的な合計点数の期待値
+    /// 振る前後でこの値と live_expected_score を比べた差がそのロールの運
+    pub fn held_expected_score(&self, game: &GameState) -> f32 {
+        if game.rolls_left() == 3 || game.rolls_left() == 0 || game.game_over {
+            return self.live_expected_score(game);
+        }
+
+        let board = game.current_board();
+        let current_total = board.total() as f32;
+        current_total
+            + self.board_held_value(
+                board,
+                &game.dice().values,
+                &game.dice().held,
+                game.rolls_left(),
+                game.dice().hold_rule,
+            )
+    }
+
+    /// 指定した席（0 = プレイヤー, 1 = AI）の得点を運と実力に分解
+    /// ゲームの記録を最初から再生し、各ロールの運と各判断の期待値損失を積み上げる
+    /// （扱えないルールは現在の合計点数だけ）
+    pub fn luck_skill_breakdown(&self, game: &GameState, seat: u8) -> LuckSkillBreakdown {
+        let hold_rule = game.hold_rule();
+        let mut board = ScoreBoard::with_ruleset(game.ruleset.clone());
+        let Some(baseline) = board
+            .expected_score()
+            .filter(|_| Self::supports(&game.ruleset))
+        else {
+            return LuckSkillBreakdown {
+                baseline: 0.0,
+                luck: 0.0,
+                skill_loss: 0.0,
+                final_score: game.board(seat).total(),
+            };
+        };
+        // 直前の状態から最適に続けた場合の最終合計点数の期待値
+        let mut live = baseline;
+        let mut luck = 0.0f32;
+        let mut skill_loss = 0.0f32;
+
+        for event in game.history().iter() {
+            match *event {
+                GameEvent::Roll {
+                    seat: s,
+                    rolls_left,
+                    held,
+                    before,
+                    after,
+                } if s == seat => {
+                    // 書き換えられた記録は飛ばす（`assist::review_game` と同じ判断だけを数える）
+                    let Some(rolls_after) = rolls_left.checked_sub(1).filter(|&r| r < 3) else {
+                        continue;
+                    };
+                    if !is_valid_dice(&before) || !is_valid_dice(&after) {
+                        continue;
+                    }
+                    let current_total = board.total() as f32;
+
+                    // ホールドの判断
+                    let chosen = if rolls_left == 3 {
+                        live
+                    } else {
+                        current_total
+                            + self.board_held_value(&board, &before, &held, rolls_left, hold_rule)
+                    };
+                    skill_loss += live - chosen;
+
+                    // ロールの運（HoldRule::Locking ではロール時にホールドしていたダイスがロックされる）
+                    let locked = match hold_rule {
+                        HoldRule::Free => [false; 5],
+                        HoldRule::Locking => held,
+                    };
+                    let after_live = current_total
+                        + self.board_live_value(
+                            &board,
+                            &after,
+                            &locked,
+                            rolls_after,
+                            rolls_left == 3,
+                            hold_rule,
+                        );
+                    luck += after_live - chosen;
+                    live = after_live;
+                }
+                GameEvent::Stand {
+                    seat: s,
+                    rolls_left,
+                    dice,
+                } if s == seat && is_valid_dice(&dice) => {
+                    // 振り直せる回数が 2 回残っていれば1回目のロールの出目
+                    let chosen = board.total() as f32
+                        + self.board_stand_value(&board, &dice, rolls_left == 2);
+                    skill_loss += live - chosen;
+                    live = chosen;
+                }
+                GameEvent::Score {
+                    seat: s,
+                    cell,
+                    score,
+                    ..
+                } if s == seat && cell.column == 0 => {
+                    let Some(category) =
+                        Category::from_index(cell.row).filter(|&c| !board.is_used(c))
+                    else {
+                        continue;
+                    };
+                    let chosen =
+                        board.total() as f32 + self.board_category_value(&board, category, score);
+                    skill_loss += live - chosen;
+                    board.set_score(category, score);
+                    live = chosen;
+                }
+                _ => {}
+            }
+        }
+
+        LuckSkillBreakdown {
+            baseline,
+            luck,
+            skill_loss,
+            final_score: board.total(),
+        }
+    }
+
+    /// サイコロを振り、そのロールによる期待値の変化（運）を返す
+    pub fn roll_with_luck(&self, game: &mut GameState) -> Result<f32, GameError> {
+        let before = self.held_expected_score(game);
+        game.roll()?;
+        Ok(self.live_expected_score(game) - before)
     }
 }
 
@@ -827,60 +2114,62 @@ impl Default for YachtAI {
     }
 }
 
-// ========== Utility Functions ==========
+impl play::Strategy<GameState> for YachtAI {
+    fn check_game(&self, game: &GameState) -> Result<(), GameError> {
+        if Self::supports(&game.ruleset) {
+            Ok(())
+        } else {
+            Err(GameError::CategoryUnavailable)
+        }
+    }
+
+    fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        YachtAI::decide_holds(self, game)
+    }
 
-#[wasm_bindgen]
-pub fn calculate_score_js(dice: Vec<u8>, category_index: usize) -> u8 {
-    if dice.len() != 5 {
-        return 0;
-    }
-    let dice_arr: [u8; 5] = [dice[0], dice[1], dice[2], dice[3], dice[4]];
-    Category::from_index(category_index)
-        .map(|c| calculate_score(&dice_arr, c))
-        .unwrap_or(0)
-}
-
-#[wasm_bindgen]
-pub fn get_category_name(category_index: usize) -> String {
-    match category_index {
-        0 => "Ones".to_string(),
-        1 => "Twos".to_string(),
-        2 => "Threes".to_string(),
-        3 => "Fours".to_string(),
-        4 => "Fives".to_string(),
-        5 => "Sixes".to_string(),
-        6 => "Full House".to_string(),
-        7 => "Four of a Kind".to_string(),
-        8 => "Little Straight".to_string(),
-        9 => "Big Straight".to_string(),
-        10 => "Choice".to_string(),
-        11 => "Yacht".to_string(),
-        _ => "Unknown".to_string(),
-    }
-}
-
-#[wasm_bindgen]
-pub fn get_category_name_ja(category_index: usize) -> String {
-    match category_index {
-        0 => "1の目".to_string(),
-        1 => "2の目".to_string(),
-        2 => "3の目".to_string(),
-        3 => "4の目".to_string(),
-        4 => "5の目".to_string(),
-        5 => "6の目".to_string(),
-        6 => "フルハウス".to_string(),
-        7 => "フォーオブアカインド".to_string(),
-        8 => "スモールストレート".to_string(),
-        9 => "ビッグストレート".to_string(),
-        10 => "チョイス".to_string(),
-        11 => "ヨット".to_string(),
-        _ => "不明".to_string(),
+    fn decide_cell(&self, game: &GameState) -> Option<Cell> {
+        self.decide_category(game).map(Cell::from)
     }
 }
 
+// ========== Utility Functions ==========
+
+/// 期待値が同じとみなす許容誤差
+const EXPECTED_TIE_EPSILON: f32 = 1e-3;
+
+/// 降順に並んだ期待値ごとに (最善との差, 隣の候補と同値か) を求める
+fn rank_expected(sorted: &[f32]) -> Vec<(f32, bool)> {
+    let best = sorted.first().copied().unwrap_or(0.0);
+    (0..sorted.len())
+        .map(|i| {
+            let tied_above = i > 0 && sorted[i - 1] - sorted[i] <= EXPECTED_TIE_EPSILON;
+            let tied_below =
+                i + 1 < sorted.len() && sorted[i] - sorted[i + 1] <= EXPECTED_TIE_EPSILON;
+            (best - sorted[i], tied_above || tied_below)
+        })
+        .collect()
+}
+
+/// DPテーブル（YDP1 形式）を実行時に読み込む
+/// embed-dp-table フィーチャーを無効にしてビルドした場合、AIやヒントを使う前に呼ぶ
+pub fn load_dp_table(data: &[u8]) -> Result<(), DpTableError> {
+    dp_table::load_table(data)
+}
+
+/// DPテーブルが利用可能か
+pub fn is_dp_table_loaded() -> bool {
+    dp_table::is_table_loaded()
+}
+
+/// このエンジンの得点ルールのチェックサム（読み込むDPテーブルのヘッダーと一致する必要がある）
+pub fn dp_table_rules_checksum() -> u32 {
+    dp_table::rules_checksum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::play::Strategy;
 
     #[test]
     fn test_yacht_score() {
@@ -917,4 +2206,265 @@ mod tests {
         let dice = [1, 1, 2, 3, 4];
         assert_eq!(calculate_score(&dice, Category::Ones), 2);
     }
+
+    #[test]
+    fn test_live_expected_score() {
+        let ai = YachtAI::new();
+        let mut game = GameState::new();
+        let initial = dp_table::get_initial_expected_score();
+        assert!((ai.live_expected_score(&game) - initial).abs() < 0.001);
+
+        // 1回目のロールの運 = ロール後の期待値 - 手番開始時の期待値
+        let luck = ai.roll_with_luck(&mut game).unwrap();
+        let live = ai.live_expected_score(&game);
+        assert!((luck - (live - initial)).abs() < 0.001);
+
+        // 最適に続けた期待値は任意のホールドの期待値以上
+        game.toggle_hold(0).unwrap();
+        game.toggle_hold(2).unwrap();
+        assert!(ai.held_expected_score(&game) <= live + 0.001);
+    }
+
+    #[test]
+    fn test_top_choices() {
+        let ai = YachtAI::new();
+        let mut game = GameState::new();
+        game.roll().unwrap();
+
+        let categories = ai.category_choices(&game, 5);
+        assert_eq!(categories.len(), 5);
+        assert!(categories
+            .windows(2)
+            .all(|w| w[0].expected >= w[1].expected));
+        assert_eq!(ai.category_choices(&game, 100).len(), 12);
+        let all = ai.category_choices(&game, 0);
+        assert_eq!(all.len(), 12);
+        assert_eq!(all[0].loss, 0.0);
+        assert!(all.iter().all(|c| c.loss >= 0.0));
+
+        let holds = ai.hold_choices(&game, 3);
+        assert_eq!(holds.len(), 3);
+        assert!(holds[1..].iter().all(|h| !h.stop));
+
+        // 同じ目の組のキープは1つにまとめられる
+        game.turn.dice.set_values([3, 3, 3, 3, 3]);
+        assert_eq!(ai.hold_choices(&game, 0).len(), 6);
+    }
+
+    #[test]
+    fn test_json_keeps_dice_fields() {
+        let mut game = GameState::new();
+        game.enter_roll([1, 2, 3, 4, 5]).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&game.to_json()).unwrap();
+        assert_eq!(json["dice"]["values"], serde_json::json!([1, 2, 3, 4, 5]));
+        assert_eq!(json["rolls_left"], 2);
+        assert!(json.get("turn").is_none());
+    }
+
+    #[test]
+    fn test_json_reads_old_score_events() {
+        let mut game = GameState::new();
+        game.enter_roll([1, 2, 3, 4, 5]).unwrap();
+        game.select(Category::BigStraight).unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&game.to_json()).unwrap();
+        // 以前の記録は欄ではなくカテゴリ名
+        let score = &mut json["history"][1]["Score"];
+        score.as_object_mut().unwrap().remove("cell");
+        score["category"] = "BigStraight".into();
+        let game: GameState = serde_json::from_value(json.clone()).unwrap();
+        assert!(matches!(
+            game.history()[1],
+            GameEvent::Score { cell, score: 30, .. } if cell == Cell::from(Category::BigStraight)
+        ));
+
+        // 欄の数がルールと合わない得点表は読まない
+        json["player_score"]["scores"] = serde_json::json!([null]);
+        assert!(serde_json::from_value::<GameState>(json).is_err());
+    }
+
+    #[test]
+    fn test_stand() {
+        let ai = YachtAI::new();
+        let mut game = GameState::new();
+        assert_eq!(game.stand(), Err(GameError::NotRolled));
+        game.roll().unwrap();
+        // ヨットが揃っていれば振り直さずに得点する
+        game.turn.dice.set_values([6, 6, 6, 6, 6]);
+        assert_eq!(ai.decide_holds(&game), HoldDecision::Stop);
+        let holds = ai.hold_choices(&game, 1);
+        assert!(holds[0].stop);
+
+        assert!(game.stand().is_ok());
+        assert_eq!(game.rolls_left(), 0);
+        assert_eq!(game.roll(), Err(GameError::NoRollsLeft));
+        assert_eq!(ai.decide_category(&game), Some(Category::Yacht));
+        assert_eq!(game.select(Category::Yacht), Ok(50));
+        assert_eq!(game.board(0).score(Category::Yacht), Some(50));
+    }
+
+    #[test]
+    fn test_enter_roll() {
+        let ai = YachtAI::new();
+        let mut game = GameState::new();
+        assert_eq!(
+            game.enter_roll([1, 2, 3, 4, 7]),
+            Err(GameError::InvalidDieValue)
+        );
+        assert_eq!(
+            game.enter_roll([0, 2, 3, 4, 5]),
+            Err(GameError::InvalidDieValue)
+        );
+        assert_eq!(game.rolls_left(), 3);
+
+        game.enter_roll([6, 6, 6, 2, 3]).unwrap();
+        assert_eq!(game.rolls_left(), 2);
+        let HoldDecision::Reroll(holds) = ai.decide_holds(&game) else {
+            panic!("should reroll");
+        };
+        assert_eq!(holds, [true, true, true, false, false]);
+        for (i, &hold) in holds.iter().enumerate() {
+            game.set_hold(i, hold).unwrap();
+        }
+        assert_eq!(
+            game.enter_roll([5, 6, 6, 6, 6]),
+            Err(GameError::HeldDieChanged)
+        );
+        game.enter_roll([6, 6, 6, 6, 1]).unwrap();
+        game.enter_roll([6, 6, 6, 6, 6]).unwrap();
+        assert_eq!(
+            game.enter_roll([6, 6, 6, 6, 6]),
+            Err(GameError::NoRollsLeft)
+        );
+        assert_eq!(game.potential_score(Category::Yacht), 50);
+        assert_eq!(ai.decide_category(&game), Some(Category::Yacht));
+        assert_eq!(game.select(Category::Yacht), Ok(50));
+        assert!(matches!(
+            game.history()[1],
+            GameEvent::Roll {
+                rolls_left: 2,
+                after: [6, 6, 6, 6, 1],
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_hold_rules() {
+        let mut game = GameState::with_hold_rule(HoldRule::Free);
+        game.roll().unwrap();
+        game.toggle_hold(0).unwrap();
+        game.roll().unwrap();
+        game.toggle_hold(0).unwrap();
+        assert!(!game.dice().held()[0]);
+
+        let mut game = GameState::with_hold_rule(HoldRule::Locking);
+        game.roll().unwrap();
+        game.toggle_hold(0).unwrap();
+        game.roll().unwrap();
+        assert_eq!(game.toggle_hold(0), Err(GameError::DieLocked));
+        assert!(game.dice().held()[0]);
+        assert!(game.dice().locked()[0]);
+    }
+
+    #[test]
+    fn test_lookahead_respects_lock_rule() {
+        let ai = YachtAI::new();
+        // 1を1個だけキープ: ロックされると最終振りで手放せない
+        let keep = [1, 0, 0, 0, 0, 0, 0, 0];
+        let mut memo = FinalRollMemo::new();
+        let free = ai.evaluate_two_rolls(&keep, 0, 0, HoldRule::Free, &mut memo);
+        let mut memo = FinalRollMemo::new();
+        let locking = ai.evaluate_two_rolls(&keep, 0, 0, HoldRule::Locking, &mut memo);
+        assert!(locking < free);
+
+        // 何もキープしなければ制約はない
+        let mut memo = FinalRollMemo::new();
+        let free =
+            ai.evaluate_two_rolls(&[0; dp_table::MAX_FACES], 0, 0, HoldRule::Free, &mut memo);
+        let mut memo = FinalRollMemo::new();
+        let locking = ai.evaluate_two_rolls(
+            &[0; dp_table::MAX_FACES],
+            0,
+            0,
+            HoldRule::Locking,
+            &mut memo,
+        );
+        assert!((locking - free).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_luck_skill_breakdown() {
+        for hold_rule in [HoldRule::Free, HoldRule::Locking] {
+            check_luck_skill_breakdown(GameState::with_hold_rule(hold_rule));
+        }
+        // ヘネラルはヘネラルの期待値で分解する
+        let mut game = GameState::with_ruleset(Ruleset::Generala);
+        game.set_seed(3);
+        check_luck_skill_breakdown(game);
+    }
+
+    #[test]
+    fn test_table_rulesets_reject_other_dice() {
+        let d8 = DiceModel::uniform(8).unwrap();
+        for ruleset in Ruleset::ALL {
+            let mut game = GameState::solo(ruleset.clone());
+            assert_eq!(
+                game.set_dice_model(d8.clone()),
+                Err(GameError::UnsupportedDice),
+                "{ruleset:?}"
+            );
+            assert_eq!(game.set_dice_model(DiceModel::default()), Ok(()));
+            assert!(game.dice_model().is_standard());
+
+            // 保存データを書き換えて d8 にしたゲームも読み込まない
+            let mut json: serde_json::Value = serde_json::from_str(&game.to_json()).unwrap();
+            json["dice_model"] = serde_json::to_value(&d8).unwrap();
+            assert!(
+                serde_json::from_value::<GameState>(json).is_err(),
+                "{ruleset:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_luck_skill_breakdown_tampered_history() {
+        let ai = YachtAI::new();
+        let mut game = GameState::new();
+        game.set_seed(4);
+        for _ in 0..4 {
+            ai.play_turn(&mut game).unwrap();
+        }
+        // 振れる回数や出目を書き換えた記録は飛ばす
+        let mut json: serde_json::Value = serde_json::from_str(&game.to_json()).unwrap();
+        let history = json["history"].as_array_mut().unwrap();
+        history[0]["Roll"]["rolls_left"] = 0.into();
+        history[1]["Roll"]["after"] = serde_json::json!([0, 7, 1, 1, 1]);
+        let tampered: GameState = serde_json::from_value(json).unwrap();
+        let b = ai.luck_skill_breakdown(&tampered, 0);
+        assert!(b.skill_loss.is_finite() && b.luck.is_finite());
+    }
+
+    fn check_luck_skill_breakdown(mut game: GameState) {
+        let ai = YachtAI::new();
+
+        while !game.is_game_over() {
+            if game.current_player() == 0 {
+                // ホールドせずに3回振って最初の空きカテゴリを選ぶ
+                while game.roll().is_ok() {}
+                let category = game.current_board().available_categories()[0];
+                game.select(category).unwrap();
+            } else {
+                ai.play_turn(&mut game).unwrap();
+            }
+        }
+
+        for seat in 0..2 {
+            let b = ai.luck_skill_breakdown(&game, seat);
+            assert!(b.skill_loss >= -0.001);
+            let reconstructed = b.baseline + b.luck - b.skill_loss;
+            assert!((reconstructed - b.final_score as f32).abs() < 0.1);
+        }
+        // AIは自分の評価関数どおりに動くので判断による損失はない
+        assert!(ai.luck_skill_breakdown(&game, 1).skill_loss.abs() < 0.01);
+    }
 }
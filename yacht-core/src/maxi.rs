@@ -0,0 +1,561 @@
+//! マキシヤッツィー（Maxi Yatzy、6個のダイス）
+//!
+//! 20カテゴリ: 1-6の目、ワンペア、ツーペア、スリーペア、スリーカード、フォーカード、ファイブカード、
+//! スモールストレート（1-5 で 15点）、ラージストレート（2-6 で 20点）、フルストレート（1-6 で 21点）、
+//! フルハウス（3+2）、キャッスル（3+3）、タワー（4+2）、チャンス、マキシヤッツィー（100点）。
+//! 上段が 84点以上でボーナス 50点。1手番に3回振れ、使わなかったロールは以降の手番に持ち越せる。
+//!
+//! 状態が多すぎて（20ビット × 上段累計 × 持ち越しロール数）DPテーブルは持たないので、
+//! AI は手番内の探索（`solver`）の葉を、カテゴリの目安点と持ち越しロールの価値で評価する。
+//! 持ち越したロール1回の価値も同じ探索で求める（今のスコアボードで1手番を4回振れるときと3回振れるときの差）。
+
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dp_table::{self, category, maxi_category, DicePattern};
+use crate::play::{Game, Strategy};
+use crate::solver::{keep_to_holds, Leaf, TurnSolver};
+use crate::turn::Turn;
+use crate::{Dice, GameError, HoldDecision, HoldRule};
+
+/// 1手番に振れる回数（持ち越し分は別）
+pub use crate::turn::ROLLS_PER_TURN;
+
+/// マキシヤッツィーのカテゴリ
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MaxiCategory {
+    Ones = 0,
+    Twos = 1,
+    Threes = 2,
+    Fours = 3,
+    Fives = 4,
+    Sixes = 5,
+    OnePair = 6,
+    TwoPairs = 7,
+    ThreePairs = 8,
+    ThreeOfAKind = 9,
+    FourOfAKind = 10,
+    FiveOfAKind = 11,
+    SmallStraight = 12,
+    LargeStraight = 13,
+    FullStraight = 14,
+    FullHouse = 15,
+    Castle = 16,
+    Tower = 17,
+    Chance = 18,
+    MaxiYatzy = 19,
+}
+
+impl MaxiCategory {
+    pub const ALL: [MaxiCategory; maxi_category::NUM_CATEGORIES] = [
+        MaxiCategory::Ones,
+        MaxiCategory::Twos,
+        MaxiCategory::Threes,
+        MaxiCategory::Fours,
+        MaxiCategory::Fives,
+        MaxiCategory::Sixes,
+        MaxiCategory::OnePair,
+        MaxiCategory::TwoPairs,
+        MaxiCategory::ThreePairs,
+        MaxiCategory::ThreeOfAKind,
+        MaxiCategory::FourOfAKind,
+        MaxiCategory::FiveOfAKind,
+        MaxiCategory::SmallStraight,
+        MaxiCategory::LargeStraight,
+        MaxiCategory::FullStraight,
+        MaxiCategory::FullHouse,
+        MaxiCategory::Castle,
+        MaxiCategory::Tower,
+        MaxiCategory::Chance,
+        MaxiCategory::MaxiYatzy,
+    ];
+
+    pub fn from_index(index: usize) -> Option<MaxiCategory> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// 英語の表示名
+    pub fn name(self) -> &'static str {
+        match self {
+            MaxiCategory::Ones => "Ones",
+            MaxiCategory::Twos => "Twos",
+            MaxiCategory::Threes => "Threes",
+            MaxiCategory::Fours => "Fours",
+            MaxiCategory::Fives => "Fives",
+            MaxiCategory::Sixes => "Sixes",
+            MaxiCategory::OnePair => "One Pair",
+            MaxiCategory::TwoPairs => "Two Pairs",
+            MaxiCategory::ThreePairs => "Three Pairs",
+            MaxiCategory::ThreeOfAKind => "Three of a Kind",
+            MaxiCategory::FourOfAKind => "Four of a Kind",
+            MaxiCategory::FiveOfAKind => "Five of a Kind",
+            MaxiCategory::SmallStraight => "Small Straight",
+            MaxiCategory::LargeStraight => "Large Straight",
+            MaxiCategory::FullStraight => "Full Straight",
+            MaxiCategory::FullHouse => "Full House",
+            MaxiCategory::Castle => "Castle",
+            MaxiCategory::Tower => "Tower",
+            MaxiCategory::Chance => "Chance",
+            MaxiCategory::MaxiYatzy => "Maxi Yatzy",
+        }
+    }
+
+    /// 日本語の表示名
+    pub fn name_ja(self) -> &'static str {
+        match self {
+            MaxiCategory::Ones => "1の目",
+            MaxiCategory::Twos => "2の目",
+            MaxiCategory::Threes => "3の目",
+            MaxiCategory::Fours => "4の目",
+            MaxiCategory::Fives => "5の目",
+            MaxiCategory::Sixes => "6の目",
+            MaxiCategory::OnePair => "ワンペア",
+            MaxiCategory::TwoPairs => "ツーペア",
+            MaxiCategory::ThreePairs => "スリーペア",
+            MaxiCategory::ThreeOfAKind => "スリーカード",
+            MaxiCategory::FourOfAKind => "フォーカード",
+            MaxiCategory::FiveOfAKind => "ファイブカード",
+            MaxiCategory::SmallStraight => "スモールストレート",
+            MaxiCategory::LargeStraight => "ラージストレート",
+            MaxiCategory::FullStraight => "フルストレート",
+            MaxiCategory::FullHouse => "フルハウス",
+            MaxiCategory::Castle => "キャッスル",
+            MaxiCategory::Tower => "タワー",
+            MaxiCategory::Chance => "チャンス",
+            MaxiCategory::MaxiYatzy => "マキシヤッツィー",
+        }
+    }
+}
+
+/// 得点計算（サイコロ値配列版）
+pub fn calculate_maxi_score(dice: &[u8; 6], category: MaxiCategory) -> u8 {
+    let pattern = dp_table::dice_to_pattern(dice);
+    dp_table::calculate_maxi_score(&pattern, category as usize)
+}
+
+/// マキシヤッツィーのスコアボード
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaxiScoreBoard {
+    scores: [Option<u8>; maxi_category::NUM_CATEGORIES],
+}
+
+impl MaxiScoreBoard {
+    pub fn new() -> MaxiScoreBoard {
+        MaxiScoreBoard::default()
+    }
+
+    /// 未使用のカテゴリに得点を記入する（使用済みなら false）
+    pub fn set_score(&mut self, category: MaxiCategory, score: u8) -> bool {
+        let slot = &mut self.scores[category as usize];
+        if slot.is_none() {
+            *slot = Some(score);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 記入済みの得点（未使用なら None）
+    pub fn score(&self, category: MaxiCategory) -> Option<u8> {
+        self.scores[category as usize]
+    }
+
+    pub fn is_used(&self, category: MaxiCategory) -> bool {
+        self.scores[category as usize].is_some()
+    }
+
+    pub fn upper_total(&self) -> u16 {
+        self.scores[..6].iter().flatten().map(|&s| s as u16).sum()
+    }
+
+    pub fn upper_bonus(&self) -> u16 {
+        if self.upper_total() >= maxi_category::UPPER_BONUS_THRESHOLD as u16 {
+            maxi_category::UPPER_BONUS_POINTS as u16
+        } else {
+            0
+        }
+    }
+
+    pub fn lower_total(&self) -> u16 {
+        self.scores[6..].iter().flatten().map(|&s| s as u16).sum()
+    }
+
+    pub fn total(&self) -> u16 {
+        self.upper_total() + self.upper_bonus() + self.lower_total()
+    }
+
+    /// 未使用のカテゴリ
+    pub fn available_categories(&self) -> Vec<MaxiCategory> {
+        MaxiCategory::ALL
+            .into_iter()
+            .filter(|&c| !self.is_used(c))
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.scores.iter().all(|s| s.is_some())
+    }
+}
+
+/// 下段のカテゴリの目安点（狙ったときのおおよその得点。空けておく価値として差し引く）
+///
+/// 手で決めた値で、このAIが 300ゲーム（シード 1000-1299）で各カテゴリに記入した平均点と
+/// 多くは数点以内で一致する。ファイブカードとマキシヤッツィーは 0点で埋まることが多く、
+/// 捨て場所として使えるように平均（約20点・約60点）より低くしてある。目安点を平均点に
+/// 置き換えても、別のシードでの平均合計は 406点 → 408点と誤差の範囲だった。
+const LOWER_PAR: [f32; maxi_category::NUM_CATEGORIES - 6] = [
+    10.0, 18.0, 20.0, 14.0, 15.0, 12.0, 10.0, 13.0, 12.0, 20.0, 15.0, 16.0, 25.0, 12.0,
+];
+
+/// 上段で各4個（ボーナスちょうどのペース）との差にかける重み
+const UPPER_PACE_WEIGHT: f32 = 1.0;
+
+/// 手番の途中状態（スコアボードと、この手番で残っているロール数）の葉の評価
+struct MaxiLeaf<'a> {
+    board: &'a MaxiScoreBoard,
+    /// 持ち越したロール1回の価値
+    roll_value: f32,
+    greedy: bool,
+}
+
+impl<'a> MaxiLeaf<'a> {
+    /// 持ち越しに価値を付けない葉
+    fn plain(board: &'a MaxiScoreBoard, greedy: bool) -> MaxiLeaf<'a> {
+        MaxiLeaf {
+            board,
+            roll_value: 0.0,
+            greedy,
+        }
+    }
+
+    /// 持ち越したロール1回の価値: 今のスコアボードで1手番を4回振れるときと3回振れるときの期待値の差
+    /// （最後のカテゴリなら持ち越したロールに価値はない）
+    fn banked_roll_value(board: &MaxiScoreBoard) -> f32 {
+        if board.available_categories().len() <= 1 {
+            return 0.0;
+        }
+        let mut solver = TurnSolver::new(MaxiLeaf::plain(board, false));
+        solver.turn_value(ROLLS_PER_TURN + 1) - solver.turn_value(ROLLS_PER_TURN)
+    }
+
+    /// cat に score を記入したときの価値（目安点との差）
+    fn category_value(&self, cat: usize, score: u8) -> f32 {
+        let score_f = score as f32;
+        if self.greedy {
+            // ボーナスを含めたその場の得点だけを見る
+            let threshold = maxi_category::UPPER_BONUS_THRESHOLD as u16;
+            let upper = self.board.upper_total();
+            let crosses =
+                category::is_upper(cat) && upper < threshold && upper + score as u16 >= threshold;
+            return score_f
+                + if crosses {
+                    maxi_category::UPPER_BONUS_POINTS as f32
+                } else {
+                    0.0
+                };
+        }
+        if !category::is_upper(cat) {
+            return score_f - LOWER_PAR[cat - 6];
+        }
+        let threshold = maxi_category::UPPER_BONUS_THRESHOLD as u16;
+        let upper = self.board.upper_total();
+        if upper >= threshold {
+            return score_f - 4.0 * (cat + 1) as f32;
+        }
+        let par = 4.0 * (cat + 1) as f32;
+        let bonus = if upper + score as u16 >= threshold {
+            maxi_category::UPPER_BONUS_POINTS as f32
+        } else {
+            0.0
+        };
+        score_f + bonus - par + (score_f - par) * UPPER_PACE_WEIGHT
+    }
+}
+
+impl Leaf for MaxiLeaf<'_> {
+    fn num_dice(&self) -> usize {
+        maxi_category::NUM_DICE
+    }
+
+    fn best_category(&self, dice: &DicePattern, rolls_left: u8) -> Option<(usize, f32)> {
+        let open = self.board.available_categories();
+        let bank = self.roll_value * rolls_left as f32;
+        let mut best: Option<(usize, f32)> = None;
+        for cat in open.into_iter().map(|c| c as usize) {
+            let score = dp_table::calculate_maxi_score(dice, cat);
+            let value = self.category_value(cat, score) + bank;
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((cat, value));
+            }
+        }
+        best
+    }
+}
+
+/// マキシヤッツィーのAI（目安点と持ち越しロールの価値による近似）
+#[derive(Clone, Debug, Default)]
+pub struct MaxiAI {
+    greedy: bool,
+    /// 最後に求めたスコアボードと、そこでの持ち越したロール1回の価値
+    /// （1手番の判断はどれも同じスコアボードなので使い回す）
+    roll_value: RefCell<Option<(MaxiScoreBoard, f32)>>,
+}
+
+impl MaxiAI {
+    /// 目安点と持ち越しロールの価値で先を見るAI
+    pub fn new() -> MaxiAI {
+        MaxiAI::default()
+    }
+
+    /// 比較用: その手番の得点が最大になるように打つAI
+    pub fn greedy() -> MaxiAI {
+        MaxiAI {
+            greedy: true,
+            ..MaxiAI::default()
+        }
+    }
+
+    fn leaf<'a>(&self, board: &'a MaxiScoreBoard) -> MaxiLeaf<'a> {
+        if self.greedy {
+            return MaxiLeaf::plain(board, true);
+        }
+        let mut cache = self.roll_value.borrow_mut();
+        let roll_value = match &*cache {
+            Some((cached, value)) if cached == board => *value,
+            _ => {
+                let value = MaxiLeaf::banked_roll_value(board);
+                *cache = Some((board.clone(), value));
+                value
+            }
+        };
+        MaxiLeaf {
+            roll_value,
+            ..MaxiLeaf::plain(board, false)
+        }
+    }
+}
+
+impl Strategy<MaxiGame, 6> for MaxiAI {
+    /// 振った後（持ち越し分を含めて残りのロールで）どのダイスをホールドして振り直すかを決める
+    fn decide_holds(&self, game: &MaxiGame) -> HoldDecision<6> {
+        let dice = game.dice().values();
+        let pattern = dp_table::dice_to_pattern(&dice);
+        let mut solver = TurnSolver::new(self.leaf(game.board()));
+        match solver.best_keep(&pattern, game.rolls_left()) {
+            Some((keep, _)) => HoldDecision::Reroll(keep_to_holds(&dice, &keep)),
+            None => HoldDecision::Stop,
+        }
+    }
+
+    /// 出目を記入するカテゴリを決める（残りのロールは持ち越しになる）
+    fn decide_cell(&self, game: &MaxiGame) -> Option<MaxiCategory> {
+        let pattern = dp_table::dice_to_pattern(&game.dice().values());
+        let (cat, _) = self
+            .leaf(game.board())
+            .best_category(&pattern, game.rolls_left())?;
+        MaxiCategory::from_index(cat)
+    }
+}
+
+/// 1人用のマキシヤッツィー
+#[derive(Clone, Debug)]
+pub struct MaxiGame {
+    turn: Turn<6>,
+    board: MaxiScoreBoard,
+}
+
+impl MaxiGame {
+    pub fn new() -> MaxiGame {
+        MaxiGame {
+            turn: Turn::with_banking(HoldRule::Free),
+            board: MaxiScoreBoard::new(),
+        }
+    }
+
+    /// 再現可能なロール用のシードを指定する
+    pub fn with_seed(seed: u64) -> MaxiGame {
+        let mut game = MaxiGame::new();
+        Game::set_seed(&mut game, seed);
+        game
+    }
+
+    pub fn dice(&self) -> &Dice<6> {
+        self.turn.dice()
+    }
+
+    pub fn board(&self) -> &MaxiScoreBoard {
+        &self.board
+    }
+
+    /// この手番で振れる残りの回数（持ち越し分を含む）
+    pub fn rolls_left(&self) -> u8 {
+        self.turn.rolls_left()
+    }
+
+    /// この手番に持ち越されてきたロール数
+    pub fn banked_rolls(&self) -> u8 {
+        self.turn.banked_rolls()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.board.is_complete()
+    }
+
+    /// ホールドしていないダイスを振る
+    pub fn roll(&mut self) -> Result<(), GameError> {
+        if self.is_game_over() {
+            return Err(GameError::GameOver);
+        }
+        self.turn.roll()?;
+        Ok(())
+    }
+
+    /// 実物のダイスで振った出目を入力する
+    pub fn enter_roll(&mut self, values: [u8; 6]) -> Result<(), GameError> {
+        if self.is_game_over() {
+            return Err(GameError::GameOver);
+        }
+        self.turn.enter_roll(values)?;
+        Ok(())
+    }
+
+    pub fn set_hold(&mut self, index: usize, hold: bool) -> Result<(), GameError> {
+        self.turn.set_hold(index, hold)
+    }
+
+    /// 記入して得点を返す（残ったロールは次の手番に持ち越す）
+    pub fn select(&mut self, category: MaxiCategory) -> Result<u8, GameError> {
+        if self.is_game_over() {
+            return Err(GameError::GameOver);
+        }
+        self.turn.check_rolled()?;
+        if self.board.is_used(category) {
+            return Err(GameError::CategoryUsed);
+        }
+        let score = calculate_maxi_score(&self.turn.dice().values(), category);
+        self.board.set_score(category, score);
+        self.turn.end();
+        Ok(score)
+    }
+}
+
+impl Default for MaxiGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game<6> for MaxiGame {
+    type Cell = MaxiCategory;
+
+    fn is_game_over(&self) -> bool {
+        MaxiGame::is_game_over(self)
+    }
+
+    fn dice(&self) -> &Dice<6> {
+        MaxiGame::dice(self)
+    }
+
+    fn rolls_left(&self) -> u8 {
+        MaxiGame::rolls_left(self)
+    }
+
+    fn roll(&mut self) -> Result<(), GameError> {
+        MaxiGame::roll(self)
+    }
+
+    fn set_hold(&mut self, index: usize, hold: bool) -> Result<(), GameError> {
+        MaxiGame::set_hold(self, index, hold)
+    }
+
+    /// 残りのロールは記入したときに持ち越すので、振り直しをやめても何もしない
+    fn stand(&mut self) -> Result<(), GameError> {
+        self.turn.check_rolled()
+    }
+
+    fn select(&mut self, category: MaxiCategory) -> Result<u8, GameError> {
+        MaxiGame::select(self, category)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.turn.set_seed(seed);
+    }
+
+    fn total(&self) -> u16 {
+        self.board.total()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maxi_scores() {
+        use MaxiCategory::*;
+        assert_eq!(calculate_maxi_score(&[3, 3, 5, 5, 6, 6], ThreePairs), 28);
+        assert_eq!(calculate_maxi_score(&[3, 3, 5, 5, 6, 6], TwoPairs), 22);
+        assert_eq!(calculate_maxi_score(&[3, 3, 3, 3, 6, 6], ThreePairs), 0);
+        assert_eq!(calculate_maxi_score(&[3, 3, 3, 5, 5, 5], FullHouse), 21);
+        assert_eq!(calculate_maxi_score(&[3, 3, 3, 5, 5, 5], Castle), 24);
+        assert_eq!(calculate_maxi_score(&[3, 3, 3, 5, 5, 1], Castle), 0);
+        assert_eq!(calculate_maxi_score(&[2, 2, 2, 2, 6, 6], Tower), 20);
+        assert_eq!(calculate_maxi_score(&[2, 2, 2, 2, 2, 2], Tower), 0);
+        assert_eq!(calculate_maxi_score(&[2, 2, 2, 2, 2, 6], FiveOfAKind), 10);
+        assert_eq!(calculate_maxi_score(&[1, 2, 3, 4, 5, 5], SmallStraight), 15);
+        assert_eq!(calculate_maxi_score(&[6, 2, 3, 4, 5, 5], LargeStraight), 20);
+        assert_eq!(calculate_maxi_score(&[6, 2, 3, 4, 5, 1], FullStraight), 21);
+        assert_eq!(calculate_maxi_score(&[6, 2, 3, 4, 5, 5], FullStraight), 0);
+        assert_eq!(calculate_maxi_score(&[4; 6], MaxiYatzy), 100);
+        assert_eq!(calculate_maxi_score(&[4; 6], Chance), 24);
+        assert_eq!(calculate_maxi_score(&[4; 6], Fours), 24);
+    }
+
+    #[test]
+    fn test_banked_rolls() {
+        let mut game = MaxiGame::with_seed(1);
+        assert_eq!(game.set_hold(0, true), Err(GameError::NotRolled));
+        game.roll().unwrap();
+        assert_eq!(game.rolls_left(), 2);
+        game.select(MaxiCategory::Chance).unwrap();
+        // 使わなかった2回を持ち越す
+        assert_eq!(game.banked_rolls(), 2);
+        assert_eq!(game.rolls_left(), 5);
+        for _ in 0..5 {
+            game.roll().unwrap();
+        }
+        assert_eq!(game.roll(), Err(GameError::NoRollsLeft));
+        assert_eq!(
+            game.select(MaxiCategory::Chance),
+            Err(GameError::CategoryUsed)
+        );
+        game.select(MaxiCategory::Ones).unwrap();
+        assert_eq!(game.banked_rolls(), 0);
+    }
+
+    #[test]
+    fn test_ai_game() {
+        let ai = MaxiAI::new();
+        let mut game = MaxiGame::new();
+        game.enter_roll([5; 6]).unwrap();
+        assert_eq!(ai.decide_holds(&game), HoldDecision::Stop);
+        assert_eq!(ai.decide_cell(&game), Some(MaxiCategory::MaxiYatzy));
+        let mut game = MaxiGame::new();
+        game.enter_roll([6, 6, 6, 6, 6, 1]).unwrap();
+        assert_eq!(
+            ai.decide_holds(&game),
+            HoldDecision::Reroll([true, true, true, true, true, false])
+        );
+
+        let mut game = MaxiGame::with_seed(3);
+        let mut banked = 0;
+        while !game.is_game_over() {
+            ai.play_turn(&mut game).unwrap();
+            banked = banked.max(game.banked_rolls());
+        }
+        assert_eq!(game.roll(), Err(GameError::GameOver));
+        assert!(game.board().total() > 150);
+        assert!(banked > 0);
+    }
+}
@@ -0,0 +1,328 @@
+//! AI でゲームを進める共通の流れ
+//!
+//! ゲーム（`Game`: `GameState`・`MaxiGame`）と、その判断をする AI（`Strategy`）の組なら、
+//! どのルールでも同じ `Strategy::play_turn`（振る → 宣言 → ホールド → … → 記入）と `simulate` で遊べる。
+//! ルールごとのモジュールが持つのは葉の評価と判断だけ。
+//! `GameState` のルールに合わせて AI を選ぶなら `RulesetAI`。
+
+use serde::{Deserialize, Serialize};
+
+use crate::custom::CustomAI;
+use crate::dice_model::DiceModel;
+use crate::triple::TripleAI;
+use crate::yamb::YambAI;
+use crate::yatzy::YatzyAI;
+use crate::{Cell, Dice, DpTableError, GameError, GameState, HoldDecision, Ruleset, YachtAI};
+
+/// AI が遊べるゲーム（N はダイスの個数）
+pub trait Game<const N: usize = 5> {
+    /// 記入する欄
+    type Cell: Copy;
+
+    fn is_game_over(&self) -> bool;
+
+    fn dice(&self) -> &Dice<N>;
+
+    fn rolls_left(&self) -> u8;
+
+    /// ホールドしていないダイスを振る
+    fn roll(&mut self) -> Result<(), GameError>;
+
+    fn set_hold(&mut self, index: usize, hold: bool) -> Result<(), GameError>;
+
+    /// 残りのロールを使わずに振り直しをやめる
+    fn stand(&mut self) -> Result<(), GameError>;
+
+    /// この手番で記入する行を宣言する（宣言のないルールは `CategoryUnavailable`）
+    fn announce(&mut self, _row: usize) -> Result<(), GameError> {
+        Err(GameError::CategoryUnavailable)
+    }
+
+    /// 記入して得点を返す
+    fn select(&mut self, cell: Self::Cell) -> Result<u8, GameError>;
+
+    /// 以降のロールをシードから決定的に生成する
+    fn set_seed(&mut self, seed: u64);
+
+    /// 最初の席（1人用なら唯一の席）の合計点数
+    fn total(&self) -> u16;
+}
+
+/// AIの手番の1ロール
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TurnRoll<const N: usize = 5> {
+    /// 振る前にホールドしたダイス（1回目は全て false）
+    #[serde(with = "crate::serde_array")]
+    pub holds: [bool; N],
+    /// 振った後の出目
+    #[serde(with = "crate::serde_array")]
+    pub dice: [u8; N],
+}
+
+/// AIの手番の記録
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TurnLog<C = crate::Cell, const N: usize = 5> {
+    pub rolls: Vec<TurnRoll<N>>,
+    /// 振り切る前に振り直しをやめた
+    pub stopped: bool,
+    /// 記入した欄
+    pub cell: C,
+    pub score: u8,
+}
+
+/// ゲームの判断をする AI
+pub trait Strategy<G: Game<N>, const N: usize = 5> {
+    /// このAIで遊べるゲームか（遊べないルールは `CategoryUnavailable`）
+    fn check_game(&self, _game: &G) -> Result<(), GameError> {
+        Ok(())
+    }
+
+    /// 1回目のロールの後に宣言する行を決める（宣言しないなら None）
+    fn decide_announcement(&self, _game: &G) -> Option<usize> {
+        None
+    }
+
+    /// どのダイスをホールドして振り直すか、振り直さずに記入するかを決める
+    fn decide_holds(&self, game: &G) -> HoldDecision<N>;
+
+    /// 出目を記入する欄を決める（記入できる欄がなければ None）
+    fn decide_cell(&self, game: &G) -> Option<G::Cell>;
+
+    /// 1手番を最後まで進める（振る → 宣言 → ホールド → … → 記入）
+    fn play_turn(&self, game: &mut G) -> Result<TurnLog<G::Cell, N>, GameError> {
+        self.check_game(game)?;
+        let mut rolls = Vec::new();
+        let mut holds = [false; N];
+        let mut stopped = false;
+        loop {
+            game.roll()?;
+            rolls.push(TurnRoll {
+                holds,
+                dice: game.dice().values(),
+            });
+            if rolls.len() == 1 {
+                if let Some(row) = self.decide_announcement(game) {
+                    game.announce(row)?;
+                }
+            }
+            if game.rolls_left() == 0 {
+                break;
+            }
+            match self.decide_holds(game) {
+                HoldDecision::Reroll(next) => {
+                    for (i, &hold) in next.iter().enumerate() {
+                        match game.set_hold(i, hold) {
+                            // ロックされたダイスは外せないのでそのまま
+                            Ok(()) | Err(GameError::DieLocked) => {}
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    holds = game.dice().held();
+                }
+                HoldDecision::Stop => {
+                    game.stand()?;
+                    stopped = true;
+                    break;
+                }
+            }
+        }
+        let cell = self.decide_cell(game).ok_or(GameError::NoCellChosen)?;
+        let score = game.select(cell)?;
+        Ok(TurnLog {
+            rolls,
+            stopped,
+            cell,
+            score,
+        })
+    }
+}
+
+/// `GameState` のルールに合わせた AI
+#[derive(Clone, Debug)]
+pub enum RulesetAI {
+    /// ヨットとヘネラル
+    Yacht(YachtAI),
+    Triple(TripleAI),
+    Yamb(YambAI),
+    Yatzy(YatzyAI),
+    Custom(Box<CustomAI>),
+}
+
+impl RulesetAI {
+    /// ruleset を遊ぶ AI（ヤッツィーのDPテーブルを読み込む前は `DpTableError::NotLoaded`）
+    ///
+    /// ハウスルールはここで期待値のテーブルを計算する（`CustomAI::new` 参照）。
+    /// 状態数が `custom::MAX_SOLVER_STATES` を超えるハウスルールは `CustomAI::greedy` で遊ぶ。
+    pub fn new(ruleset: &Ruleset) -> Result<RulesetAI, DpTableError> {
+        RulesetAI::with_dice(ruleset, DiceModel::standard())
+    }
+
+    /// game のルールとダイス（`GameState::set_dice_model`）で遊ぶ AI
+    pub fn for_game(game: &GameState) -> Result<RulesetAI, DpTableError> {
+        RulesetAI::with_dice(game.ruleset(), game.dice_model())
+    }
+
+    /// dice を振る前提の AI（標準以外のダイスで遊べるのはハウスルールだけ。`Ruleset::supports_dice`）
+    pub fn with_dice(ruleset: &Ruleset, dice: &DiceModel) -> Result<RulesetAI, DpTableError> {
+        Ok(match ruleset {
+            Ruleset::Yacht | Ruleset::Generala => RulesetAI::Yacht(YachtAI::new()),
+            Ruleset::Triple => RulesetAI::Triple(TripleAI::new()),
+            Ruleset::Yamb => RulesetAI::Yamb(YambAI::new()),
+            Ruleset::Yatzy => RulesetAI::Yatzy(YatzyAI::new()?),
+            Ruleset::Custom(rules) => {
+                let ai = CustomAI::with_dice(rules, dice.clone())
+                    .unwrap_or_else(|_| CustomAI::greedy_with_dice(rules, dice.clone()));
+                RulesetAI::Custom(Box::new(ai))
+            }
+        })
+    }
+
+    fn strategy(&self) -> &dyn Strategy<GameState> {
+        match self {
+            RulesetAI::Yacht(ai) => ai,
+            RulesetAI::Triple(ai) => ai,
+            RulesetAI::Yamb(ai) => ai,
+            RulesetAI::Yatzy(ai) => ai,
+            RulesetAI::Custom(ai) => ai.as_ref(),
+        }
+    }
+}
+
+impl Strategy<GameState> for RulesetAI {
+    fn check_game(&self, game: &GameState) -> Result<(), GameError> {
+        self.strategy().check_game(game)
+    }
+
+    fn decide_announcement(&self, game: &GameState) -> Option<usize> {
+        self.strategy().decide_announcement(game)
+    }
+
+    fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        self.strategy().decide_holds(game)
+    }
+
+    fn decide_cell(&self, game: &GameState) -> Option<Cell> {
+        self.strategy().decide_cell(game)
+    }
+}
+
+/// ai で games 回遊んだ各ゲームの合計点数
+///
+/// 各ゲームは new_game で始める（seed を指定すると i 番目のゲームはシード seed + i）。
+/// ai が遊べないゲーム（`Strategy::check_game`）や手番の失敗はそのエラーを返す。
+pub fn simulate<G, S, const N: usize>(
+    ai: &S,
+    new_game: impl Fn() -> G,
+    games: usize,
+    seed: Option<u64>,
+) -> Result<Vec<u16>, GameError>
+where
+    G: Game<N>,
+    S: Strategy<G, N>,
+{
+    (0..games)
+        .map(|i| {
+            let mut game = new_game();
+            if let Some(seed) = seed {
+                game.set_seed(seed.wrapping_add(i as u64));
+            }
+            while !game.is_game_over() {
+                ai.play_turn(&mut game)?;
+            }
+            Ok(game.total())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom::CustomRuleset;
+    use crate::maxi::{MaxiAI, MaxiGame};
+
+    fn average<G: Game<N>, S: Strategy<G, N>, const N: usize>(
+        ai: &S,
+        new_game: impl Fn() -> G,
+        games: usize,
+        seed: u64,
+    ) -> f32 {
+        let totals = simulate(ai, new_game, games, Some(seed)).unwrap();
+        totals.iter().map(|&t| t as f32).sum::<f32>() / games as f32
+    }
+
+    #[test]
+    fn test_play_turn_log() {
+        let ai = YachtAI::new();
+        let mut game = GameState::new();
+        game.set_seed(3);
+        let log = ai.play_turn(&mut game).unwrap();
+        assert!((1..=3).contains(&log.rolls.len()));
+        assert!(log.rolls[0].holds.iter().all(|&h| !h));
+        assert_eq!(log.stopped, log.rolls.len() < 3);
+        assert_eq!(game.board(0).cell(log.cell), Some(log.score));
+        assert_eq!(game.current_player(), 1);
+
+        // 遊べないルールではダイスを振らない
+        let mut triple = GameState::solo(Ruleset::Triple);
+        assert_eq!(
+            ai.play_turn(&mut triple).unwrap_err(),
+            GameError::CategoryUnavailable
+        );
+        assert_eq!(triple.rolls_left(), 3);
+        assert_eq!(
+            simulate(&ai, || GameState::solo(Ruleset::Triple), 1, None),
+            Err(GameError::CategoryUnavailable)
+        );
+
+        // 記入する欄を決められない AI はゲームの途中でもエラーにする
+        struct NoCell;
+        impl Strategy<GameState> for NoCell {
+            fn decide_holds(&self, _game: &GameState) -> HoldDecision {
+                HoldDecision::Stop
+            }
+
+            fn decide_cell(&self, _game: &GameState) -> Option<Cell> {
+                None
+            }
+        }
+        assert_eq!(
+            NoCell.play_turn(&mut GameState::new()).unwrap_err(),
+            GameError::NoCellChosen
+        );
+    }
+
+    #[test]
+    fn test_ruleset_ai() {
+        // ヨットの12カテゴリのハウスルールは状態数が多いので貪欲な AI で遊ぶ
+        let custom = Ruleset::Custom(std::sync::Arc::new(CustomRuleset::yacht()));
+        for ruleset in [
+            Ruleset::Yacht,
+            Ruleset::Generala,
+            Ruleset::Triple,
+            Ruleset::Yamb,
+            custom,
+        ] {
+            let ai = RulesetAI::new(&ruleset).unwrap();
+            let mut game = GameState::solo(ruleset.clone());
+            game.set_seed(5);
+            let log = ai.play_turn(&mut game).unwrap();
+            assert_eq!(game.board(0).cell(log.cell), Some(log.score), "{ruleset:?}");
+        }
+    }
+
+    #[test]
+    fn test_ai_beats_greedy() {
+        let triple = || GameState::solo(Ruleset::Triple);
+        let (dp, greedy) = (
+            average(&TripleAI::new(), triple, 20, 7),
+            average(&TripleAI::greedy(), triple, 20, 7),
+        );
+        assert!(dp > greedy, "triple: dp {dp} greedy {greedy}");
+
+        let (par, greedy) = (
+            average(&MaxiAI::new(), MaxiGame::new, 8, 1000),
+            average(&MaxiAI::greedy(), MaxiGame::new, 8, 1000),
+        );
+        assert!(par > greedy, "maxi: par {par} greedy {greedy}");
+    }
+}
@@ -0,0 +1,604 @@
+//! Python バインディング（`python` フィーチャー）
+//!
+//! wasm 版と同じエンジンとソルバーを PyO3 で公開する。
+//! 戻り値は list / tuple / dict などの Python ネイティブの値にする。
+//! カテゴリは 0-11 のインデックス、ホールドのルールは "locking" / "free" で指定する。
+//! ヨット以外のルール（"generala" / "triple" / "yamb" / "yatzy"）は `GameState(ruleset=...)` で作り、
+//! 欄を列と行で指定して遊ぶ（`select_cell`）。ハウスルールは `custom_rules` に JSON の定義を渡す。
+
+// #[pymethods] が生成する PyResult の変換に反応するため
+#![allow(clippy::useless_conversion)]
+
+use std::sync::Arc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::custom::CustomRuleset;
+use crate::dice_model::DiceModel;
+use crate::play::{self, RulesetAI, Strategy};
+use crate::triple::TripleAI;
+use crate::{
+    dp_table, yatzy, Category, Cell, DpTableError, GameError, GameEvent, GameState, HoldDecision,
+    HoldRule, Ruleset, ScoreBoard, YachtAI,
+};
+
+fn parse_category(index: usize) -> PyResult<Category> {
+    Category::from_index(index)
+        .ok_or_else(|| PyValueError::new_err(format!("invalid category index: {}", index)))
+}
+
+fn parse_hold_rule(name: &str) -> PyResult<HoldRule> {
+    match name {
+        "locking" => Ok(HoldRule::Locking),
+        "free" => Ok(HoldRule::Free),
+        _ => Err(PyValueError::new_err(format!(
+            "invalid hold rule: {}",
+            name
+        ))),
+    }
+}
+
+fn parse_ruleset(name: &str) -> PyResult<Ruleset> {
+    Ruleset::from_name(name)
+        .ok_or_else(|| PyValueError::new_err(format!("invalid ruleset: {}", name)))
+}
+
+fn hold_rule_name(hold_rule: HoldRule) -> &'static str {
+    match hold_rule {
+        HoldRule::Locking => "locking",
+        HoldRule::Free => "free",
+    }
+}
+
+fn parse_dice(dice: Vec<u8>) -> PyResult<[u8; 5]> {
+    let values: [u8; 5] = dice
+        .try_into()
+        .map_err(|_| PyValueError::new_err("dice must have exactly 5 values"))?;
+    if values.iter().any(|&v| !(1..=6).contains(&v)) {
+        return Err(PyValueError::new_err("dice values must be 1-6"));
+    }
+    Ok(values)
+}
+
+fn game_error(e: GameError) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn table_error(e: DpTableError) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn parse_seat(seat: u8) -> PyResult<u8> {
+    if seat > 1 {
+        return Err(PyValueError::new_err(format!("invalid seat: {}", seat)));
+    }
+    Ok(seat)
+}
+
+/// 得点表
+#[pyclass(name = "ScoreBoard", module = "yacht_core")]
+#[derive(Clone)]
+pub struct PyScoreBoard {
+    inner: ScoreBoard,
+}
+
+#[pymethods]
+impl PyScoreBoard {
+    #[new]
+    fn new() -> Self {
+        PyScoreBoard {
+            inner: ScoreBoard::new(),
+        }
+    }
+
+    /// 未使用のカテゴリに得点を記入する（使用済みなら False）
+    fn set_score(&mut self, category: usize, score: u8) -> PyResult<bool> {
+        Ok(self.inner.set_score(parse_category(category)?, score))
+    }
+
+    /// 記入済みの得点（未使用なら None）
+    fn get_score(&self, category: usize) -> PyResult<Option<u8>> {
+        Ok(self.inner.score(parse_category(category)?))
+    }
+
+    /// 欄（列・行）の得点（未記入なら None）
+    fn get_cell(&self, column: usize, row: usize) -> Option<u8> {
+        self.inner.cell(Cell::new(column, row))
+    }
+
+    fn is_used(&self, category: usize) -> PyResult<bool> {
+        Ok(self.inner.is_used(parse_category(category)?))
+    }
+
+    /// カテゴリごとの得点（未使用は None）
+    fn scores(&self) -> Vec<Option<u8>> {
+        Category::ALL.iter().map(|&c| self.inner.score(c)).collect()
+    }
+
+    #[getter]
+    fn upper_total(&self) -> u16 {
+        self.inner.upper_total()
+    }
+
+    #[getter]
+    fn upper_bonus(&self) -> u16 {
+        self.inner.upper_bonus()
+    }
+
+    #[getter]
+    fn lower_total(&self) -> u16 {
+        self.inner.lower_total()
+    }
+
+    #[getter]
+    fn total(&self) -> u16 {
+        self.inner.total()
+    }
+
+    #[getter]
+    fn upper_sum_capped(&self) -> usize {
+        self.inner.upper_sum_capped()
+    }
+
+    #[getter]
+    fn used_hands_mask(&self) -> usize {
+        self.inner.used_hands_mask()
+    }
+
+    fn available_categories(&self) -> Vec<usize> {
+        self.inner
+            .available_categories()
+            .into_iter()
+            .map(|c| c as usize)
+            .collect()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+
+    /// 最適に続けた場合の最終合計点数の期待値（手番の開始時点、期待値の表がないルールは None）
+    fn expected_score(&self) -> Option<f32> {
+        self.inner.expected_score()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ScoreBoard(total={})", self.inner.total())
+    }
+}
+
+/// ゲーム状態
+#[pyclass(name = "GameState", module = "yacht_core")]
+#[derive(Clone)]
+pub struct PyGameState {
+    inner: GameState,
+    /// `play_ai_turn` の AI（ハウスルールの期待値を毎手番計算しないように持っておく）
+    ai: Option<RulesetAI>,
+}
+
+#[pymethods]
+impl PyGameState {
+    /// hold_rule を省略するとそのルールの標準（ヨットは "locking"、それ以外は "free"）
+    /// custom_rules（ハウスルールの JSON 定義）を指定すると ruleset より優先する
+    #[new]
+    #[pyo3(signature = (hold_rule = None, seed = None, ruleset = "yacht", custom_rules = None))]
+    fn new(
+        hold_rule: Option<&str>,
+        seed: Option<u64>,
+        ruleset: &str,
+        custom_rules: Option<&str>,
+    ) -> PyResult<Self> {
+        let ruleset = match custom_rules {
+            Some(json) => Ruleset::Custom(Arc::new(
+                CustomRuleset::from_json(json).map_err(|e| PyValueError::new_err(e.to_string()))?,
+            )),
+            None => parse_ruleset(ruleset)?,
+        };
+        let hold_rule = match hold_rule {
+            Some(name) => parse_hold_rule(name)?,
+            None => ruleset.default_hold_rule(),
+        };
+        let mut inner = GameState::with_rules(ruleset, hold_rule);
+        if let Some(seed) = seed {
+            inner.set_seed(seed);
+        }
+        Ok(PyGameState { inner, ai: None })
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.inner.set_seed(seed);
+    }
+
+    /// 以降のロールで振るダイスを目ごとの重み（weights[0] が 1 の目、2-8 面）で設定する
+    /// 標準以外のダイスで遊べるのはハウスルールだけ
+    fn set_dice_weights(&mut self, weights: Vec<f64>) -> PyResult<()> {
+        let model =
+            DiceModel::weighted(&weights).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        self.inner.set_dice_model(model).map_err(game_error)
+    }
+
+    fn roll_dice(&mut self) -> PyResult<()> {
+        self.inner.roll().map_err(game_error)
+    }
+
+    /// 実物のダイスで振った出目を入力する（ホールドしたダイスは今の出目のまま）
+    fn enter_roll(&mut self, values: [u8; 5]) -> PyResult<()> {
+        self.inner.enter_roll(values).map_err(game_error)
+    }
+
+    fn stand(&mut self) -> PyResult<()> {
+        self.inner.stand().map_err(game_error)
+    }
+
+    fn toggle_hold(&mut self, index: usize) -> PyResult<()> {
+        self.inner.toggle_hold(index).map_err(game_error)
+    }
+
+    fn reset_holds(&mut self) {
+        self.inner.reset_holds();
+    }
+
+    /// 記入して得点を返す
+    fn select_category(&mut self, category: usize) -> PyResult<u8> {
+        self.inner
+            .select(parse_category(category)?)
+            .map_err(game_error)
+    }
+
+    /// 欄（列・行）を選んで記入し、得点を返す
+    fn select_cell(&mut self, column: usize, row: usize) -> PyResult<u8> {
+        self.inner
+            .select_cell(Cell::new(column, row))
+            .map_err(game_error)
+    }
+
+    /// この手番で記入する行を宣言する（宣言の列があるルールの1回目のロールの後）
+    fn announce(&mut self, row: usize) -> PyResult<()> {
+        self.inner.announce(row).map_err(game_error)
+    }
+
+    /// 現在の出目でそのカテゴリを選んだ場合の得点
+    fn potential_score(&self, category: usize) -> PyResult<u8> {
+        Ok(crate::calculate_score(
+            &self.inner.dice().values,
+            parse_category(category)?,
+        ))
+    }
+
+    #[getter]
+    fn dice(&self) -> Vec<u8> {
+        self.inner.dice().values.to_vec()
+    }
+
+    #[getter]
+    fn holds(&self) -> Vec<bool> {
+        self.inner.dice().held.to_vec()
+    }
+
+    #[getter]
+    fn locks(&self) -> Vec<bool> {
+        self.inner.dice().locked.to_vec()
+    }
+
+    #[getter]
+    fn hold_rule(&self) -> &'static str {
+        hold_rule_name(self.inner.hold_rule())
+    }
+
+    #[getter]
+    fn ruleset(&self) -> &'static str {
+        self.inner.ruleset().name()
+    }
+
+    #[getter]
+    fn rolls_left(&self) -> u8 {
+        self.inner.rolls_left()
+    }
+
+    #[getter]
+    fn current_player(&self) -> u8 {
+        self.inner.current_player
+    }
+
+    #[getter]
+    fn game_over(&self) -> bool {
+        self.inner.game_over
+    }
+
+    /// 指定した席（0 = プレイヤー, 1 = AI）の得点表のコピー
+    fn board(&self, seat: u8) -> PyResult<PyScoreBoard> {
+        Ok(PyScoreBoard {
+            inner: self.inner.board(parse_seat(seat)?).clone(),
+        })
+    }
+
+    /// 両席の合計点数
+    fn totals(&self) -> (u16, u16) {
+        (self.inner.board(0).total(), self.inner.board(1).total())
+    }
+
+    /// ゲームの記録（1手ごとの dict のリスト）
+    fn history<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.inner
+            .history()
+            .iter()
+            .map(|event| event_to_dict(py, event))
+            .collect()
+    }
+
+    fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GameState(dice={:?}, rolls_left={}, current_player={}, totals=({}, {}))",
+            self.inner.dice().values,
+            self.inner.rolls_left(),
+            self.inner.current_player,
+            self.inner.board(0).total(),
+            self.inner.board(1).total()
+        )
+    }
+}
+
+fn event_to_dict<'py>(py: Python<'py>, event: &GameEvent) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    match *event {
+        GameEvent::Roll {
+            seat,
+            rolls_left,
+            held,
+            before,
+            after,
+        } => {
+            dict.set_item("kind", "roll")?;
+            dict.set_item("seat", seat)?;
+            dict.set_item("rolls_left", rolls_left)?;
+            dict.set_item("held", held.to_vec())?;
+            dict.set_item("before", before.to_vec())?;
+            dict.set_item("after", after.to_vec())?;
+        }
+        GameEvent::Stand {
+            seat,
+            rolls_left,
+            dice,
+        } => {
+            dict.set_item("kind", "stand")?;
+            dict.set_item("seat", seat)?;
+            dict.set_item("rolls_left", rolls_left)?;
+            dict.set_item("dice", dice.to_vec())?;
+        }
+        GameEvent::Score {
+            seat,
+            rolls_left,
+            dice,
+            cell,
+            score,
+        } => {
+            dict.set_item("kind", "score")?;
+            dict.set_item("seat", seat)?;
+            dict.set_item("rolls_left", rolls_left)?;
+            dict.set_item("dice", dice.to_vec())?;
+            dict.set_item("column", cell.column)?;
+            dict.set_item("category", cell.row)?;
+            dict.set_item("score", score)?;
+        }
+        GameEvent::Announce { seat, row } => {
+            dict.set_item("kind", "announce")?;
+            dict.set_item("seat", seat)?;
+            dict.set_item("row", row)?;
+        }
+    }
+    Ok(dict)
+}
+
+/// 出目とカテゴリから得点を計算
+#[pyfunction]
+fn calculate_score(dice: Vec<u8>, category: usize) -> PyResult<u8> {
+    Ok(crate::calculate_score(
+        &parse_dice(dice)?,
+        parse_category(category)?,
+    ))
+}
+
+/// 上段累計（63上限）と使用済みカテゴリのビットマスクから、残りの手番で得られる点数の期待値
+#[pyfunction]
+fn get_expected_score(upper_sum: usize, used_hands: usize) -> PyResult<f32> {
+    if upper_sum > 63 || used_hands >= 1 << dp_table::NUM_CATEGORIES {
+        return Err(PyValueError::new_err("state out of range"));
+    }
+    Ok(dp_table::get_expected_score(upper_sum, used_hands))
+}
+
+/// カテゴリ選択の候補（期待値の高い順、n = 0 なら全て）
+#[pyfunction]
+#[pyo3(signature = (game, n = 0))]
+fn top_category_choices<'py>(
+    py: Python<'py>,
+    game: &PyGameState,
+    n: usize,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    YachtAI::new()
+        .category_choices(&game.inner, n)
+        .into_iter()
+        .map(|c| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("category", c.category as usize)?;
+            dict.set_item("name", c.category.name())?;
+            dict.set_item("score", c.score)?;
+            dict.set_item("expected", c.expected)?;
+            dict.set_item("loss", c.loss)?;
+            dict.set_item("tied", c.tied)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// キープの候補（期待値の高い順、n = 0 なら全て）
+#[pyfunction]
+#[pyo3(signature = (game, n = 0))]
+fn top_hold_choices<'py>(
+    py: Python<'py>,
+    game: &PyGameState,
+    n: usize,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    YachtAI::new()
+        .hold_choices(&game.inner, n)
+        .into_iter()
+        .map(|c| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("holds", c.holds.to_vec())?;
+            dict.set_item("stop", c.stop)?;
+            dict.set_item("expected", c.expected)?;
+            dict.set_item("loss", c.loss)?;
+            dict.set_item("tied", c.tied)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// AIが推奨するホールド（振り直しをやめるなら None）
+#[pyfunction]
+fn hold_decision(game: &PyGameState) -> Option<Vec<bool>> {
+    match YachtAI::new().decide_holds(&game.inner) {
+        HoldDecision::Stop => None,
+        HoldDecision::Reroll(holds) => Some(holds.to_vec()),
+    }
+}
+
+/// AIが推奨するカテゴリ（決まらなければ None）
+#[pyfunction]
+fn category_decision(game: &PyGameState) -> Option<usize> {
+    YachtAI::new()
+        .decide_category(&game.inner)
+        .map(|c| c as usize)
+}
+
+/// 手番の席が現在の状態から最適に続けた場合の最終合計点数の期待値
+#[pyfunction]
+fn live_expected_score(game: &PyGameState) -> f32 {
+    YachtAI::new().live_expected_score(&game.inner)
+}
+
+/// 指定した席の得点を運と実力に分解
+#[pyfunction]
+fn luck_skill_breakdown<'py>(
+    py: Python<'py>,
+    game: &PyGameState,
+    seat: u8,
+) -> PyResult<Bound<'py, PyDict>> {
+    let b = YachtAI::new().luck_skill_breakdown(&game.inner, parse_seat(seat)?);
+    let dict = PyDict::new_bound(py);
+    dict.set_item("baseline", b.baseline)?;
+    dict.set_item("luck", b.luck)?;
+    dict.set_item("skill_loss", b.skill_loss)?;
+    dict.set_item("final_score", b.final_score)?;
+    Ok(dict)
+}
+
+/// AIの手番を1回進める（その手番の記録を dict で返す。AI はゲームのルールに合わせて選ぶ）
+#[pyfunction]
+fn play_ai_turn<'py>(py: Python<'py>, game: &mut PyGameState) -> PyResult<Bound<'py, PyDict>> {
+    // ダイスを設定し直したら AI も作り直す
+    let ai = match &mut game.ai {
+        Some(ai) if ai.check_game(&game.inner).is_ok() => ai,
+        ai => ai.insert(RulesetAI::for_game(&game.inner).map_err(table_error)?),
+    };
+    let log = ai.play_turn(&mut game.inner).map_err(game_error)?;
+    let rolls: Vec<(Vec<bool>, Vec<u8>)> = log
+        .rolls
+        .iter()
+        .map(|r| (r.holds.to_vec(), r.dice.to_vec()))
+        .collect();
+    let dict = PyDict::new_bound(py);
+    dict.set_item("rolls", rolls)?;
+    dict.set_item("stopped", log.stopped)?;
+    dict.set_item("column", log.cell.column)?;
+    dict.set_item("category", log.cell.row)?;
+    dict.set_item("score", log.score)?;
+    Ok(dict)
+}
+
+/// ヤッツィーのDPテーブル（YDP1 形式）を読み込む
+/// embed-yatzy-table フィーチャーなしのビルドでは、ヤッツィーの AI を使う前に呼ぶ
+#[pyfunction]
+fn load_yatzy_table(data: &[u8]) -> PyResult<()> {
+    yatzy::load_table(data).map_err(table_error)
+}
+
+/// 両席をAIが打つゲームを games 回シミュレーションし、各ゲームの (席0, 席1) の合計点数を返す
+/// seed を指定すると i 番目のゲームはシード seed + i で再現できる
+#[pyfunction]
+#[pyo3(signature = (games, seed = None, hold_rule = "locking"))]
+fn simulate(
+    py: Python<'_>,
+    games: usize,
+    seed: Option<u64>,
+    hold_rule: &str,
+) -> PyResult<Vec<(u16, u16)>> {
+    let hold_rule = parse_hold_rule(hold_rule)?;
+    py.allow_threads(|| {
+        let ai = YachtAI::new();
+        (0..games)
+            .map(|i| {
+                let mut game = GameState::with_hold_rule(hold_rule);
+                if let Some(seed) = seed {
+                    game.set_seed(seed.wrapping_add(i as u64));
+                }
+                while !game.game_over {
+                    ai.play_turn(&mut game)?;
+                }
+                Ok((game.board(0).total(), game.board(1).total()))
+            })
+            .collect::<Result<Vec<_>, GameError>>()
+    })
+    .map_err(game_error)
+}
+
+/// トリプルヨット（×1・×2・×3 の3列）を games 回シミュレーションし、各ゲームの合計点数を返す
+/// greedy = True ならその手番の得点だけを見るAIで打つ（比較用）
+#[pyfunction]
+#[pyo3(signature = (games, seed = None, greedy = false))]
+fn simulate_triple(
+    py: Python<'_>,
+    games: usize,
+    seed: Option<u64>,
+    greedy: bool,
+) -> PyResult<Vec<u16>> {
+    let ai = if greedy {
+        TripleAI::greedy()
+    } else {
+        TripleAI::new()
+    };
+    py.allow_threads(|| play::simulate(&ai, || GameState::solo(Ruleset::Triple), games, seed))
+        .map_err(game_error)
+}
+
+#[pymodule]
+fn yacht_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyScoreBoard>()?;
+    m.add_class::<PyGameState>()?;
+    m.add(
+        "CATEGORY_NAMES",
+        (0..dp_table::NUM_CATEGORIES)
+            .filter_map(Category::from_index)
+            .map(Category::name)
+            .collect::<Vec<_>>(),
+    )?;
+    m.add_function(wrap_pyfunction!(calculate_score, m)?)?;
+    m.add_function(wrap_pyfunction!(get_expected_score, m)?)?;
+    m.add_function(wrap_pyfunction!(top_category_choices, m)?)?;
+    m.add_function(wrap_pyfunction!(top_hold_choices, m)?)?;
+    m.add_function(wrap_pyfunction!(hold_decision, m)?)?;
+    m.add_function(wrap_pyfunction!(category_decision, m)?)?;
+    m.add_function(wrap_pyfunction!(live_expected_score, m)?)?;
+    m.add_function(wrap_pyfunction!(luck_skill_breakdown, m)?)?;
+    m.add_function(wrap_pyfunction!(play_ai_turn, m)?)?;
+    m.add_function(wrap_pyfunction!(load_yatzy_table, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_triple, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,353 @@
+//! スコア記録だけのモード（実物のダイスと紙のいらないスコアシート）
+//!
+//! 任意の人数のプレイヤーごとに `ScoreBoard` を持ち、カテゴリごとに出目か得点を記入する。
+//! 手番の順番やロールの回数は管理しないので、記入の順番は自由で、記入済みの欄は訂正できる。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{calculate_score, dp_table, Category, ScoreBoard};
+
+/// 1つの欄への記入
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Entry {
+    /// 出目（得点は `calculate_score` で計算する）
+    Dice([u8; 5]),
+    /// 得点を直接書く（そのカテゴリで出せる点数であること）
+    Score(u8),
+}
+
+/// 記入できなかった理由
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScorekeeperError {
+    /// プレイヤーの番号が範囲外
+    UnknownPlayer,
+    /// 出目が 1-6 ではない
+    InvalidDieValue,
+    /// そのカテゴリではありえない得点
+    InvalidScore,
+    /// 記入済み（訂正は `correct` で行う）
+    CategoryUsed,
+    /// 未記入の欄は訂正・消去できない
+    CategoryEmpty,
+}
+
+impl fmt::Display for ScorekeeperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ScorekeeperError::UnknownPlayer => "no such player",
+            ScorekeeperError::InvalidDieValue => "die value must be 1-6",
+            ScorekeeperError::InvalidScore => "score is not possible in this category",
+            ScorekeeperError::CategoryUsed => "category is already filled",
+            ScorekeeperError::CategoryEmpty => "category is not filled",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ScorekeeperError {}
+
+/// そのカテゴリで出せる得点か（5個のダイスの全パターンで確かめる）
+pub fn is_possible_score(category: Category, score: u8) -> bool {
+    dp_table::dice_patterns::get_patterns(5)
+        .iter()
+        .any(|p| dp_table::calculate_score(&p.pattern, category as usize) == score)
+}
+
+/// 記入を得点に変換する
+pub fn entry_score(category: Category, entry: Entry) -> Result<u8, ScorekeeperError> {
+    match entry {
+        Entry::Dice(dice) => {
+            if dice.iter().any(|v| !(1..=6).contains(v)) {
+                return Err(ScorekeeperError::InvalidDieValue);
+            }
+            Ok(calculate_score(&dice, category))
+        }
+        Entry::Score(score) => {
+            if is_possible_score(category, score) {
+                Ok(score)
+            } else {
+                Err(ScorekeeperError::InvalidScore)
+            }
+        }
+    }
+}
+
+/// 途中経過
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunningTotals {
+    pub upper_total: u16,
+    pub upper_bonus: u16,
+    pub lower_total: u16,
+    pub total: u16,
+    /// ボーナスまであと何点か（達成済みなら 0）
+    pub bonus_remaining: u16,
+    /// 記入済みの上段を各3個ずつ（ボーナスちょうどのペース）と比べた差
+    pub bonus_pace: i16,
+}
+
+impl RunningTotals {
+    pub fn of(board: &ScoreBoard) -> RunningTotals {
+        let upper_total = board.upper_total();
+        let par: u16 = Category::ALL[..6]
+            .iter()
+            .filter(|&&c| board.is_used(c))
+            .map(|&c| 3 * (c as u16 + 1))
+            .sum();
+        RunningTotals {
+            upper_total,
+            upper_bonus: board.upper_bonus(),
+            lower_total: board.lower_total(),
+            total: board.total(),
+            bonus_remaining: (dp_table::UPPER_BONUS_THRESHOLD as u16).saturating_sub(upper_total),
+            bonus_pace: upper_total as i16 - par as i16,
+        }
+    }
+}
+
+/// 最終順位の1行
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    /// 1 から（同点は同順位）
+    pub rank: usize,
+    pub player: usize,
+    pub name: String,
+    pub total: u16,
+}
+
+/// 1人分のスコアシート
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerSheet {
+    pub name: String,
+    board: ScoreBoard,
+    entries: [Option<Entry>; 12],
+}
+
+impl PlayerSheet {
+    pub fn board(&self) -> &ScoreBoard {
+        &self.board
+    }
+
+    /// 記入した内容（未記入なら None）
+    pub fn entry(&self, category: Category) -> Option<Entry> {
+        self.entries[category as usize]
+    }
+
+    pub fn totals(&self) -> RunningTotals {
+        RunningTotals::of(&self.board)
+    }
+}
+
+/// 全員のスコアシート
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Scorekeeper {
+    players: Vec<PlayerSheet>,
+}
+
+impl Scorekeeper {
+    pub fn new<S: AsRef<str>>(names: &[S]) -> Scorekeeper {
+        let mut keeper = Scorekeeper::default();
+        for name in names {
+            keeper.add_player(name.as_ref());
+        }
+        keeper
+    }
+
+    /// プレイヤーを加えて番号を返す
+    pub fn add_player(&mut self, name: &str) -> usize {
+        self.players.push(PlayerSheet {
+            name: name.to_string(),
+            board: ScoreBoard::new(),
+            entries: [None; 12],
+        });
+        self.players.len() - 1
+    }
+
+    pub fn players(&self) -> &[PlayerSheet] {
+        &self.players
+    }
+
+    pub fn player(&self, player: usize) -> Option<&PlayerSheet> {
+        self.players.get(player)
+    }
+
+    fn sheet_mut(&mut self, player: usize) -> Result<&mut PlayerSheet, ScorekeeperError> {
+        self.players
+            .get_mut(player)
+            .ok_or(ScorekeeperError::UnknownPlayer)
+    }
+
+    /// 未記入の欄に記入して得点を返す
+    pub fn record(
+        &mut self,
+        player: usize,
+        category: Category,
+        entry: Entry,
+    ) -> Result<u8, ScorekeeperError> {
+        let sheet = self.sheet_mut(player)?;
+        if sheet.board.is_used(category) {
+            return Err(ScorekeeperError::CategoryUsed);
+        }
+        let score = entry_score(category, entry)?;
+        sheet.board.set_score(category, score);
+        sheet.entries[category as usize] = Some(entry);
+        Ok(score)
+    }
+
+    /// 記入済みの欄を書き直して新しい得点を返す（不正な記入なら元のまま）
+    pub fn correct(
+        &mut self,
+        player: usize,
+        category: Category,
+        entry: Entry,
+    ) -> Result<u8, ScorekeeperError> {
+        let sheet = self.sheet_mut(player)?;
+        if !sheet.board.is_used(category) {
+            return Err(ScorekeeperError::CategoryEmpty);
+        }
+        let score = entry_score(category, entry)?;
+        sheet.board.clear_score(category);
+        sheet.board.set_score(category, score);
+        sheet.entries[category as usize] = Some(entry);
+        Ok(score)
+    }
+
+    /// 記入済みの欄を消す
+    pub fn clear(&mut self, player: usize, category: Category) -> Result<(), ScorekeeperError> {
+        let sheet = self.sheet_mut(player)?;
+        sheet
+            .board
+            .clear_score(category)
+            .ok_or(ScorekeeperError::CategoryEmpty)?;
+        sheet.entries[category as usize] = None;
+        Ok(())
+    }
+
+    pub fn totals(&self, player: usize) -> Option<RunningTotals> {
+        self.player(player).map(PlayerSheet::totals)
+    }
+
+    /// 全員の全欄が埋まった
+    pub fn is_complete(&self) -> bool {
+        self.players.iter().all(|p| p.board.is_complete())
+    }
+
+    /// 合計点の高い順（同点は同順位で、追加した順に並べる）
+    pub fn ranking(&self) -> Vec<Standing> {
+        let mut order: Vec<usize> = (0..self.players.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.players[i].board.total()));
+        let mut standings: Vec<Standing> = Vec::with_capacity(order.len());
+        for (position, &player) in order.iter().enumerate() {
+            let total = self.players[player].board.total();
+            let rank = match standings.last() {
+                Some(prev) if prev.total == total => prev.rank,
+                _ => position + 1,
+            };
+            standings.push(Standing {
+                rank,
+                player,
+                name: self.players[player].name.clone(),
+                total,
+            });
+        }
+        standings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_possible_scores() {
+        assert!(is_possible_score(Category::Threes, 12));
+        assert!(!is_possible_score(Category::Threes, 13));
+        assert!(!is_possible_score(Category::Threes, 18));
+        assert!(is_possible_score(Category::Yacht, 50));
+        assert!(!is_possible_score(Category::Yacht, 25));
+        assert!(is_possible_score(Category::Choice, 5));
+        assert!(!is_possible_score(Category::Choice, 4));
+        assert!(is_possible_score(Category::FullHouse, 0));
+        assert_eq!(
+            entry_score(Category::FullHouse, Entry::Dice([2, 2, 5, 5, 5])),
+            Ok(19)
+        );
+        assert_eq!(
+            entry_score(Category::Choice, Entry::Dice([2, 2, 5, 5, 0])),
+            Err(ScorekeeperError::InvalidDieValue)
+        );
+    }
+
+    #[test]
+    fn test_scorekeeping() {
+        let mut keeper = Scorekeeper::new(&["alice", "bob", "carol"]);
+        assert_eq!(
+            keeper.record(0, Category::Sixes, Entry::Dice([6, 6, 6, 6, 1])),
+            Ok(24)
+        );
+        assert_eq!(
+            keeper.record(0, Category::Sixes, Entry::Score(6)),
+            Err(ScorekeeperError::CategoryUsed)
+        );
+        assert_eq!(
+            keeper.record(3, Category::Sixes, Entry::Score(6)),
+            Err(ScorekeeperError::UnknownPlayer)
+        );
+        assert_eq!(
+            keeper.record(1, Category::Fives, Entry::Score(7)),
+            Err(ScorekeeperError::InvalidScore)
+        );
+        keeper.record(1, Category::Fives, Entry::Score(10)).unwrap();
+
+        let totals = keeper.totals(0).unwrap();
+        assert_eq!(totals.upper_total, 24);
+        assert_eq!(totals.bonus_remaining, 39);
+        assert_eq!(totals.bonus_pace, 6);
+        assert_eq!(keeper.totals(1).unwrap().bonus_pace, -5);
+
+        // 訂正
+        assert_eq!(
+            keeper.correct(0, Category::Ones, Entry::Score(1)),
+            Err(ScorekeeperError::CategoryEmpty)
+        );
+        assert_eq!(
+            keeper.correct(0, Category::Sixes, Entry::Score(5)),
+            Err(ScorekeeperError::InvalidScore)
+        );
+        assert_eq!(keeper.totals(0).unwrap().upper_total, 24);
+        assert_eq!(keeper.correct(0, Category::Sixes, Entry::Score(18)), Ok(18));
+        assert_eq!(
+            keeper.player(0).unwrap().entry(Category::Sixes),
+            Some(Entry::Score(18))
+        );
+        assert_eq!(keeper.totals(0).unwrap().bonus_pace, 0);
+        keeper.clear(1, Category::Fives).unwrap();
+        assert_eq!(
+            keeper.clear(1, Category::Fives),
+            Err(ScorekeeperError::CategoryEmpty)
+        );
+
+        // ボーナスと順位
+        for (i, c) in Category::ALL[..5].iter().enumerate() {
+            let face = i as u8 + 1;
+            keeper.record(0, *c, Entry::Score(face * 3)).unwrap();
+            keeper.record(2, *c, Entry::Score(face * 3)).unwrap();
+        }
+        keeper.record(2, Category::Sixes, Entry::Score(18)).unwrap();
+        assert!(!keeper.is_complete());
+        let totals = keeper.totals(0).unwrap();
+        assert_eq!(
+            (totals.upper_total, totals.upper_bonus, totals.total),
+            (63, 35, 98)
+        );
+        assert_eq!(totals.bonus_remaining, 0);
+
+        let ranking = keeper.ranking();
+        let ranks: Vec<_> = ranking
+            .iter()
+            .map(|s| (s.rank, s.name.as_str(), s.total))
+            .collect();
+        assert_eq!(ranks, [(1, "alice", 98), (1, "carol", 98), (3, "bob", 0)]);
+    }
+}
@@ -0,0 +1,1061 @@
+//! ローカル対戦サーバー（`server` フィーチャー）
+//!
+//! 名前付きのルームごとに権威ある `GameState` を持ち、WebSocket で接続したクライアントの
+//! 操作を検証して適用し、変化のたびにルームの全員へ状態を配信する。AI の席は `YachtAI` が打つ
+//! （ルームごとのロックだけを持ち、ブロッキング用のスレッドで打つので、他のルームや接続の処理は止まらない）。
+//! 起動は `cargo run --release --features server --bin yacht-server -- 127.0.0.1:9000`。
+//!
+//! メッセージは JSON のテキストフレームで、`type` フィールドで種類を区別する。
+//!
+//! クライアント → サーバー
+//! - `{"type":"join","room":"r1","name":"alice","vs_ai":false}` 空いている席に座る
+//!   （`vs_ai` はルームを作るときだけ有効で、席1を AI にする）
+//! - `{"type":"entropy","entropy":"..."}` 検証可能なダイス用の任意の文字列（席ごとに1回、最初のロールまで）
+//! - `{"type":"spectate","room":"r1"}` 観戦する
+//! - `{"type":"resume","room":"r1","token":"..."}` 切断した席に戻る
+//! - `{"type":"roll"}` / `{"type":"stand"}` / `{"type":"toggle_hold","index":2}` /
+//!   `{"type":"select","category":"Yacht"}` 手番の操作
+//!
+//! サーバー → クライアント
+//! - `{"type":"joined","seat":0,"token":"...","commitment":"..."}` 席の割り当てとダイスのコミットメント
+//!   （観戦では seat / token は null）
+//! - `{"type":"state","room":{...}}` ルームの全状態（参加・再接続の直後と変化のたび）
+//! - `{"type":"error","message":"..."}` 受け付けなかった要求（状態は変わらない）
+//!
+//! ダイスは `fair` のコミット・リビール方式で振る。`joined` でコミットメントを渡し、それを見た
+//! プレイヤーが `entropy` を送る。人間の席が全員エントロピーを送るまで最初のロールは受け付けない
+//! （どのプレイヤーもサーバーだけを信じなくてよい）。最初のロールで全員のエントロピーを合わせて
+//! シードを確定し（以後のエントロピーは受け付けない）、終了後にサーバーシードを公開する。
+//! `RoomView::fair_record` で `fair::verify` に渡す記録を作れる。
+//!
+//! 接続が全て切れたルームは、再接続を待つ席がなくなった時点（ゲームが終わっているか、
+//! 切断から `RESUME_GRACE` が過ぎた）で消す。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::fair::{FairDice, FairRecord};
+use crate::play::Strategy;
+use crate::{Category, GameError, GameState, YachtAI};
+
+/// クライアントからのメッセージ
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join {
+        room: String,
+        name: String,
+        #[serde(default)]
+        vs_ai: bool,
+    },
+    Entropy {
+        entropy: String,
+    },
+    Spectate {
+        room: String,
+    },
+    Resume {
+        room: String,
+        token: String,
+    },
+    Roll,
+    Stand,
+    ToggleHold {
+        index: usize,
+    },
+    Select {
+        category: Category,
+    },
+}
+
+/// サーバーからのメッセージ
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Joined {
+        seat: Option<u8>,
+        /// 再接続用のトークン（席についたときのみ）
+        token: Option<String>,
+        /// ダイスのコミットメント（エントロピーはこれを見てから送る）
+        commitment: String,
+    },
+    State {
+        room: Box<RoomView>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// 席の公開情報
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SeatView {
+    pub name: String,
+    pub ai: bool,
+    /// 人間の席が接続中か（AI は常に true）
+    pub connected: bool,
+}
+
+/// 検証可能なダイスの公開情報
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FairView {
+    /// ルーム作成時に公開したコミットメント
+    pub commitment: String,
+    /// プレイヤーから受け取ったエントロピー（受け取った順）
+    pub client_entropy: Vec<String>,
+    /// サーバーシード（ゲーム終了後のみ）
+    pub server_seed: Option<String>,
+}
+
+/// ルームの全状態（クライアントはこれだけで画面を再構成できる）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoomView {
+    pub name: String,
+    pub seats: [Option<SeatView>; 2],
+    pub spectators: usize,
+    /// 両方の席が埋まり、操作を受け付けている
+    pub started: bool,
+    pub game: GameState,
+    pub fair: FairView,
+}
+
+impl RoomView {
+    /// ゲーム終了後、ダイスを検証するための記録
+    pub fn fair_record(&self) -> Option<FairRecord> {
+        Some(FairRecord {
+            commitment: self.fair.commitment.clone(),
+            server_seed: self.fair.server_seed.clone()?,
+            client_entropy: self.fair.client_entropy.clone(),
+            history: self.game.history().to_vec(),
+        })
+    }
+}
+
+/// 受け付けなかった要求
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoomError {
+    /// JSON として解釈できない
+    BadMessage(String),
+    /// そのルームはない
+    UnknownRoom,
+    /// 両方の席が埋まっている
+    RoomFull,
+    /// トークンが一致する席がない
+    UnknownToken,
+    /// その席には別の接続がある
+    SeatConnected,
+    /// この接続はすでにルームに入っている
+    AlreadyJoined,
+    /// ルームに入る前の操作
+    NotJoined,
+    /// 観戦者は操作できない
+    Spectator,
+    /// まだ相手がいない
+    NotStarted,
+    /// 手番ではない
+    NotYourTurn,
+    /// エントロピーは送信済みか、最初のロールで確定した
+    EntropyLocked,
+    /// まだエントロピーを送っていない席がある（全員が送るまで最初のロールはできない）
+    EntropyPending,
+    /// 手番の操作ではないメッセージ（`Room::apply` に参加・観戦などを渡した）
+    UnexpectedMessage,
+    Game(GameError),
+}
+
+impl fmt::Display for RoomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoomError::BadMessage(e) => write!(f, "bad message: {}", e),
+            RoomError::UnknownRoom => f.write_str("no such room"),
+            RoomError::RoomFull => f.write_str("room is full"),
+            RoomError::UnknownToken => f.write_str("unknown token"),
+            RoomError::SeatConnected => f.write_str("seat is already connected"),
+            RoomError::AlreadyJoined => f.write_str("already in a room"),
+            RoomError::NotJoined => f.write_str("join a room first"),
+            RoomError::Spectator => f.write_str("spectators cannot act"),
+            RoomError::NotStarted => f.write_str("waiting for an opponent"),
+            RoomError::NotYourTurn => f.write_str("not your turn"),
+            RoomError::EntropyLocked => f.write_str("entropy is already locked in"),
+            RoomError::EntropyPending => f.write_str("waiting for entropy from every player"),
+            RoomError::UnexpectedMessage => f.write_str("not a turn action"),
+            RoomError::Game(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl std::error::Error for RoomError {}
+
+impl From<GameError> for RoomError {
+    fn from(e: GameError) -> Self {
+        RoomError::Game(e)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Seat {
+    Human {
+        name: String,
+        token: String,
+        /// 切断した時刻（接続中は None）
+        disconnected_at: Option<Instant>,
+        /// エントロピーを送った
+        entropy: bool,
+    },
+    Ai,
+}
+
+/// 1つのルーム（ネットワークに依存しない部分）
+#[derive(Clone, Debug)]
+pub struct Room {
+    name: String,
+    game: GameState,
+    seats: [Option<Seat>; 2],
+    ai: YachtAI,
+    spectators: usize,
+    fair: FairDice,
+    /// 最初のロールでダイスのシードを確定した
+    seeded: bool,
+}
+
+impl Room {
+    /// `vs_ai` なら席1を AI にする
+    pub fn new(name: &str, vs_ai: bool) -> Room {
+        Room {
+            name: name.to_string(),
+            game: GameState::new(),
+            seats: [None, if vs_ai { Some(Seat::Ai) } else { None }],
+            ai: YachtAI::new(),
+            spectators: 0,
+            fair: FairDice::new(),
+            seeded: false,
+        }
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.seats.iter().all(Option::is_some)
+    }
+
+    /// ダイスのコミットメント
+    pub fn commitment(&self) -> String {
+        self.fair.commitment()
+    }
+
+    /// 空いている席に座り、席番号と再接続用のトークンを返す
+    pub fn join(&mut self, name: &str) -> Result<(u8, String), RoomError> {
+        let seat = self
+            .seats
+            .iter()
+            .position(Option::is_none)
+            .ok_or(RoomError::RoomFull)?;
+        let token = format!("{:032x}", rand::random::<u128>());
+        self.seats[seat] = Some(Seat::Human {
+            name: name.to_string(),
+            token: token.clone(),
+            disconnected_at: None,
+            entropy: false,
+        });
+        Ok((seat as u8, token))
+    }
+
+    /// 席のエントロピーを受け取る（席ごとに1回、シードの確定前のみ）
+    pub fn add_entropy(&mut self, seat: u8, entropy: &str) -> Result<(), RoomError> {
+        if self.seeded {
+            return Err(RoomError::EntropyLocked);
+        }
+        match self.seats.get_mut(seat as usize) {
+            Some(Some(Seat::Human { entropy: sent, .. })) => {
+                if *sent {
+                    return Err(RoomError::EntropyLocked);
+                }
+                *sent = true;
+                self.fair.add_client_entropy(entropy);
+                Ok(())
+            }
+            _ => Err(RoomError::NotJoined),
+        }
+    }
+
+    /// トークンの席に戻る
+    pub fn resume(&mut self, token: &str) -> Result<u8, RoomError> {
+        for (seat, entry) in self.seats.iter_mut().enumerate() {
+            if let Some(Seat::Human {
+                token: t,
+                disconnected_at,
+                ..
+            }) = entry
+            {
+                if t == token {
+                    if disconnected_at.is_none() {
+                        return Err(RoomError::SeatConnected);
+                    }
+                    *disconnected_at = None;
+                    return Ok(seat as u8);
+                }
+            }
+        }
+        Err(RoomError::UnknownToken)
+    }
+
+    /// 席の接続が切れた（席とトークンは残る）
+    pub fn disconnect(&mut self, seat: u8) {
+        if let Some(Some(Seat::Human {
+            disconnected_at, ..
+        })) = self.seats.get_mut(seat as usize)
+        {
+            *disconnected_at = Some(Instant::now());
+        }
+    }
+
+    /// 戻ってくる席がない（ゲームが終わったか、どの席も切断から grace 以上たった）
+    pub fn is_abandoned(&self, grace: Duration) -> bool {
+        self.game.is_game_over()
+            || !self.seats.iter().flatten().any(|seat| match seat {
+                Seat::Human {
+                    disconnected_at, ..
+                } => disconnected_at.is_none_or(|t| t.elapsed() < grace),
+                Seat::Ai => false,
+            })
+    }
+
+    /// 手番の操作を検証して適用し、AI の手番になったら続けて打たせる
+    pub fn apply(&mut self, seat: u8, message: &ClientMessage) -> Result<(), RoomError> {
+        if !self.is_started() {
+            return Err(RoomError::NotStarted);
+        }
+        if self.game.is_game_over() {
+            return Err(RoomError::Game(GameError::GameOver));
+        }
+        if self.game.current_player() != seat {
+            return Err(RoomError::NotYourTurn);
+        }
+        match message {
+            ClientMessage::Roll => {
+                if !self.seeded {
+                    // 人間の席が全員エントロピーを送ってからシードを確定する
+                    if !self.has_all_entropy() {
+                        return Err(RoomError::EntropyPending);
+                    }
+                    self.game.set_fair_seed(self.fair.seed());
+                    self.seeded = true;
+                }
+                self.game.roll()?
+            }
+            ClientMessage::Stand => self.game.stand()?,
+            ClientMessage::ToggleHold { index } => self.game.toggle_hold(*index)?,
+            ClientMessage::Select { category } => {
+                self.game.select(*category)?;
+            }
+            _ => return Err(RoomError::UnexpectedMessage),
+        }
+        self.play_ai_turns()
+    }
+
+    /// 人間の席が全員エントロピーを送った
+    fn has_all_entropy(&self) -> bool {
+        self.seats.iter().flatten().all(|seat| match seat {
+            Seat::Human { entropy, .. } => *entropy,
+            Seat::Ai => true,
+        })
+    }
+
+    fn play_ai_turns(&mut self) -> Result<(), RoomError> {
+        while !self.game.is_game_over()
+            && matches!(
+                self.seats[self.game.current_player() as usize],
+                Some(Seat::Ai)
+            )
+        {
+            self.ai.play_turn(&mut self.game)?;
+        }
+        Ok(())
+    }
+
+    pub fn view(&self) -> RoomView {
+        RoomView {
+            name: self.name.clone(),
+            seats: self.seats.clone().map(|seat| {
+                seat.map(|seat| match seat {
+                    Seat::Human {
+                        name,
+                        disconnected_at,
+                        ..
+                    } => SeatView {
+                        name,
+                        ai: false,
+                        connected: disconnected_at.is_none(),
+                    },
+                    Seat::Ai => SeatView {
+                        name: "AI".to_string(),
+                        ai: true,
+                        connected: true,
+                    },
+                })
+            }),
+            spectators: self.spectators,
+            started: self.is_started(),
+            game: self.game.clone(),
+            fair: FairView {
+                commitment: self.fair.commitment(),
+                client_entropy: self.fair.client_entropy().to_vec(),
+                server_seed: self.game.is_game_over().then(|| self.fair.reveal()),
+            },
+        }
+    }
+}
+
+/// 接続中のクライアント
+struct Subscriber {
+    seat: Option<u8>,
+    tx: UnboundedSender<ServerMessage>,
+}
+
+struct RoomEntry {
+    room: Room,
+    subscribers: HashMap<u64, Subscriber>,
+    /// サーバーから外された（このルームにはもう入れない）
+    closed: bool,
+}
+
+impl RoomEntry {
+    fn new(room: Room) -> RoomEntry {
+        RoomEntry {
+            room,
+            subscribers: HashMap::new(),
+            closed: false,
+        }
+    }
+
+    /// 接続を配信先に加え、席の割り当てと現在の状態を送る
+    fn enter(
+        &mut self,
+        connection: u64,
+        tx: &UnboundedSender<ServerMessage>,
+        seat: Option<u8>,
+        token: Option<String>,
+    ) {
+        let _ = tx.send(ServerMessage::Joined {
+            seat,
+            token,
+            commitment: self.room.commitment(),
+        });
+        self.subscribers.insert(
+            connection,
+            Subscriber {
+                seat,
+                tx: tx.clone(),
+            },
+        );
+        self.broadcast();
+    }
+
+    fn broadcast(&self) {
+        let message = ServerMessage::State {
+            room: Box::new(self.room.view()),
+        };
+        for subscriber in self.subscribers.values() {
+            let _ = subscriber.tx.send(message.clone());
+        }
+    }
+}
+
+/// 接続がどのルームのどの席にいるか
+struct Session {
+    room: String,
+    seat: Option<u8>,
+}
+
+/// 切断した席が再接続を待つ時間の既定値
+pub const RESUME_GRACE: Duration = Duration::from_secs(300);
+
+/// ルームの集合
+///
+/// 全体のロックはルームを探すときだけ持ち、操作（AI の手番を含む）はルームごとのロックで行う。
+pub struct Server {
+    rooms: Mutex<HashMap<String, Arc<Mutex<RoomEntry>>>>,
+    next_connection: AtomicU64,
+    resume_grace: Duration,
+}
+
+impl Default for Server {
+    fn default() -> Server {
+        Server::with_resume_grace(RESUME_GRACE)
+    }
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server::default()
+    }
+
+    /// 切断した席が再接続を待つ時間を指定する
+    pub fn with_resume_grace(resume_grace: Duration) -> Server {
+        Server {
+            rooms: Mutex::new(HashMap::new()),
+            next_connection: AtomicU64::new(0),
+            resume_grace,
+        }
+    }
+
+    /// ルームのロックを取って f を実行する
+    fn with_room<T>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&mut RoomEntry) -> Result<T, RoomError>,
+    ) -> Result<T, RoomError> {
+        let shared = self
+            .rooms
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or(RoomError::UnknownRoom)?;
+        let mut entry = shared.lock().unwrap();
+        if entry.closed {
+            return Err(RoomError::UnknownRoom);
+        }
+        f(&mut entry)
+    }
+
+    /// 1つのメッセージを処理する（返信と配信は各接続のチャンネルに送る）
+    fn handle_message(
+        &self,
+        connection: u64,
+        tx: &UnboundedSender<ServerMessage>,
+        session: &mut Option<Session>,
+        message: ClientMessage,
+    ) -> Result<(), RoomError> {
+        match message {
+            ClientMessage::Join { room, name, vs_ai } => {
+                if session.is_some() {
+                    return Err(RoomError::AlreadyJoined);
+                }
+                let seat = loop {
+                    let shared = self
+                        .rooms
+                        .lock()
+                        .unwrap()
+                        .entry(room.clone())
+                        .or_insert_with(|| {
+                            Arc::new(Mutex::new(RoomEntry::new(Room::new(&room, vs_ai))))
+                        })
+                        .clone();
+                    let mut entry = shared.lock().unwrap();
+                    // 片付けと入れ違いになったら作り直す
+                    if entry.closed {
+                        continue;
+                    }
+                    let (seat, token) = entry.room.join(&name)?;
+                    entry.enter(connection, tx, Some(seat), Some(token));
+                    break seat;
+                };
+                *session = Some(Session {
+                    room,
+                    seat: Some(seat),
+                });
+            }
+            ClientMessage::Spectate { room } => {
+                if session.is_some() {
+                    return Err(RoomError::AlreadyJoined);
+                }
+                self.with_room(&room, |entry| {
+                    entry.room.spectators += 1;
+                    entry.enter(connection, tx, None, None);
+                    Ok(())
+                })?;
+                *session = Some(Session { room, seat: None });
+            }
+            ClientMessage::Resume { room, token } => {
+                if session.is_some() {
+                    return Err(RoomError::AlreadyJoined);
+                }
+                let seat = self.with_room(&room, |entry| {
+                    let seat = entry.room.resume(&token)?;
+                    entry.enter(connection, tx, Some(seat), None);
+                    Ok(seat)
+                })?;
+                *session = Some(Session {
+                    room,
+                    seat: Some(seat),
+                });
+            }
+            ClientMessage::Entropy { entropy } => {
+                let session = session.as_ref().ok_or(RoomError::NotJoined)?;
+                let seat = session.seat.ok_or(RoomError::Spectator)?;
+                self.with_room(&session.room, |entry| {
+                    entry.room.add_entropy(seat, &entropy)?;
+                    entry.broadcast();
+                    Ok(())
+                })?;
+            }
+            action => {
+                let session = session.as_ref().ok_or(RoomError::NotJoined)?;
+                let seat = session.seat.ok_or(RoomError::Spectator)?;
+                self.with_room(&session.room, |entry| {
+                    entry.room.apply(seat, &action)?;
+                    entry.broadcast();
+                    Ok(())
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 切断の後始末（誰もいなくなったルームは、再接続を待つ席がなくなったら消す）
+    fn leave(self: &Arc<Self>, connection: u64, session: &Session) {
+        let empty = self
+            .with_room(&session.room, |entry| {
+                if let Some(subscriber) = entry.subscribers.remove(&connection) {
+                    match subscriber.seat {
+                        Some(seat) => entry.room.disconnect(seat),
+                        None => entry.room.spectators -= 1,
+                    }
+                }
+                entry.broadcast();
+                Ok(entry.subscribers.is_empty())
+            })
+            .unwrap_or(false);
+        if !empty {
+            return;
+        }
+        self.sweep();
+        if self.rooms.lock().unwrap().contains_key(&session.room) {
+            // 再接続の猶予が過ぎてからもう一度片付ける
+            let server = Arc::clone(self);
+            tokio::spawn(async move {
+                tokio::time::sleep(server.resume_grace).await;
+                server.sweep();
+            });
+        }
+    }
+
+    /// 接続がなく、戻ってくる席もないルームを消す
+    fn sweep(&self) {
+        let grace = self.resume_grace;
+        self.rooms.lock().unwrap().retain(|_, shared| {
+            // 処理中のルームには接続がある
+            let Ok(mut entry) = shared.try_lock() else {
+                return true;
+            };
+            let keep = !entry.subscribers.is_empty() || !entry.room.is_abandoned(grace);
+            entry.closed = !keep;
+            keep
+        });
+    }
+
+    /// 1つの WebSocket 接続を最後まで処理する
+    pub async fn handle_connection(self: Arc<Self>, stream: TcpStream) {
+        let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+            return;
+        };
+        let (mut sink, mut source) = ws.split();
+        let (tx, mut rx) = mpsc::unbounded_channel::<ServerMessage>();
+        let writer = tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                let text = serde_json::to_string(&message).unwrap_or_default();
+                if sink.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let connection = self.next_connection.fetch_add(1, Ordering::Relaxed);
+        let mut session = None;
+        while let Some(Ok(frame)) = source.next().await {
+            let text = match frame {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let result = match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(message) => {
+                    // AI の手番はルームのロックを持ったまま期待値を探索するので、
+                    // 非同期のワーカーを止めないようにブロッキング用のスレッドで処理する
+                    let server = Arc::clone(&self);
+                    let tx = tx.clone();
+                    let mut current = session.take();
+                    let (result, next) = tokio::task::spawn_blocking(move || {
+                        let result = server.handle_message(connection, &tx, &mut current, message);
+                        (result, current)
+                    })
+                    .await
+                    .expect("message handler panicked");
+                    session = next;
+                    result
+                }
+                Err(e) => Err(RoomError::BadMessage(e.to_string())),
+            };
+            if let Err(e) = result {
+                let _ = tx.send(ServerMessage::Error {
+                    message: e.to_string(),
+                });
+            }
+        }
+
+        if let Some(session) = &session {
+            self.leave(connection, session);
+        }
+        drop(tx);
+        let _ = writer.await;
+    }
+
+    /// このサーバーで接続を受け付け続ける
+    pub async fn run(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            tokio::spawn(self.clone().handle_connection(stream));
+        }
+    }
+}
+
+/// 接続を受け付け続ける
+pub async fn serve(listener: TcpListener) -> std::io::Result<()> {
+    Arc::new(Server::new()).run(listener).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn start() -> String {
+        start_server(Server::new()).await
+    }
+
+    async fn start_server(server: Server) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(Arc::new(server).run(listener));
+        url
+    }
+
+    async fn connect(url: &str) -> Client {
+        connect_async(url).await.unwrap().0
+    }
+
+    async fn send(client: &mut Client, message: ClientMessage) {
+        let text = serde_json::to_string(&message).unwrap();
+        client.send(Message::Text(text)).await.unwrap();
+    }
+
+    async fn recv(client: &mut Client) -> ServerMessage {
+        loop {
+            let frame = tokio::time::timeout(std::time::Duration::from_secs(10), client.next())
+                .await
+                .expect("timed out")
+                .unwrap()
+                .unwrap();
+            if let Message::Text(text) = frame {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    async fn recv_state(client: &mut Client) -> RoomView {
+        match recv(client).await {
+            ServerMessage::State { room } => *room,
+            other => panic!("expected state, got {:?}", other),
+        }
+    }
+
+    async fn recv_error(client: &mut Client) -> String {
+        match recv(client).await {
+            ServerMessage::Error { message } => message,
+            other => panic!("expected error, got {:?}", other),
+        }
+    }
+
+    /// 席につき、席番号・トークン・コミットメントを返す
+    async fn join(
+        client: &mut Client,
+        room: &str,
+        name: &str,
+        vs_ai: bool,
+    ) -> (u8, String, String) {
+        let message = ClientMessage::Join {
+            room: room.to_string(),
+            name: name.to_string(),
+            vs_ai,
+        };
+        send(client, message).await;
+        match recv(client).await {
+            ServerMessage::Joined {
+                seat: Some(seat),
+                token: Some(token),
+                commitment,
+            } => (seat, token, commitment),
+            other => panic!("expected joined, got {:?}", other),
+        }
+    }
+
+    async fn send_entropy(client: &mut Client, entropy: &str) {
+        let message = ClientMessage::Entropy {
+            entropy: entropy.to_string(),
+        };
+        send(client, message).await;
+    }
+
+    /// 1回振って最善のカテゴリを選ぶ（手番の最後の状態を返す）
+    async fn play_turn(client: &mut Client) -> RoomView {
+        send(client, ClientMessage::Roll).await;
+        let view = recv_state(client).await;
+        let category = YachtAI::new()
+            .decide_category(&view.game)
+            .expect("a category is open after rolling");
+        send(client, ClientMessage::Select { category }).await;
+        recv_state(client).await
+    }
+
+    #[test]
+    fn test_room_rules() {
+        let mut room = Room::new("r", false);
+        let (seat, token) = room.join("alice").unwrap();
+        assert_eq!(seat, 0);
+        assert_eq!(
+            room.apply(0, &ClientMessage::Roll),
+            Err(RoomError::NotStarted)
+        );
+        assert_eq!(room.join("bob").unwrap().0, 1);
+        assert_eq!(room.join("carol"), Err(RoomError::RoomFull));
+        let spectate = ClientMessage::Spectate {
+            room: "r".to_string(),
+        };
+        assert_eq!(room.apply(0, &spectate), Err(RoomError::UnexpectedMessage));
+        assert_eq!(
+            room.apply(1, &ClientMessage::Roll),
+            Err(RoomError::NotYourTurn)
+        );
+        assert_eq!(
+            room.apply(0, &ClientMessage::Stand),
+            Err(RoomError::Game(GameError::NotRolled))
+        );
+        room.add_entropy(0, "alice").unwrap();
+        assert_eq!(room.add_entropy(0, "again"), Err(RoomError::EntropyLocked));
+        // 席1がまだエントロピーを送っていない
+        assert_eq!(
+            room.apply(0, &ClientMessage::Roll),
+            Err(RoomError::EntropyPending)
+        );
+        room.add_entropy(1, "bob").unwrap();
+        room.apply(0, &ClientMessage::Roll).unwrap();
+        // 最初のロールの後はシードが確定している
+        assert_eq!(room.add_entropy(1, "again"), Err(RoomError::EntropyLocked));
+        assert_eq!(room.view().fair.client_entropy, ["alice", "bob"]);
+
+        assert_eq!(room.resume(&token), Err(RoomError::SeatConnected));
+        room.disconnect(0);
+        assert!(!room.view().seats[0].as_ref().unwrap().connected);
+        assert_eq!(room.resume("nope"), Err(RoomError::UnknownToken));
+        assert_eq!(room.resume(&token), Ok(0));
+
+        // 切断した席は猶予の間だけ再接続を待つ
+        let grace = Duration::from_secs(60);
+        assert!(!room.is_abandoned(grace));
+        room.disconnect(0);
+        room.disconnect(1);
+        assert!(!room.is_abandoned(grace));
+        assert!(room.is_abandoned(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_room_vs_ai() {
+        let mut room = Room::new("r", true);
+        room.join("alice").unwrap();
+        assert!(room.is_started());
+        // AI の席はエントロピーを送らない
+        room.add_entropy(0, "alice").unwrap();
+        room.apply(0, &ClientMessage::Roll).unwrap();
+        room.apply(
+            0,
+            &ClientMessage::Select {
+                category: Category::Choice,
+            },
+        )
+        .unwrap();
+        // AI の手番はその場で打たれる
+        assert_eq!(room.game().current_player(), 0);
+        assert_eq!(room.game().board(1).available_categories().len(), 11);
+    }
+
+    #[tokio::test]
+    async fn test_game_against_ai_with_spectator() {
+        let url = start().await;
+        let mut alice = connect(&url).await;
+        let (seat, _, commitment) = join(&mut alice, "r1", "alice", true).await;
+        assert_eq!(seat, 0);
+        let view = recv_state(&mut alice).await;
+        assert!(view.started);
+        assert!(view.seats[1].as_ref().unwrap().ai);
+        assert_eq!(view.fair.commitment, commitment);
+        assert!(view.fair.client_entropy.is_empty());
+        // コミットメントを見てからエントロピーを送る
+        send_entropy(&mut alice, "alice entropy").await;
+        let view = recv_state(&mut alice).await;
+        assert_eq!(view.fair.client_entropy, ["alice entropy"]);
+        assert!(view.fair_record().is_none());
+
+        let mut watcher = connect(&url).await;
+        send(
+            &mut watcher,
+            ClientMessage::Spectate {
+                room: "r1".to_string(),
+            },
+        )
+        .await;
+        assert!(matches!(
+            recv(&mut watcher).await,
+            ServerMessage::Joined {
+                seat: None,
+                token: None,
+                ..
+            }
+        ));
+        assert_eq!(recv_state(&mut watcher).await.spectators, 1);
+        assert_eq!(recv_state(&mut alice).await.spectators, 1);
+
+        send(&mut watcher, ClientMessage::Roll).await;
+        assert_eq!(recv_error(&mut watcher).await, "spectators cannot act");
+
+        let mut view = view;
+        for _ in 0..12 {
+            view = play_turn(&mut alice).await;
+            // 観戦者にも同じ更新が届く
+            recv_state(&mut watcher).await;
+            assert_eq!(
+                recv_state(&mut watcher).await.game.to_json(),
+                view.game.to_json()
+            );
+        }
+        assert!(view.game.is_game_over());
+        assert!(view.game.board(0).is_complete() && view.game.board(1).is_complete());
+
+        // 終了後に公開されたシードで全ロールを検証できる
+        let record = view.fair_record().unwrap();
+        assert_eq!(record.commitment, commitment);
+        assert_eq!(crate::fair::verify(&record), Ok(()));
+
+        send_entropy(&mut alice, "late entropy").await;
+        assert_eq!(recv_error(&mut alice).await, "entropy is already locked in");
+        send(&mut alice, ClientMessage::Roll).await;
+        assert_eq!(recv_error(&mut alice).await, "game is over");
+    }
+
+    #[tokio::test]
+    async fn test_two_players_and_resume() {
+        let url = start().await;
+        let mut alice = connect(&url).await;
+        let (_, alice_token, _) = join(&mut alice, "r2", "alice", false).await;
+        assert!(!recv_state(&mut alice).await.started);
+
+        let mut bob = connect(&url).await;
+        assert_eq!(join(&mut bob, "r2", "bob", false).await.0, 1);
+        assert!(recv_state(&mut bob).await.started);
+        recv_state(&mut alice).await;
+
+        send(&mut bob, ClientMessage::Roll).await;
+        assert_eq!(recv_error(&mut bob).await, "not your turn");
+
+        send_entropy(&mut alice, "alice entropy").await;
+        recv_state(&mut alice).await;
+        recv_state(&mut bob).await;
+        send(&mut alice, ClientMessage::Roll).await;
+        assert_eq!(
+            recv_error(&mut alice).await,
+            "waiting for entropy from every player"
+        );
+        send_entropy(&mut bob, "bob entropy").await;
+        recv_state(&mut alice).await;
+        recv_state(&mut bob).await;
+
+        send(&mut alice, ClientMessage::Roll).await;
+        recv_state(&mut bob).await;
+        let before = recv_state(&mut alice).await;
+
+        // 切断しても席と状態は残る
+        alice.close(None).await.unwrap();
+        let view = recv_state(&mut bob).await;
+        assert!(!view.seats[0].as_ref().unwrap().connected);
+
+        let mut alice = connect(&url).await;
+        send(
+            &mut alice,
+            ClientMessage::Resume {
+                room: "r2".to_string(),
+                token: alice_token,
+            },
+        )
+        .await;
+        assert!(matches!(
+            recv(&mut alice).await,
+            ServerMessage::Joined {
+                seat: Some(0),
+                token: None,
+                ..
+            }
+        ));
+        let resynced = recv_state(&mut alice).await;
+        assert!(resynced.seats[0].as_ref().unwrap().connected);
+        assert_eq!(resynced.game.to_json(), before.game.to_json());
+        recv_state(&mut bob).await;
+
+        send(
+            &mut alice,
+            ClientMessage::Select {
+                category: Category::Choice,
+            },
+        )
+        .await;
+        assert_eq!(recv_state(&mut alice).await.game.current_player(), 1);
+        recv_state(&mut bob).await;
+        let view = play_turn(&mut bob).await;
+        assert_eq!(view.game.current_player(), 0);
+
+        send(&mut bob, ClientMessage::ToggleHold { index: 0 }).await;
+        assert_eq!(recv_error(&mut bob).await, "not your turn");
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_room_is_removed() {
+        let url = start_server(Server::with_resume_grace(Duration::ZERO)).await;
+        let mut alice = connect(&url).await;
+        join(&mut alice, "r3", "alice", true).await;
+        recv_state(&mut alice).await;
+        alice.close(None).await.unwrap();
+
+        // 切断の後始末は非同期なので、消えるまで待つ
+        let mut watcher = connect(&url).await;
+        for _ in 0..100 {
+            let message = ClientMessage::Spectate {
+                room: "r3".to_string(),
+            };
+            send(&mut watcher, message).await;
+            match recv(&mut watcher).await {
+                ServerMessage::Error { message } => {
+                    assert_eq!(message, "no such room");
+                    return;
+                }
+                _ => {
+                    recv_state(&mut watcher).await;
+                    watcher = connect(&url).await;
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            }
+        }
+        panic!("room was not removed");
+    }
+}
@@ -0,0 +1,174 @@
+//! 得点ルールごとの手番内の最適化
+//!
+//! 出目が確定したときの評価（`Leaf`）を葉として、残りロール数ごとの期待値を計算する。
+//! ホールドは自由（振るたびに全てのダイスを選び直せる）で、ダイスの個数と種類は `Leaf` が決める。
+
+use std::collections::HashMap;
+
+use crate::dice_model::DiceModel;
+use crate::dp_table::{self, DicePattern, Rules};
+
+/// 出目が確定したときの評価
+pub(crate) trait Leaf {
+    /// 1手番で振るダイスの個数
+    fn num_dice(&self) -> usize;
+
+    /// 振るダイス（標準は6面の一様なダイス。変えるのは `custom` の葉だけ）
+    fn dice(&self) -> &DiceModel {
+        DiceModel::standard()
+    }
+
+    /// dice で得点し、rolls_left 回のロールを残して手番を終えるときの最良カテゴリとその価値
+    /// （空きがなければ None）
+    fn best_category(&self, dice: &DicePattern, rolls_left: u8) -> Option<(usize, f32)>;
+}
+
+/// DPテーブルの値を葉とする評価（5個のダイス、残ったロールは持ち越さない）
+pub(crate) struct TableLeaf {
+    pub rules: &'static Rules,
+    pub upper_sum: usize,
+    pub used_hands: usize,
+}
+
+impl Leaf for TableLeaf {
+    fn num_dice(&self) -> usize {
+        5
+    }
+
+    fn best_category(&self, dice: &DicePattern, _rolls_left: u8) -> Option<(usize, f32)> {
+        let mut best: Option<(usize, f32)> = None;
+        for cat in 0..self.rules.num_categories {
+            if (self.used_hands >> cat) & 1 == 1 {
+                continue;
+            }
+            let score = (self.rules.score)(dice, cat);
+            let value =
+                self.rules
+                    .evaluate_category_choice(self.upper_sum, self.used_hands, cat, score);
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((cat, value));
+            }
+        }
+        best
+    }
+}
+
+pub(crate) struct TurnSolver<L> {
+    leaf: L,
+    /// (出目, 残りロール数) → 振った直後の価値
+    after_roll: HashMap<(DicePattern, u8), f32>,
+    /// (キープ, 残りロール数) → キープして振る価値
+    keep_value: HashMap<(DicePattern, u8), f32>,
+}
+
+impl TurnSolver<TableLeaf> {
+    /// DPテーブルを持つルールの手番（upper_sum / used_hands は手番開始時点）
+    pub fn for_table(rules: &'static Rules, upper_sum: usize, used_hands: usize) -> Self {
+        TurnSolver::new(TableLeaf {
+            rules,
+            upper_sum,
+            used_hands,
+        })
+    }
+}
+
+impl<L: Leaf> TurnSolver<L> {
+    pub fn new(leaf: L) -> TurnSolver<L> {
+        TurnSolver {
+            leaf,
+            after_roll: HashMap::new(),
+            keep_value: HashMap::new(),
+        }
+    }
+
+    /// 出目が確定したときの最良カテゴリとその価値（空きがなければ None）
+    pub fn best_category(&self, dice: &DicePattern, rolls_left: u8) -> Option<(usize, f32)> {
+        self.leaf.best_category(dice, rolls_left)
+    }
+
+    /// 振った直後（あと rolls_left 回振れる）の価値
+    pub fn value_after_roll(&mut self, dice: &DicePattern, rolls_left: u8) -> f32 {
+        if let Some(&value) = self.after_roll.get(&(*dice, rolls_left)) {
+            return value;
+        }
+        let mut best = self.best_category(dice, rolls_left).map_or(0.0, |(_, v)| v);
+        if rolls_left > 0 {
+            let num_dice = self.leaf.num_dice() as u8;
+            for keep in dp_table::enumerate_keep_patterns(dice) {
+                if dp_table::pattern_count(&keep) < num_dice {
+                    best = best.max(self.value_of_keep(&keep, rolls_left));
+                }
+            }
+        }
+        self.after_roll.insert((*dice, rolls_left), best);
+        best
+    }
+
+    /// keep を残して振り直す価値（rolls_left >= 1）
+    pub fn value_of_keep(&mut self, keep: &DicePattern, rolls_left: u8) -> f32 {
+        if let Some(&value) = self.keep_value.get(&(*keep, rolls_left)) {
+            return value;
+        }
+        let num_reroll = self.leaf.num_dice() - dp_table::pattern_count(keep) as usize;
+        let patterns = self.leaf.dice().patterns(num_reroll).to_vec();
+        let mut total = 0.0f32;
+        for pp in &patterns {
+            let dice = dp_table::add_patterns(keep, &pp.pattern);
+            total += pp.probability * self.value_after_roll(&dice, rolls_left - 1);
+        }
+        self.keep_value.insert((*keep, rolls_left), total);
+        total
+    }
+
+    /// 最良の振り直し（今の出目で得点するほうがよければ None）
+    pub fn best_keep(&mut self, dice: &DicePattern, rolls_left: u8) -> Option<(DicePattern, f32)> {
+        if rolls_left == 0 {
+            return None;
+        }
+        let score_now = self.best_category(dice, rolls_left).map_or(0.0, |(_, v)| v);
+        let num_dice = self.leaf.num_dice() as u8;
+        let mut best: Option<(DicePattern, f32)> = None;
+        for keep in dp_table::enumerate_keep_patterns(dice) {
+            if dp_table::pattern_count(&keep) == num_dice {
+                continue;
+            }
+            let value = self.value_of_keep(&keep, rolls_left);
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((keep, value));
+            }
+        }
+        best.filter(|&(_, value)| value >= score_now)
+    }
+
+    /// 手番開始時（まだ振っていない、rolls 回振れる）の期待値
+    pub fn turn_value(&mut self, rolls: u8) -> f32 {
+        let mut total = 0.0f32;
+        let patterns = self.leaf.dice().patterns(self.leaf.num_dice()).to_vec();
+        for pp in &patterns {
+            total += pp.probability * self.value_after_roll(&pp.pattern, rolls - 1);
+        }
+        total
+    }
+}
+
+/// all の部分集合（all 自身を除く、使用済みカテゴリの集合）を残りの期待値の表を埋める順に返す
+///
+/// 記入すると使用済みが増えるので、ある状態の値はその上位集合の値から決まる。上位集合は数値として
+/// 大きいので、大きい順に埋めれば参照する値は先に決まっている。
+pub(crate) fn used_sets_descending(all: usize) -> impl Iterator<Item = usize> {
+    (0..all).rev().filter(move |&used| used & !all == 0)
+}
+
+/// キープパターンをホールド配列に変換する（同じ目は左から順にホールド）
+pub(crate) fn keep_to_holds<const N: usize>(dice: &[u8; N], keep: &DicePattern) -> [bool; N] {
+    let mut remaining = *keep;
+    std::array::from_fn(|i| {
+        let face = (dice[i] - 1) as usize;
+        if remaining[face] > 0 {
+            remaining[face] -= 1;
+            true
+        } else {
+            false
+        }
+    })
+}
@@ -0,0 +1,182 @@
+//! トリプルヨット（3列の重み付き得点表）
+//!
+//! ヨットの12カテゴリを ×1・×2・×3 の3列に持ち、毎手番どの列のどのカテゴリに記入してもよい。
+//! 上段ボーナスは列ごとで、列の合計（ボーナス込み）に重みをかけた和が得点になる。
+//!
+//! 得点表は `ScoreBoard`（`Ruleset::Triple`、欄の行は `Category` の番号）。
+//! 36マスは `dp[upper_sum][used_hands]` に収まらないので、AI は列ごとにヨットのDPテーブルで
+//! 「その列だけを最適に続けた期待値」の増減を見積もり、重みをかけて比べる（列の分解による近似）。
+
+use crate::dp_table::{self, DicePattern};
+use crate::play::Strategy;
+use crate::solver::{keep_to_holds, Leaf, TurnSolver};
+use crate::{Cell, GameError, GameState, HoldDecision, Ruleset, ScoreBoard};
+
+pub use crate::turn::ROLLS_PER_TURN;
+pub const NUM_COLUMNS: usize = 3;
+/// 列ごとの重み
+pub const COLUMN_WEIGHTS: [u16; NUM_COLUMNS] = [1, 2, 3];
+
+/// 手番の途中状態の葉の評価
+struct TripleLeaf<'a> {
+    board: &'a ScoreBoard,
+    /// 列ごとの (上段累計, 使用済みカテゴリ, 残りの期待値)
+    columns: [(usize, usize, f32); NUM_COLUMNS],
+    greedy: bool,
+}
+
+impl<'a> TripleLeaf<'a> {
+    fn new(board: &'a ScoreBoard, greedy: bool) -> TripleLeaf<'a> {
+        let all = (1 << dp_table::NUM_CATEGORIES) - 1;
+        let columns = std::array::from_fn(|c| {
+            let (upper_sum, used_hands) = board.column_state(c);
+            let future = if greedy || used_hands == all {
+                0.0
+            } else {
+                dp_table::get_expected_score(upper_sum, used_hands)
+            };
+            (upper_sum, used_hands, future)
+        });
+        TripleLeaf {
+            board,
+            columns,
+            greedy,
+        }
+    }
+
+    /// 欄に記入したときの価値（重みをかけた、その列の最終得点の期待値の増減）
+    fn cell_value(&self, column: usize, cat: usize, score: u8) -> f32 {
+        let (upper_sum, used_hands, future) = self.columns[column];
+        let gain = if self.greedy {
+            // ボーナスを含めたその場の得点だけを見る
+            let crosses = dp_table::category::is_upper(cat)
+                && upper_sum < dp_table::UPPER_BONUS_THRESHOLD
+                && upper_sum + score as usize >= dp_table::UPPER_BONUS_THRESHOLD;
+            score as f32
+                + if crosses {
+                    dp_table::UPPER_BONUS_POINTS as f32
+                } else {
+                    0.0
+                }
+        } else {
+            dp_table::evaluate_category_choice(upper_sum, used_hands, cat, score) - future
+        };
+        gain * COLUMN_WEIGHTS[column] as f32
+    }
+
+    /// 最良の欄（列 × 12 + カテゴリ）とその価値
+    fn best(&self, dice: &DicePattern) -> Option<(usize, f32)> {
+        let mut best: Option<(usize, f32)> = None;
+        for cell in self.board.available_cells() {
+            let value = self.cell_value(
+                cell.column,
+                cell.row,
+                dp_table::calculate_score(dice, cell.row),
+            );
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((cell.column * dp_table::NUM_CATEGORIES + cell.row, value));
+            }
+        }
+        best
+    }
+}
+
+impl Leaf for TripleLeaf<'_> {
+    fn num_dice(&self) -> usize {
+        5
+    }
+
+    fn best_category(&self, dice: &DicePattern, _rolls_left: u8) -> Option<(usize, f32)> {
+        self.best(dice)
+    }
+}
+
+/// トリプルヨットのAI
+#[derive(Clone, Debug, Default)]
+pub struct TripleAI {
+    greedy: bool,
+}
+
+impl TripleAI {
+    /// 列ごとのDPテーブルで将来の期待値も見るAI
+    pub fn new() -> TripleAI {
+        TripleAI { greedy: false }
+    }
+
+    /// 比較用: その手番で重み付きの得点が最大になるように打つAI
+    pub fn greedy() -> TripleAI {
+        TripleAI { greedy: true }
+    }
+}
+
+impl Strategy<GameState> for TripleAI {
+    fn check_game(&self, game: &GameState) -> Result<(), GameError> {
+        game.check_ruleset(Ruleset::Triple)
+    }
+
+    fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        let dice = game.dice().values();
+        let pattern = dp_table::dice_to_pattern(&dice);
+        let mut solver = TurnSolver::new(TripleLeaf::new(game.current_board(), self.greedy));
+        match solver.best_keep(&pattern, game.rolls_left()) {
+            Some((keep, _)) => HoldDecision::Reroll(keep_to_holds(&dice, &keep)),
+            None => HoldDecision::Stop,
+        }
+    }
+
+    fn decide_cell(&self, game: &GameState) -> Option<Cell> {
+        let leaf = TripleLeaf::new(game.current_board(), self.greedy);
+        let (cell, _) = leaf.best(&dp_table::dice_to_pattern(&game.dice().values()))?;
+        Some(Cell::new(
+            cell / dp_table::NUM_CATEGORIES,
+            cell % dp_table::NUM_CATEGORIES,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Category;
+
+    #[test]
+    fn test_weighted_columns() {
+        let choice = |column| Cell::new(column, Category::Choice as usize);
+        let mut game = GameState::solo(Ruleset::Triple);
+        game.set_seed(2);
+        assert_eq!(game.select_cell(choice(0)), Err(GameError::NotRolled));
+        game.roll().unwrap();
+        let score = game.select_cell(choice(2)).unwrap();
+        game.roll().unwrap();
+        assert_eq!(game.select_cell(choice(2)), Err(GameError::CategoryUsed));
+        assert_eq!(
+            game.select_cell(choice(3)),
+            Err(GameError::CategoryUnavailable)
+        );
+        let ones = game.select(Category::Ones).unwrap();
+        let board = game.board(0);
+        assert_eq!(board.total(), score as u16 * 3 + ones as u16);
+        assert_eq!(board.available_cells().len(), 34);
+
+        let mut board = ScoreBoard::with_ruleset(Ruleset::Triple);
+        for (face, cat) in Category::ALL[..6].iter().enumerate() {
+            board
+                .set_cell(Cell::new(1, *cat as usize), 3 * (face as u8 + 1))
+                .unwrap();
+        }
+        assert_eq!(board.column_total(1), (63 + 35) * 2);
+    }
+
+    #[test]
+    fn test_ai_decisions() {
+        let ai = TripleAI::new();
+        let mut game = GameState::solo(Ruleset::Triple);
+        game.enter_roll([6; 5]).unwrap();
+        // ヨットは ×3 の列に記入する
+        assert_eq!(ai.decide_holds(&game), HoldDecision::Stop);
+        assert_eq!(
+            ai.decide_cell(&game),
+            Some(Cell::new(2, Category::Yacht as usize))
+        );
+    }
+}
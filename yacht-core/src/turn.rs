@@ -0,0 +1,330 @@
+//! 1手番のダイス操作（振る・ホールド・振り直しをやめる）
+//!
+//! `GameState`（ヨット・ヘネラル・トリプルヨット・ヤンブ）とカスタムルールのゲームが共有する手番の状態。
+//! ここで扱うのはダイス（`N` 個、既定は5個）とその種類（`DiceModel`）、残りロール、
+//! 乱数（シード・検証可能なダイス）だけで、
+//! ゲームの終了判定、記入、記録はそれぞれのゲームが持つ。マキシヤッツィーは6個のダイスで、
+//! 使わなかったロールを次の手番に持ち越す（`Turn::with_banking`）。
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::dice_model::DiceModel;
+use crate::{fair, Dice, GameError, HoldRule};
+
+/// 1手番に振れる回数
+pub const ROLLS_PER_TURN: u8 = 3;
+
+/// 1回のロールの記録
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RollRecord<const N: usize = 5> {
+    /// 振る前の残りロール
+    pub rolls_left: u8,
+    /// 振る前のホールド
+    pub held: [bool; N],
+    pub before: [u8; N],
+    pub after: [u8; N],
+}
+
+/// 手番中のダイスと残りロール
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Turn<const N: usize = 5> {
+    pub(crate) dice: Dice<N>,
+    /// 振るダイスの種類（標準の d6 なら JSON に出さない）
+    #[serde(default, skip_serializing_if = "DiceModel::is_standard")]
+    dice_model: DiceModel,
+    rolls_left: u8,
+    /// この手番を始めたときに振れた回数（持ち越し分を含む）
+    #[serde(default = "default_turn_rolls")]
+    turn_rolls: u8,
+    /// 使わなかったロールを次の手番に持ち越す
+    #[serde(default)]
+    banking: bool,
+    /// 再現可能なロール用の乱数生成器（未設定ならスレッドローカルの乱数）
+    #[serde(skip)]
+    rng: Option<StdRng>,
+    /// 検証可能なダイスのシード（`fair` 参照）。ゲーム中は秘密なのでシリアライズしない
+    #[serde(skip)]
+    fair_seed: Option<fair::Seed>,
+    /// ゲーム開始からのロール回数（検証可能なダイスのロール番号）
+    #[serde(skip)]
+    rolls_made: u64,
+}
+
+fn default_turn_rolls() -> u8 {
+    ROLLS_PER_TURN
+}
+
+impl<const N: usize> Turn<N> {
+    pub fn new(hold_rule: HoldRule) -> Turn<N> {
+        Turn {
+            dice: Dice::with_hold_rule(hold_rule),
+            dice_model: DiceModel::default(),
+            rolls_left: ROLLS_PER_TURN,
+            turn_rolls: ROLLS_PER_TURN,
+            banking: false,
+            rng: None,
+            fair_seed: None,
+            rolls_made: 0,
+        }
+    }
+
+    /// 使わなかったロールを次の手番に持ち越す手番
+    pub fn with_banking(hold_rule: HoldRule) -> Turn<N> {
+        Turn {
+            banking: true,
+            ..Turn::new(hold_rule)
+        }
+    }
+
+    /// 以降のロールをシードから決定的に生成する
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// 以降のロールを検証可能なダイスのシードから導く（`set_seed` より優先）
+    ///
+    /// 検証可能なダイスは d6 だけなので、ダイスの種類は標準に戻す。
+    pub fn set_fair_seed(&mut self, seed: fair::Seed) {
+        self.fair_seed = Some(seed);
+        self.dice_model = DiceModel::default();
+    }
+
+    /// 以降のロールで振るダイスの種類（d8 や偏ったダイス）を設定する
+    ///
+    /// 標準以外のダイスでは検証可能なダイスのシードを外す（`set_seed` のシードはそのまま使う）。
+    pub fn set_dice_model(&mut self, model: DiceModel) {
+        if !model.is_standard() {
+            self.fair_seed = None;
+        }
+        self.dice_model = model;
+    }
+
+    pub fn dice_model(&self) -> &DiceModel {
+        &self.dice_model
+    }
+
+    pub fn dice(&self) -> &Dice<N> {
+        &self.dice
+    }
+
+    pub fn rolls_left(&self) -> u8 {
+        self.rolls_left
+    }
+
+    /// この手番に持ち越されてきたロール数（書き換えた JSON で turn_rolls が 3 未満でも 0）
+    pub fn banked_rolls(&self) -> u8 {
+        self.turn_rolls.saturating_sub(ROLLS_PER_TURN)
+    }
+
+    pub fn hold_rule(&self) -> HoldRule {
+        self.dice.hold_rule()
+    }
+
+    /// ホールドしていないダイスを振る
+    pub fn roll(&mut self) -> Result<RollRecord<N>, GameError> {
+        self.check_rollable()?;
+        let before = self.dice.values;
+        let held = self.dice.held;
+        if let Some(seed) = self.fair_seed {
+            // ロールの番号はゲーム全体で数える
+            self.dice
+                .roll_from(fair::roll_values(&seed, self.rolls_made));
+        } else {
+            match self.rng.as_mut() {
+                Some(rng) => self.dice.roll_model(&self.dice_model, rng),
+                None => self
+                    .dice
+                    .roll_model(&self.dice_model, &mut rand::thread_rng()),
+            }
+        }
+        Ok(self.finish_roll(held, before))
+    }
+
+    /// 実物のダイスで振った出目を入力する（ホールドしたダイスは今の出目のままであること）
+    pub fn enter_roll(&mut self, values: [u8; N]) -> Result<RollRecord<N>, GameError> {
+        self.check_rollable()?;
+        let faces = self.dice_model.faces() as u8;
+        if values.iter().any(|v| !(1..=faces).contains(v)) {
+            return Err(GameError::InvalidDieValue);
+        }
+        let before = self.dice.values;
+        let held = self.dice.held;
+        if (0..N).any(|i| held[i] && values[i] != before[i]) {
+            return Err(GameError::HeldDieChanged);
+        }
+        self.dice.roll_from(values);
+        Ok(self.finish_roll(held, before))
+    }
+
+    fn check_rollable(&self) -> Result<(), GameError> {
+        if self.rolls_left == 0 {
+            Err(GameError::NoRollsLeft)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn finish_roll(&mut self, held: [bool; N], before: [u8; N]) -> RollRecord<N> {
+        let record = RollRecord {
+            rolls_left: self.rolls_left,
+            held,
+            before,
+            after: self.dice.values,
+        };
+        self.rolls_left -= 1;
+        self.rolls_made += 1;
+        record
+    }
+
+    /// 残りのロールを使わずに振り直しをやめる（やめる前の残りロールを返す）
+    pub fn stand(&mut self) -> Result<u8, GameError> {
+        self.check_rerollable()?;
+        let rolls_left = self.rolls_left;
+        self.rolls_left = 0;
+        Ok(rolls_left)
+    }
+
+    /// ダイスのホールドを設定する（ロックされたダイスは外せない）
+    pub fn set_hold(&mut self, index: usize, hold: bool) -> Result<(), GameError> {
+        self.check_rerollable()?;
+        if index >= N {
+            return Err(GameError::InvalidDie);
+        }
+        if self.dice.locked[index] && !hold {
+            return Err(GameError::DieLocked);
+        }
+        self.dice.set_hold(index, hold);
+        Ok(())
+    }
+
+    pub fn toggle_hold(&mut self, index: usize) -> Result<(), GameError> {
+        let held = *self.dice.held.get(index).ok_or(GameError::InvalidDie)?;
+        self.set_hold(index, !held)
+    }
+
+    /// 手番中に振り直しの操作ができるか
+    fn check_rerollable(&self) -> Result<(), GameError> {
+        self.check_rolled()?;
+        self.check_rollable()
+    }
+
+    /// この手番で1回以上振ったか（記入の前提）
+    pub fn check_rolled(&self) -> Result<(), GameError> {
+        if self.rolls_left == self.turn_rolls {
+            Err(GameError::NotRolled)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn reset_holds(&mut self) {
+        self.dice.reset_holds();
+    }
+
+    /// 手番を終えて次の手番の状態に戻す（持ち越すルールでは残りのロールを次の手番に足す）
+    pub fn end(&mut self) {
+        self.dice.reset_holds();
+        let banked = if self.banking { self.rolls_left } else { 0 };
+        self.rolls_left = ROLLS_PER_TURN + banked;
+        self.turn_rolls = self.rolls_left;
+    }
+}
+
+impl<const N: usize> Default for Turn<N> {
+    fn default() -> Self {
+        Self::new(HoldRule::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_rolls_and_holds() {
+        let mut turn = Turn::<5>::new(HoldRule::Locking);
+        assert_eq!(turn.set_hold(0, true), Err(GameError::NotRolled));
+        assert_eq!(turn.check_rolled(), Err(GameError::NotRolled));
+
+        let record = turn.enter_roll([2, 2, 5, 6, 1]).unwrap();
+        assert_eq!(record.rolls_left, 3);
+        assert_eq!(record.after, [2, 2, 5, 6, 1]);
+        turn.set_hold(0, true).unwrap();
+        assert_eq!(
+            turn.enter_roll([3, 2, 5, 6, 1]),
+            Err(GameError::HeldDieChanged)
+        );
+        turn.enter_roll([2, 4, 4, 4, 4]).unwrap();
+        // ロックするルールでは振った後にホールドを外せない
+        assert_eq!(turn.set_hold(0, false), Err(GameError::DieLocked));
+        assert_eq!(turn.stand(), Ok(1));
+        assert_eq!(turn.roll(), Err(GameError::NoRollsLeft));
+
+        turn.end();
+        assert_eq!(turn.rolls_left(), ROLLS_PER_TURN);
+        assert!(!turn.dice().is_held(0));
+    }
+
+    #[test]
+    fn test_banked_rolls() {
+        let mut turn = Turn::<6>::with_banking(HoldRule::Free);
+        turn.enter_roll([1, 2, 3, 4, 5, 6]).unwrap();
+        turn.end();
+        // 使わなかった2回を持ち越す
+        assert_eq!(turn.banked_rolls(), 2);
+        assert_eq!(turn.rolls_left(), 5);
+        assert_eq!(turn.check_rolled(), Err(GameError::NotRolled));
+        for _ in 0..5 {
+            turn.roll().unwrap();
+        }
+        assert_eq!(turn.roll(), Err(GameError::NoRollsLeft));
+        turn.end();
+        assert_eq!(turn.banked_rolls(), 0);
+
+        let mut json: serde_json::Value = serde_json::to_value(&turn).unwrap();
+        json["turn_rolls"] = 1.into();
+        let tampered: Turn<6> = serde_json::from_value(json).unwrap();
+        assert_eq!(tampered.banked_rolls(), 0);
+    }
+
+    #[test]
+    fn test_turn_seeded_rolls_repeat() {
+        let mut a = Turn::<5>::new(HoldRule::Free);
+        let mut b = Turn::<5>::new(HoldRule::Free);
+        a.set_seed(7);
+        b.set_seed(7);
+        for _ in 0..ROLLS_PER_TURN {
+            assert_eq!(a.roll().unwrap(), b.roll().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_turn_dice_model() {
+        let mut turn = Turn::<5>::new(HoldRule::Free);
+        assert_eq!(
+            turn.enter_roll([1, 2, 3, 4, 7]),
+            Err(GameError::InvalidDieValue)
+        );
+        turn.set_dice_model(DiceModel::uniform(8).unwrap());
+        turn.enter_roll([1, 2, 3, 4, 8]).unwrap();
+        turn.set_dice_model(DiceModel::uniform(4).unwrap());
+        assert_eq!(
+            turn.enter_roll([1, 2, 3, 4, 5]),
+            Err(GameError::InvalidDieValue)
+        );
+        turn.set_seed(3);
+        for _ in 0..2 {
+            let record = turn.roll().unwrap();
+            assert!(record.after.iter().all(|v| (1..=4).contains(v)));
+        }
+
+        let json = serde_json::to_string(&turn).unwrap();
+        let restored: Turn = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.dice_model(), turn.dice_model());
+        // 標準のダイスは書き出さない（従来の JSON のまま）
+        let json = serde_json::to_string(&Turn::<5>::default()).unwrap();
+        assert!(!json.contains("dice_model"));
+    }
+}
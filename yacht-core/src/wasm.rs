@@ -0,0 +1,799 @@
+//! wasm-bindgen のファサード（`wasm` フィーチャー）
+//!
+//! コアの型をラップして JS 向けの API を提供する。JS からはこれまでどおり
+//! `GameState` / `YachtAI` / `AiTurn` などのクラス名で見える。
+//! ホールドは [0,1,1,0,1] 形式、カテゴリは 0-11 のインデックス、未記入の得点は -1 で表す。
+//! ヨット以外のルール（"generala" / "triple" / "yamb" / "yatzy"）は `GameState.with_ruleset` で作り、
+//! 欄を列と行で指定して遊ぶ（AI は `RulesetAI`）。ハウスルールは `GameState.with_custom_rules` で作る。
+
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+
+use crate::custom::CustomRuleset;
+use crate::dice_model::DiceModel;
+use crate::play::{RulesetAI, Strategy};
+use crate::{
+    calculate_score, dp_table, yatzy, AiStep, AiStepKind, AiTurn, Category, CategoryChoice, Cell,
+    Dice, GameState, HoldDecision, HoldRule, LuckSkillBreakdown, Ruleset, ScoreBoard, TurnLog,
+    YachtAI,
+};
+
+fn bools_to_u8(values: &[bool]) -> Vec<u8> {
+    values.iter().map(|&b| if b { 1 } else { 0 }).collect()
+}
+
+fn u8_to_bools(values: &[u8]) -> [bool; 5] {
+    std::array::from_fn(|i| values.get(i).is_some_and(|&v| v == 1))
+}
+
+fn parse_ruleset(name: &str) -> Result<Ruleset, JsError> {
+    Ruleset::from_name(name).ok_or_else(|| JsError::new(&format!("unknown ruleset: {}", name)))
+}
+
+fn score_or_sentinel(board: &ScoreBoard, category_index: usize) -> i16 {
+    Category::from_index(category_index)
+        .and_then(|c| board.score(c))
+        .map(|s| s as i16)
+        .unwrap_or(-1)
+}
+
+// ========== Dice ==========
+
+#[wasm_bindgen(js_name = Dice)]
+pub struct WasmDice {
+    inner: Dice,
+}
+
+#[wasm_bindgen(js_class = Dice)]
+impl WasmDice {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmDice {
+        WasmDice { inner: Dice::new() }
+    }
+
+    pub fn with_hold_rule(hold_rule: HoldRule) -> WasmDice {
+        WasmDice {
+            inner: Dice::with_hold_rule(hold_rule),
+        }
+    }
+
+    pub fn get_hold_rule(&self) -> HoldRule {
+        self.inner.hold_rule()
+    }
+
+    pub fn roll(&mut self) {
+        self.inner.roll();
+    }
+
+    pub fn set_hold(&mut self, index: usize, hold: bool) {
+        self.inner.set_hold(index, hold);
+    }
+
+    pub fn toggle_hold(&mut self, index: usize) {
+        self.inner.toggle_hold(index);
+    }
+
+    pub fn reset_holds(&mut self) {
+        self.inner.reset_holds();
+    }
+
+    pub fn is_locked(&self, index: usize) -> bool {
+        self.inner.is_locked(index)
+    }
+
+    pub fn get_locks(&self) -> Vec<u8> {
+        bools_to_u8(&self.inner.locked())
+    }
+
+    pub fn get_value(&self, index: usize) -> u8 {
+        self.inner.values()[index]
+    }
+
+    pub fn get_values(&self) -> Vec<u8> {
+        self.inner.values().to_vec()
+    }
+
+    pub fn is_held(&self, index: usize) -> bool {
+        self.inner.is_held(index)
+    }
+
+    pub fn get_holds(&self) -> Vec<u8> {
+        bools_to_u8(&self.inner.held())
+    }
+
+    pub fn set_values(&mut self, values: Vec<u8>) {
+        let mut current = self.inner.values();
+        for (i, v) in values.iter().take(5).enumerate() {
+            current[i] = *v;
+        }
+        self.inner.set_values(current);
+    }
+}
+
+impl Default for WasmDice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ========== ScoreBoard ==========
+
+#[wasm_bindgen(js_name = ScoreBoard)]
+pub struct WasmScoreBoard {
+    inner: ScoreBoard,
+}
+
+#[wasm_bindgen(js_class = ScoreBoard)]
+impl WasmScoreBoard {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmScoreBoard {
+        WasmScoreBoard {
+            inner: ScoreBoard::new(),
+        }
+    }
+
+    pub fn set_score(&mut self, category: Category, score: u8) -> bool {
+        self.inner.set_score(category, score)
+    }
+
+    pub fn get_score(&self, category: Category) -> i16 {
+        score_or_sentinel(&self.inner, category as usize)
+    }
+
+    pub fn is_used(&self, category: Category) -> bool {
+        self.inner.is_used(category)
+    }
+
+    pub fn get_upper_total(&self) -> u16 {
+        self.inner.upper_total()
+    }
+
+    pub fn get_upper_bonus(&self) -> u16 {
+        self.inner.upper_bonus()
+    }
+
+    pub fn get_lower_total(&self) -> u16 {
+        self.inner.lower_total()
+    }
+
+    pub fn get_total(&self) -> u16 {
+        self.inner.total()
+    }
+
+    pub fn available_categories(&self) -> Vec<u8> {
+        self.inner
+            .available_categories()
+            .into_iter()
+            .map(|c| c as u8)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+
+    /// 使用済みカテゴリのビットマスクを取得
+    pub fn used_hands_mask(&self) -> usize {
+        self.inner.used_hands_mask()
+    }
+
+    /// 上段スコアの累計を取得（63上限）
+    pub fn upper_sum_capped(&self) -> usize {
+        self.inner.upper_sum_capped()
+    }
+}
+
+impl Default for WasmScoreBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ========== GameState ==========
+
+#[wasm_bindgen(js_name = GameState)]
+pub struct WasmGameState {
+    inner: GameState,
+}
+
+#[wasm_bindgen(js_class = GameState)]
+impl WasmGameState {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmGameState {
+        WasmGameState {
+            inner: GameState::new(),
+        }
+    }
+
+    pub fn with_hold_rule(hold_rule: HoldRule) -> WasmGameState {
+        WasmGameState {
+            inner: GameState::with_hold_rule(hold_rule),
+        }
+    }
+
+    /// ルールを名前で指定して作る（solo なら1人用）
+    pub fn with_ruleset(ruleset: &str, solo: bool) -> Result<WasmGameState, JsError> {
+        Ok(Self::from_ruleset(parse_ruleset(ruleset)?, solo))
+    }
+
+    /// ハウスルール（`custom` の JSON 定義）のゲームを作る
+    pub fn with_custom_rules(rules_json: &str, solo: bool) -> Result<WasmGameState, JsError> {
+        let rules =
+            CustomRuleset::from_json(rules_json).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(Self::from_ruleset(Ruleset::Custom(Arc::new(rules)), solo))
+    }
+
+    pub fn get_ruleset(&self) -> String {
+        self.inner.ruleset().name().to_string()
+    }
+
+    /// 得点表の列の数
+    pub fn get_columns(&self) -> usize {
+        self.inner.ruleset().columns()
+    }
+
+    /// 得点表の行の数（このルールで使わない行も含む）
+    pub fn get_rows(&self) -> usize {
+        self.inner.ruleset().rows()
+    }
+
+    pub fn get_row_name(&self, row: usize) -> String {
+        self.inner.ruleset().row_name(row).to_string()
+    }
+
+    /// 以降のロールをシードから決定的に生成する
+    pub fn set_seed(&mut self, seed: u64) {
+        self.inner.set_seed(seed);
+    }
+
+    /// 以降のロールで振るダイスを目ごとの重み（weights[0] が 1 の目、2-8 面）で設定する
+    /// （標準以外のダイスで遊べるのはハウスルールだけ）
+    pub fn set_dice_weights(&mut self, weights: Vec<f64>) -> Result<(), JsError> {
+        let model = DiceModel::weighted(&weights).map_err(|e| JsError::new(&e.to_string()))?;
+        self.inner
+            .set_dice_model(model)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    pub fn roll_dice(&mut self) -> bool {
+        self.inner.roll().is_ok()
+    }
+
+    /// 実物のダイスで振った出目を入力する（[1-6] x 5、ホールドしたダイスは今の出目のまま）
+    pub fn enter_roll(&mut self, values: Vec<u8>) -> bool {
+        match <[u8; 5]>::try_from(values) {
+            Ok(values) => self.inner.enter_roll(values).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// 残りのロールを使わずに振り直しをやめる（この後はカテゴリ選択のみ）
+    pub fn stand(&mut self) -> bool {
+        self.inner.stand().is_ok()
+    }
+
+    pub fn toggle_hold(&mut self, index: usize) {
+        let _ = self.inner.toggle_hold(index);
+    }
+
+    pub fn get_dice_locks(&self) -> Vec<u8> {
+        bools_to_u8(&self.inner.dice().locked())
+    }
+
+    pub fn get_hold_rule(&self) -> HoldRule {
+        self.inner.hold_rule()
+    }
+
+    pub fn reset_holds(&mut self) {
+        self.inner.reset_holds();
+    }
+
+    pub fn select_category(&mut self, category_index: usize) -> bool {
+        Category::from_index(category_index).is_some_and(|c| self.inner.select(c).is_ok())
+    }
+
+    /// 欄（列・行）を選んで記入する
+    pub fn select_cell(&mut self, column: usize, row: usize) -> bool {
+        self.inner.select_cell(Cell::new(column, row)).is_ok()
+    }
+
+    /// この手番で記入する行を宣言する（宣言の列があるルールの1回目のロールの後）
+    pub fn announce(&mut self, row: usize) -> bool {
+        self.inner.announce(row).is_ok()
+    }
+
+    /// 席（0 = プレイヤー, 1 = AI）の欄の得点（未記入は -1）
+    pub fn get_cell_score(&self, seat: u8, column: usize, row: usize) -> i16 {
+        self.inner
+            .board(seat)
+            .cell(Cell::new(column, row))
+            .map_or(-1, i16::from)
+    }
+
+    pub fn get_dice_values(&self) -> Vec<u8> {
+        self.inner.dice().values().to_vec()
+    }
+
+    pub fn get_dice_holds(&self) -> Vec<u8> {
+        bools_to_u8(&self.inner.dice().held())
+    }
+
+    pub fn get_rolls_left(&self) -> u8 {
+        self.inner.rolls_left()
+    }
+
+    pub fn get_current_player(&self) -> u8 {
+        self.inner.current_player()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.inner.is_game_over()
+    }
+
+    pub fn get_player_score(&self, category_index: usize) -> i16 {
+        score_or_sentinel(self.inner.board(0), category_index)
+    }
+
+    pub fn get_ai_score(&self, category_index: usize) -> i16 {
+        score_or_sentinel(self.inner.board(1), category_index)
+    }
+
+    pub fn get_player_total(&self) -> u16 {
+        self.inner.board(0).total()
+    }
+
+    pub fn get_ai_total(&self) -> u16 {
+        self.inner.board(1).total()
+    }
+
+    pub fn get_player_upper_total(&self) -> u16 {
+        self.inner.board(0).upper_total()
+    }
+
+    pub fn get_ai_upper_total(&self) -> u16 {
+        self.inner.board(1).upper_total()
+    }
+
+    pub fn get_player_upper_bonus(&self) -> u16 {
+        self.inner.board(0).upper_bonus()
+    }
+
+    pub fn get_ai_upper_bonus(&self) -> u16 {
+        self.inner.board(1).upper_bonus()
+    }
+
+    pub fn get_available_categories(&self) -> Vec<u8> {
+        self.inner
+            .current_board()
+            .available_categories()
+            .into_iter()
+            .map(|c| c as u8)
+            .collect()
+    }
+
+    pub fn get_potential_score(&self, category_index: usize) -> u8 {
+        Category::from_index(category_index)
+            .map(|c| self.inner.potential_score(c))
+            .unwrap_or(0)
+    }
+
+    pub fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+
+    /// AI用: 現在のAIの上段累計スコア（63上限）
+    pub fn ai_upper_sum_capped(&self) -> usize {
+        self.inner.board(1).upper_sum_capped()
+    }
+
+    /// AI用: AIの使用済みカテゴリマスク
+    pub fn ai_used_hands_mask(&self) -> usize {
+        self.inner.board(1).used_hands_mask()
+    }
+
+    /// プレイヤー用: 上段累計スコア（63上限）
+    pub fn player_upper_sum_capped(&self) -> usize {
+        self.inner.board(0).upper_sum_capped()
+    }
+
+    /// プレイヤー用: 使用済みカテゴリマスク
+    pub fn player_used_hands_mask(&self) -> usize {
+        self.inner.board(0).used_hands_mask()
+    }
+}
+
+impl WasmGameState {
+    fn from_ruleset(ruleset: Ruleset, solo: bool) -> WasmGameState {
+        WasmGameState {
+            inner: if solo {
+                GameState::solo(ruleset)
+            } else {
+                GameState::with_ruleset(ruleset)
+            },
+        }
+    }
+}
+
+impl Default for WasmGameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ========== AI ==========
+
+/// キープの候補
+#[wasm_bindgen(js_name = HoldChoice, getter_with_clone)]
+pub struct WasmHoldChoice {
+    /// ホールドするダイス [0,1,1,0,1]
+    pub holds: Vec<u8>,
+    /// 振り直さずに今の出目で得点する（holds は全て1）
+    pub stop: bool,
+    /// 最終的な合計点数の期待値
+    pub expected: f32,
+    /// 最善の候補との期待値の差
+    pub loss: f32,
+    /// 他の候補と期待値が（誤差の範囲で）同じ
+    pub tied: bool,
+}
+
+/// AIの手番の1ロール
+#[wasm_bindgen(js_name = TurnRoll, getter_with_clone)]
+#[derive(Clone)]
+pub struct WasmTurnRoll {
+    /// 振る前にホールドしたダイス（1回目は全て0）
+    pub holds: Vec<u8>,
+    /// 振った後の出目
+    pub dice: Vec<u8>,
+}
+
+/// AIの手番の記録
+#[wasm_bindgen(js_name = TurnLog, getter_with_clone)]
+pub struct WasmTurnLog {
+    pub rolls: Vec<WasmTurnRoll>,
+    /// 3回振り切る前に振り直しをやめた
+    pub stopped: bool,
+    pub column: usize,
+    pub row: usize,
+    /// 行がカテゴリのルール（ヨット・ヘネラル・トリプルヨット）での記入したカテゴリ
+    pub category: Option<Category>,
+    pub score: u8,
+}
+
+impl WasmTurnLog {
+    fn new(log: TurnLog, ruleset: &Ruleset) -> WasmTurnLog {
+        WasmTurnLog {
+            rolls: log
+                .rolls
+                .iter()
+                .map(|r| WasmTurnRoll {
+                    holds: bools_to_u8(&r.holds),
+                    dice: r.dice.to_vec(),
+                })
+                .collect(),
+            stopped: log.stopped,
+            column: log.cell.column,
+            row: log.cell.row,
+            category: Category::from_index(log.cell.row).filter(|&c| ruleset.has_category(c)),
+            score: log.score,
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = YachtAI)]
+pub struct WasmYachtAI {
+    inner: YachtAI,
+}
+
+#[wasm_bindgen(js_class = YachtAI)]
+impl WasmYachtAI {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmYachtAI {
+        WasmYachtAI {
+            inner: YachtAI::new(),
+        }
+    }
+
+    /// AIの手番を実行（ロールとカテゴリ選択を含む）
+    pub fn play_turn(&self, game: &mut WasmGameState) -> Result<WasmTurnLog, JsError> {
+        let log = self
+            .inner
+            .play_turn(&mut game.inner)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WasmTurnLog::new(log, game.inner.ruleset()))
+    }
+
+    /// AIが選ぶべきホールドパターンを取得
+    /// 振り直さない場合は全てのダイスをホールドしたパターンを返す
+    pub fn get_holds_decision(&self, game: &WasmGameState) -> Vec<u8> {
+        match self.inner.decide_holds(&game.inner) {
+            HoldDecision::Stop => vec![1; 5],
+            HoldDecision::Reroll(holds) => bools_to_u8(&holds),
+        }
+    }
+
+    /// AIが振り直しをやめて得点すべきかを取得
+    pub fn get_stop_decision(&self, game: &WasmGameState) -> bool {
+        self.inner.decide_holds(&game.inner) == HoldDecision::Stop
+    }
+
+    /// AIが選ぶべきカテゴリを取得（決まらなければ undefined）
+    pub fn get_category_decision(&self, game: &WasmGameState) -> Option<usize> {
+        self.inner.decide_category(&game.inner).map(|c| c as usize)
+    }
+
+    /// プレイヤー向け: カテゴリ選択の上位n個を取得（n = 0 なら全候補）
+    pub fn get_top_category_choices(&self, game: &WasmGameState, n: usize) -> Vec<CategoryChoice> {
+        self.inner.category_choices(&game.inner, n)
+    }
+
+    /// プレイヤー向け: キープパターンの上位n個を取得（n = 0 なら全候補）
+    pub fn get_top_hold_choices(&self, game: &WasmGameState, n: usize) -> Vec<WasmHoldChoice> {
+        self.inner
+            .hold_choices(&game.inner, n)
+            .into_iter()
+            .map(|c| WasmHoldChoice {
+                holds: bools_to_u8(&c.holds),
+                stop: c.stop,
+                expected: c.expected,
+                loss: c.loss,
+                tied: c.tied,
+            })
+            .collect()
+    }
+
+    /// プレイヤー向け: 現在の状態からの総合期待値を取得
+    pub fn get_player_expected_score(&self, game: &WasmGameState) -> f32 {
+        self.inner.player_expected_score(&game.inner)
+    }
+
+    /// 手番中の期待値
+    pub fn get_live_expected_score(&self, game: &WasmGameState) -> f32 {
+        self.inner.live_expected_score(&game.inner)
+    }
+
+    /// 現在のホールドのまま次に振る場合の最終的な合計点数の期待値
+    pub fn get_held_expected_score(&self, game: &WasmGameState) -> f32 {
+        self.inner.held_expected_score(&game.inner)
+    }
+
+    /// 指定した席（0 = プレイヤー, 1 = AI）の得点を運と実力に分解
+    pub fn get_luck_skill_breakdown(&self, game: &WasmGameState, seat: u8) -> LuckSkillBreakdown {
+        self.inner.luck_skill_breakdown(&game.inner, seat)
+    }
+
+    /// サイコロを振り、そのロールによる期待値の変化（運）を返す
+    /// 振れなかった場合は 0
+    pub fn roll_with_luck(&self, game: &mut WasmGameState) -> f32 {
+        self.inner.roll_with_luck(&mut game.inner).unwrap_or(0.0)
+    }
+}
+
+impl Default for WasmYachtAI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ========== AiTurn ==========
+
+/// AIの手番の1ステップ
+#[wasm_bindgen(js_name = AiStep, getter_with_clone)]
+#[derive(Clone)]
+pub struct WasmAiStep {
+    pub kind: AiStepKind,
+    /// SetHolds: ホールドするダイス [0,1,1,0,1]
+    pub holds: Option<Vec<u8>>,
+    /// ChooseCategory: 選ぶカテゴリ
+    pub category: Option<Category>,
+}
+
+impl From<AiStep> for WasmAiStep {
+    fn from(step: AiStep) -> Self {
+        WasmAiStep {
+            kind: step.kind(),
+            holds: match step {
+                AiStep::SetHolds(holds) => Some(bools_to_u8(&holds)),
+                _ => None,
+            },
+            category: match step {
+                AiStep::ChooseCategory(c) => Some(c),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl WasmAiStep {
+    fn to_step(&self) -> Option<AiStep> {
+        Some(match self.kind {
+            AiStepKind::Roll => AiStep::Roll,
+            AiStepKind::SetHolds => AiStep::SetHolds(u8_to_bools(self.holds.as_deref()?)),
+            AiStepKind::Stop => AiStep::Stop,
+            AiStepKind::ChooseCategory => AiStep::ChooseCategory(self.category?),
+        })
+    }
+}
+
+/// AIの手番の進行状態
+#[wasm_bindgen(js_name = AiTurn)]
+pub struct WasmAiTurn {
+    inner: AiTurn,
+}
+
+#[wasm_bindgen(js_class = AiTurn)]
+impl WasmAiTurn {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmAiTurn {
+        WasmAiTurn {
+            inner: AiTurn::new(),
+        }
+    }
+
+    /// 次に行うステップを決める（ゲーム状態は変更しない）
+    /// 手番が終わっていれば undefined
+    pub fn next_step(&self, game: &WasmGameState) -> Option<WasmAiStep> {
+        self.inner.next_step(&game.inner).map(WasmAiStep::from)
+    }
+
+    /// ステップをゲーム状態に適用する
+    pub fn apply(&mut self, game: &mut WasmGameState, step: &WasmAiStep) -> bool {
+        step.to_step()
+            .is_some_and(|s| self.inner.apply(&mut game.inner, &s).is_ok())
+    }
+
+    /// 次のステップを決めて適用し、そのステップを返す
+    pub fn step(&mut self, game: &mut WasmGameState) -> Result<Option<WasmAiStep>, JsError> {
+        self.inner
+            .step(&mut game.inner)
+            .map(|step| step.map(WasmAiStep::from))
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+}
+
+impl Default for WasmAiTurn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ゲームのルールに合わせた AI（ヨット以外のルールも遊べる）
+#[wasm_bindgen(js_name = RulesetAI)]
+pub struct WasmRulesetAI {
+    inner: RulesetAI,
+}
+
+#[wasm_bindgen(js_class = RulesetAI)]
+impl WasmRulesetAI {
+    /// ヤッツィーは先に `load_yatzy_table` でDPテーブルを読み込む
+    #[wasm_bindgen(constructor)]
+    pub fn new(ruleset: &str) -> Result<WasmRulesetAI, JsError> {
+        let inner =
+            RulesetAI::new(&parse_ruleset(ruleset)?).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WasmRulesetAI { inner })
+    }
+
+    /// game のルールとダイスで遊ぶ AI（ハウスルールはここで期待値を計算する）
+    pub fn for_game(game: &WasmGameState) -> Result<WasmRulesetAI, JsError> {
+        let inner = RulesetAI::for_game(&game.inner).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WasmRulesetAI { inner })
+    }
+
+    /// AIの手番を実行（ロール・宣言・記入を含む）
+    pub fn play_turn(&self, game: &mut WasmGameState) -> Result<WasmTurnLog, JsError> {
+        let log = self
+            .inner
+            .play_turn(&mut game.inner)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WasmTurnLog::new(log, game.inner.ruleset()))
+    }
+
+    /// AIが選ぶべきホールドパターンを取得（振り直さない場合は全て1）
+    pub fn get_holds_decision(&self, game: &WasmGameState) -> Vec<u8> {
+        match self.inner.decide_holds(&game.inner) {
+            HoldDecision::Stop => vec![1; 5],
+            HoldDecision::Reroll(holds) => bools_to_u8(&holds),
+        }
+    }
+
+    /// AIが選ぶべき欄 [列, 行]（決まらなければ undefined）
+    pub fn get_cell_decision(&self, game: &WasmGameState) -> Option<Vec<usize>> {
+        self.inner
+            .decide_cell(&game.inner)
+            .map(|cell| vec![cell.column, cell.row])
+    }
+}
+
+// ========== Functions ==========
+
+#[wasm_bindgen]
+pub fn calculate_score_js(dice: Vec<u8>, category_index: usize) -> u8 {
+    let Ok(dice_arr) = <[u8; 5]>::try_from(dice) else {
+        return 0;
+    };
+    Category::from_index(category_index)
+        .map(|c| calculate_score(&dice_arr, c))
+        .unwrap_or(0)
+}
+
+/// DPテーブル（YDP1 形式）を実行時に読み込む
+/// embed-dp-table フィーチャーを無効にしてビルドした場合、AIやヒントを使う前に呼ぶ
+#[wasm_bindgen]
+pub fn load_dp_table(data: &[u8]) -> Result<(), JsError> {
+    dp_table::load_table(data).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// ヤッツィーのDPテーブル（YDP1 形式）を実行時に読み込む
+/// embed-yatzy-table フィーチャーなしのビルドでは、ヤッツィーの AI を作る前に呼ぶ
+#[wasm_bindgen]
+pub fn load_yatzy_table(data: &[u8]) -> Result<(), JsError> {
+    yatzy::load_table(data).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// DPテーブルが利用可能か
+#[wasm_bindgen]
+pub fn is_dp_table_loaded() -> bool {
+    dp_table::is_table_loaded()
+}
+
+/// このエンジンの得点ルールのチェックサム（読み込むDPテーブルのヘッダーと一致する必要がある）
+#[wasm_bindgen]
+pub fn get_dp_table_rules_checksum() -> u32 {
+    dp_table::rules_checksum()
+}
+
+#[wasm_bindgen]
+pub fn get_category_name(category_index: usize) -> String {
+    Category::from_index(category_index)
+        .map(Category::name)
+        .unwrap_or("Unknown")
+        .to_string()
+}
+
+#[wasm_bindgen]
+pub fn get_category_name_ja(category_index: usize) -> String {
+    Category::from_index(category_index)
+        .map(Category::name_ja)
+        .unwrap_or("不明")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ai_step_roundtrip() {
+        let steps = [
+            AiStep::Roll,
+            AiStep::SetHolds([true, false, true, false, false]),
+            AiStep::Stop,
+            AiStep::ChooseCategory(Category::Choice),
+        ];
+        for step in steps {
+            assert_eq!(WasmAiStep::from(step).to_step(), Some(step));
+        }
+    }
+
+    #[test]
+    fn test_facade_turn() {
+        let mut game = WasmGameState::new();
+        let mut turn = WasmAiTurn::new();
+        while let Some(step) = turn.next_step(&game) {
+            assert!(turn.apply(&mut game, &step));
+        }
+        assert!(turn.is_finished());
+        assert_eq!(game.get_current_player(), 1);
+        assert_eq!(game.get_available_categories().len(), 12);
+        assert_eq!(
+            (0..12).filter(|&c| game.get_player_score(c) >= 0).count(),
+            1
+        );
+        assert_eq!(game.get_player_score(12), -1);
+    }
+}
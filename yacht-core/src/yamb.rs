@@ -0,0 +1,514 @@
+//! ヤンブ（Yamb、バルカン半島で遊ばれる複数列のルール）
+//!
+//! 13行: 1-6の目、マックス・ミニマム（出目の合計）、ケンタ（1-5 / 2-6 のストレート。
+//! 手番の1回目のロールで 66点、2回目で 56点、3回目で 46点）、トリリング（同じ目3個 + 10点）、
+//! フル（3+2 で合計 + 30点）、ポーカー（同じ目4個 + 40点）、ヤンブ（同じ目5個 + 50点）。
+//!
+//! 4列: 上から順に埋める「下り」、下から順に埋める「上り」、順不同の「自由」、
+//! 1回目のロールの直後に記入する行を宣言してから振り直す「宣言」。
+//! 各列で上段が 60点以上ならボーナス 30点、中段は (マックス - ミニマム) × 1の目の欄の得点。
+//!
+//! 得点表は `ScoreBoard`（`Ruleset::Yamb`、列 × 行の欄は `cell`）で、宣言は `GameState::announce`。
+//! 52マスで列ごとの順序もあるので DPテーブルは持たず、AI は手番内の探索（`solver`）の葉を
+//! 行ごとの目安点で評価する。
+
+use serde::{Deserialize, Serialize};
+
+use crate::dp_table::{self, DicePattern};
+use crate::play::Strategy;
+use crate::solver::{keep_to_holds, Leaf, TurnSolver};
+use crate::{Cell, GameError, GameState, HoldDecision, Ruleset, ScoreBoard};
+
+pub use crate::turn::ROLLS_PER_TURN;
+pub const NUM_ROWS: usize = 13;
+pub const NUM_COLUMNS: usize = 4;
+pub const UPPER_BONUS_THRESHOLD: u16 = 60;
+pub const UPPER_BONUS_POINTS: u16 = 30;
+
+/// ヤンブの行
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum YambRow {
+    Ones = 0,
+    Twos = 1,
+    Threes = 2,
+    Fours = 3,
+    Fives = 4,
+    Sixes = 5,
+    Max = 6,
+    Min = 7,
+    Straight = 8,
+    ThreeOfAKind = 9,
+    FullHouse = 10,
+    Poker = 11,
+    Yamb = 12,
+}
+
+impl YambRow {
+    pub const ALL: [YambRow; NUM_ROWS] = [
+        YambRow::Ones,
+        YambRow::Twos,
+        YambRow::Threes,
+        YambRow::Fours,
+        YambRow::Fives,
+        YambRow::Sixes,
+        YambRow::Max,
+        YambRow::Min,
+        YambRow::Straight,
+        YambRow::ThreeOfAKind,
+        YambRow::FullHouse,
+        YambRow::Poker,
+        YambRow::Yamb,
+    ];
+
+    pub fn from_index(index: usize) -> Option<YambRow> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// 英語の表示名
+    pub fn name(self) -> &'static str {
+        match self {
+            YambRow::Ones => "Ones",
+            YambRow::Twos => "Twos",
+            YambRow::Threes => "Threes",
+            YambRow::Fours => "Fours",
+            YambRow::Fives => "Fives",
+            YambRow::Sixes => "Sixes",
+            YambRow::Max => "Max",
+            YambRow::Min => "Min",
+            YambRow::Straight => "Straight",
+            YambRow::ThreeOfAKind => "Three of a Kind",
+            YambRow::FullHouse => "Full House",
+            YambRow::Poker => "Poker",
+            YambRow::Yamb => "Yamb",
+        }
+    }
+
+    /// 日本語の表示名
+    pub fn name_ja(self) -> &'static str {
+        match self {
+            YambRow::Ones => "1の目",
+            YambRow::Twos => "2の目",
+            YambRow::Threes => "3の目",
+            YambRow::Fours => "4の目",
+            YambRow::Fives => "5の目",
+            YambRow::Sixes => "6の目",
+            YambRow::Max => "マックス",
+            YambRow::Min => "ミニマム",
+            YambRow::Straight => "ケンタ",
+            YambRow::ThreeOfAKind => "トリリング",
+            YambRow::FullHouse => "フル",
+            YambRow::Poker => "ポーカー",
+            YambRow::Yamb => "ヤンブ",
+        }
+    }
+}
+
+/// ヤンブの列（記入順の制約）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum YambColumn {
+    /// 上の行から順に埋める
+    Down = 0,
+    /// 下の行から順に埋める
+    Up = 1,
+    /// 順不同
+    Free = 2,
+    /// 1回目のロールの直後に宣言した行にだけ記入できる
+    Announce = 3,
+}
+
+impl YambColumn {
+    pub const ALL: [YambColumn; NUM_COLUMNS] = [
+        YambColumn::Down,
+        YambColumn::Up,
+        YambColumn::Free,
+        YambColumn::Announce,
+    ];
+
+    pub fn from_index(index: usize) -> Option<YambColumn> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// 英語の表示名
+    pub fn name(self) -> &'static str {
+        match self {
+            YambColumn::Down => "Down",
+            YambColumn::Up => "Up",
+            YambColumn::Free => "Free",
+            YambColumn::Announce => "Announce",
+        }
+    }
+
+    /// 日本語の表示名
+    pub fn name_ja(self) -> &'static str {
+        match self {
+            YambColumn::Down => "下り",
+            YambColumn::Up => "上り",
+            YambColumn::Free => "自由",
+            YambColumn::Announce => "宣言",
+        }
+    }
+}
+
+/// 出目パターンからヤンブの得点を計算（roll_number: 手番の何回目のロールの出目か、1 から数える）
+fn pattern_score(p: &DicePattern, row: YambRow, roll_number: u8) -> u8 {
+    let sum: u8 = (0..6).map(|i| p[i] * (i as u8 + 1)).sum();
+    // count 個以上ある一番大きい目
+    let best_face = |count: u8| (0..6).rev().find(|&i| p[i] >= count).map(|i| i as u8 + 1);
+    match row {
+        YambRow::Ones
+        | YambRow::Twos
+        | YambRow::Threes
+        | YambRow::Fours
+        | YambRow::Fives
+        | YambRow::Sixes => {
+            let face = row as usize;
+            p[face] * (face as u8 + 1)
+        }
+        YambRow::Max | YambRow::Min => sum,
+        // 1-5 か 2-6
+        YambRow::Straight if dp_table::has_big_straight(p) => match roll_number {
+            1 => 66,
+            2 => 56,
+            _ => 46,
+        },
+        YambRow::ThreeOfAKind => best_face(3).map_or(0, |f| 3 * f + 10),
+        YambRow::FullHouse if p.contains(&3) && p.contains(&2) => sum + 30,
+        YambRow::Poker => best_face(4).map_or(0, |f| 4 * f + 40),
+        YambRow::Yamb => best_face(5).map_or(0, |f| 5 * f + 50),
+        _ => 0,
+    }
+}
+
+/// 得点計算（サイコロ値配列版）
+pub fn calculate_yamb_score(dice: &[u8; 5], row: YambRow, roll_number: u8) -> u8 {
+    pattern_score(&dp_table::dice_to_pattern(dice), row, roll_number)
+}
+
+/// ヤンブの欄
+pub fn cell(column: YambColumn, row: YambRow) -> Cell {
+    Cell::new(column as usize, row as usize)
+}
+
+/// 列の記入順で row が今埋められるか（cells はその列の行ごとの得点。宣言の列は宣言すれば埋められる欄）
+pub(crate) fn is_open(column: usize, row: usize, cells: &[Option<u8>]) -> bool {
+    match YambColumn::from_index(column) {
+        Some(YambColumn::Down) => cells[..row].iter().all(|c| c.is_some()),
+        Some(YambColumn::Up) => cells[row + 1..].iter().all(|c| c.is_some()),
+        Some(YambColumn::Free | YambColumn::Announce) => true,
+        None => false,
+    }
+}
+
+/// 中段の得点 (マックス - ミニマム) × 1の目の欄（3つ揃うまでは 0、マイナスにはしない）
+pub fn middle_total(cells: &[Option<u8>]) -> u16 {
+    match (
+        cells[YambRow::Max as usize],
+        cells[YambRow::Min as usize],
+        cells[YambRow::Ones as usize],
+    ) {
+        (Some(max), Some(min), Some(ones)) => max.saturating_sub(min) as u16 * ones as u16,
+        _ => 0,
+    }
+}
+
+/// 下段（ケンタ以降）の合計
+pub fn lower_total(cells: &[Option<u8>]) -> u16 {
+    cells[YambRow::Straight as usize..]
+        .iter()
+        .flatten()
+        .map(|&s| s as u16)
+        .sum()
+}
+
+/// マックスの目安点
+const MAX_PAR: f32 = 24.0;
+/// ミニマムの目安点
+const MIN_PAR: f32 = 9.0;
+/// マックス・ミニマムの 1点が中段で何点になるかの目安（1の目の欄のおおよその得点）
+const MIDDLE_WEIGHT: f32 = 2.0;
+/// ケンタ・トリリング・フル・ポーカー・ヤンブの目安点
+const LOWER_PAR: [f32; 5] = [40.0, 20.0, 30.0, 25.0, 10.0];
+/// 上段で各3個（ボーナスちょうどのペース）との差にかける重み
+const UPPER_PACE_WEIGHT: f32 = 0.5;
+/// 自由の列の欄を空けておく価値（後で困った出目の逃げ場になる）
+const FREE_CELL_VALUE: f32 = 3.0;
+
+/// 手番の途中状態の葉の評価（記入できる欄の中で一番よいもの）
+struct YambLeaf<'a> {
+    board: &'a ScoreBoard,
+    cells: Vec<Cell>,
+}
+
+impl<'a> YambLeaf<'a> {
+    /// 宣言していない手番（宣言の列には記入できない）
+    fn unannounced(board: &'a ScoreBoard) -> YambLeaf<'a> {
+        let mut cells = board.available_cells();
+        cells.retain(|c| c.column != YambColumn::Announce as usize);
+        YambLeaf { board, cells }
+    }
+
+    /// row を宣言した手番
+    fn announced(board: &'a ScoreBoard, row: usize) -> YambLeaf<'a> {
+        YambLeaf {
+            board,
+            cells: vec![Cell::new(YambColumn::Announce as usize, row)],
+        }
+    }
+
+    /// 欄に score を記入したときの価値（目安点との差）
+    fn cell_value(&self, cell: Cell, score: u8) -> f32 {
+        let Some(row) = YambRow::from_index(cell.row) else {
+            return f32::NEG_INFINITY;
+        };
+        let score_f = score as f32;
+        let value = match row {
+            YambRow::Max => (score_f - MAX_PAR) * MIDDLE_WEIGHT,
+            YambRow::Min => (MIN_PAR - score_f) * MIDDLE_WEIGHT,
+            YambRow::Straight
+            | YambRow::ThreeOfAKind
+            | YambRow::FullHouse
+            | YambRow::Poker
+            | YambRow::Yamb => score_f - LOWER_PAR[row as usize - YambRow::Straight as usize],
+            _ => {
+                let par = 3.0 * (row as usize + 1) as f32;
+                let upper = self.board.column_upper_total(cell.column);
+                if upper >= UPPER_BONUS_THRESHOLD {
+                    score_f - par
+                } else {
+                    let bonus = if upper + score as u16 >= UPPER_BONUS_THRESHOLD {
+                        UPPER_BONUS_POINTS as f32
+                    } else {
+                        0.0
+                    };
+                    score_f + bonus - par + (score_f - par) * UPPER_PACE_WEIGHT
+                }
+            }
+        };
+        if cell.column == YambColumn::Free as usize {
+            value - FREE_CELL_VALUE
+        } else {
+            value
+        }
+    }
+
+    fn best(&self, dice: &DicePattern, roll_number: u8) -> Option<(usize, f32)> {
+        let mut best: Option<(usize, f32)> = None;
+        for (i, &cell) in self.cells.iter().enumerate() {
+            let score =
+                YambRow::from_index(cell.row).map_or(0, |r| pattern_score(dice, r, roll_number));
+            let value = self.cell_value(cell, score);
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((i, value));
+            }
+        }
+        best
+    }
+}
+
+impl Leaf for YambLeaf<'_> {
+    fn num_dice(&self) -> usize {
+        5
+    }
+
+    fn best_category(&self, dice: &DicePattern, rolls_left: u8) -> Option<(usize, f32)> {
+        self.best(dice, ROLLS_PER_TURN - rolls_left)
+    }
+}
+
+/// ヤンブのAI（行ごとの目安点による近似）
+#[derive(Clone, Debug, Default)]
+pub struct YambAI {}
+
+impl YambAI {
+    pub fn new() -> YambAI {
+        YambAI {}
+    }
+
+    fn leaf(game: &GameState) -> YambLeaf<'_> {
+        match game.announcement() {
+            Some(row) => YambLeaf::announced(game.current_board(), row),
+            None => YambLeaf::unannounced(game.current_board()),
+        }
+    }
+}
+
+impl Strategy<GameState> for YambAI {
+    fn check_game(&self, game: &GameState) -> Result<(), GameError> {
+        game.check_ruleset(Ruleset::Yamb)
+    }
+
+    /// 1回目のロールの後に宣言する行を決める（宣言しない、または宣言できなければ None）
+    fn decide_announcement(&self, game: &GameState) -> Option<usize> {
+        let board = game.current_board();
+        if *game.ruleset() != Ruleset::Yamb
+            || game.is_game_over()
+            || game.rolls_this_turn() != 1
+            || game.announcement().is_some()
+        {
+            return None;
+        }
+        let pattern = dp_table::dice_to_pattern(&game.dice().values());
+        let rolls_left = game.rolls_left();
+        let mut best: Option<(Option<usize>, f32)> = None;
+        if !board.must_announce() {
+            let mut solver = TurnSolver::new(YambLeaf::unannounced(board));
+            best = Some((None, solver.value_after_roll(&pattern, rolls_left)));
+        }
+        for row in 0..NUM_ROWS {
+            if !board.is_open(Cell::new(YambColumn::Announce as usize, row)) {
+                continue;
+            }
+            let mut solver = TurnSolver::new(YambLeaf::announced(board, row));
+            let value = solver.value_after_roll(&pattern, rolls_left);
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((Some(row), value));
+            }
+        }
+        best.and_then(|(row, _)| row)
+    }
+
+    fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        let dice = game.dice().values();
+        let pattern = dp_table::dice_to_pattern(&dice);
+        let mut solver = TurnSolver::new(Self::leaf(game));
+        match solver.best_keep(&pattern, game.rolls_left()) {
+            Some((keep, _)) => HoldDecision::Reroll(keep_to_holds(&dice, &keep)),
+            None => HoldDecision::Stop,
+        }
+    }
+
+    fn decide_cell(&self, game: &GameState) -> Option<Cell> {
+        let leaf = Self::leaf(game);
+        let pattern = dp_table::dice_to_pattern(&game.dice().values());
+        let (i, _) = leaf.best(&pattern, game.rolls_this_turn())?;
+        Some(leaf.cells[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yamb_scores() {
+        use YambRow::*;
+        assert_eq!(calculate_yamb_score(&[1, 2, 3, 4, 5], Straight, 1), 66);
+        assert_eq!(calculate_yamb_score(&[6, 2, 3, 4, 5], Straight, 2), 56);
+        assert_eq!(calculate_yamb_score(&[6, 2, 3, 4, 5], Straight, 3), 46);
+        assert_eq!(calculate_yamb_score(&[6, 2, 3, 4, 1], Straight, 1), 0);
+        assert_eq!(calculate_yamb_score(&[4, 4, 4, 2, 4], ThreeOfAKind, 1), 22);
+        assert_eq!(calculate_yamb_score(&[4, 4, 4, 2, 4], Poker, 1), 56);
+        assert_eq!(calculate_yamb_score(&[4, 4, 4, 2, 2], FullHouse, 1), 46);
+        assert_eq!(calculate_yamb_score(&[4; 5], FullHouse, 1), 0);
+        assert_eq!(calculate_yamb_score(&[6; 5], Yamb, 3), 80);
+        assert_eq!(calculate_yamb_score(&[6, 5, 1, 1, 2], Max, 1), 15);
+        assert_eq!(calculate_yamb_score(&[6, 5, 1, 1, 2], Ones, 1), 2);
+    }
+
+    #[test]
+    fn test_column_order_and_totals() {
+        use YambColumn::*;
+        let mut board = ScoreBoard::with_ruleset(Ruleset::Yamb);
+        assert_eq!(
+            board.set_cell(cell(Down, YambRow::Twos), 4),
+            Err(GameError::OutOfOrder)
+        );
+        assert_eq!(
+            board.set_cell(cell(Up, YambRow::Poker), 40),
+            Err(GameError::OutOfOrder)
+        );
+        board.set_cell(cell(Down, YambRow::Ones), 3).unwrap();
+        board.set_cell(cell(Down, YambRow::Twos), 4).unwrap();
+        board.set_cell(cell(Up, YambRow::Yamb), 0).unwrap();
+        assert!(board.is_open(cell(Up, YambRow::Poker)));
+        assert_eq!(
+            board.set_cell(cell(Down, YambRow::Ones), 3),
+            Err(GameError::CategoryUsed)
+        );
+
+        board.set_cell(cell(Free, YambRow::Max), 27).unwrap();
+        board.set_cell(cell(Free, YambRow::Min), 7).unwrap();
+        assert_eq!(middle_total(board.column(Free as usize)), 0);
+        board.set_cell(cell(Free, YambRow::Ones), 3).unwrap();
+        assert_eq!(middle_total(board.column(Free as usize)), 60);
+        for (row, score) in [
+            (YambRow::Sixes, 24),
+            (YambRow::Fives, 20),
+            (YambRow::Fours, 16),
+        ] {
+            board.set_cell(cell(Free, row), score).unwrap();
+        }
+        assert_eq!(board.column_total(Free as usize), 63 + 30 + 60);
+        assert_eq!(board.total(), 7 + 153);
+    }
+
+    #[test]
+    fn test_announce() {
+        use YambColumn::*;
+        let mut game = GameState::solo(Ruleset::Yamb);
+        game.set_seed(1);
+        assert_eq!(
+            game.announce(YambRow::Yamb as usize),
+            Err(GameError::NotRolled)
+        );
+        game.roll().unwrap();
+        assert_eq!(
+            game.select_cell(cell(Announce, YambRow::Yamb)),
+            Err(GameError::NotAnnounced)
+        );
+        game.announce(YambRow::Yamb as usize).unwrap();
+        assert_eq!(
+            game.announce(YambRow::Poker as usize),
+            Err(GameError::AnnounceTooLate)
+        );
+        game.roll().unwrap();
+        assert_eq!(
+            game.select_cell(cell(Free, YambRow::Yamb)),
+            Err(GameError::AnnouncementMismatch)
+        );
+        game.select_cell(cell(Announce, YambRow::Yamb)).unwrap();
+        assert_eq!(game.announcement(), None);
+        assert_eq!(game.current_player(), 0);
+
+        game.roll().unwrap();
+        game.roll().unwrap();
+        assert_eq!(
+            game.announce(YambRow::Ones as usize),
+            Err(GameError::AnnounceTooLate)
+        );
+        game.roll().unwrap();
+        assert_eq!(game.roll(), Err(GameError::NoRollsLeft));
+        game.select_cell(cell(Free, YambRow::Max)).unwrap();
+
+        // ヨットには宣言の列がない
+        let mut yacht = GameState::new();
+        yacht.roll().unwrap();
+        assert_eq!(yacht.announce(0), Err(GameError::CategoryUnavailable));
+    }
+
+    #[test]
+    fn test_ai_game() {
+        let ai = YambAI::new();
+        let mut game = GameState::solo(Ruleset::Yamb);
+        game.enter_roll([1, 2, 3, 4, 5]).unwrap();
+        // 1回目のケンタは振り直さない
+        assert_eq!(ai.decide_holds(&game), HoldDecision::Stop);
+        assert_eq!(
+            ai.decide_announcement(&game),
+            Some(YambRow::Straight as usize)
+        );
+
+        let mut game = GameState::solo(Ruleset::Yamb);
+        game.set_seed(4);
+        let mut announced = 0;
+        while !game.is_game_over() {
+            let cell = ai.play_turn(&mut game).unwrap().cell;
+            if cell.column == YambColumn::Announce as usize {
+                announced += 1;
+            }
+        }
+        assert_eq!(announced, NUM_ROWS);
+        assert_eq!(game.roll(), Err(GameError::GameOver));
+        assert!(game.board(0).total() > 600, "{}", game.board(0).total());
+    }
+}
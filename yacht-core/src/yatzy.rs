@@ -0,0 +1,252 @@
+//! 北欧式ヤッツィー（Scandinavian Yatzy）
+//!
+//! 15カテゴリ: 1-6の目、ワンペア、ツーペア、スリーカード、フォーカード、
+//! スモールストレート（1-5 で 15点）、ラージストレート（2-6 で 20点）、フルハウス、チャンス、ヤッツィー（50点）。
+//! 上段が 63点以上でボーナス 50点。ホールドは自由で、1手番に3回まで振れる。
+//!
+//! ゲームは `GameState`（`Ruleset::Yatzy`、欄の行は `YatzyCategory` の番号）で遊ぶ。
+//! 期待値は専用のDPテーブル（`dp_table::YATZY`、used_hands は 15ビット）から求める。
+//! テーブル（約2MB）は `embed-yatzy-table` フィーチャーでだけ埋め込み、既定では AI や期待値を
+//! 使う前に `load_table` で `src/yatzy_dp_table.ydp` を読み込む。
+
+use serde::{Deserialize, Serialize};
+
+use crate::dp_table::{self, yatzy_category, YATZY};
+use crate::play::Strategy;
+use crate::solver::{keep_to_holds, TableLeaf, TurnSolver};
+use crate::{Cell, DpTableError, GameError, GameState, HoldDecision, Ruleset, ScoreBoard};
+
+/// ヤッツィーのDPテーブル（YDP1 形式）を実行時に読み込む
+/// embed-yatzy-table フィーチャーを有効にしていない場合、AIや期待値を使う前に呼ぶ
+pub fn load_table(data: &[u8]) -> Result<(), DpTableError> {
+    YATZY.load_table(data)
+}
+
+/// ヤッツィーのDPテーブルが利用可能か
+pub fn is_table_loaded() -> bool {
+    YATZY.is_table_loaded()
+}
+
+/// ヤッツィーのカテゴリ
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum YatzyCategory {
+    Ones = 0,
+    Twos = 1,
+    Threes = 2,
+    Fours = 3,
+    Fives = 4,
+    Sixes = 5,
+    OnePair = 6,
+    TwoPairs = 7,
+    ThreeOfAKind = 8,
+    FourOfAKind = 9,
+    SmallStraight = 10,
+    LargeStraight = 11,
+    FullHouse = 12,
+    Chance = 13,
+    Yatzy = 14,
+}
+
+impl YatzyCategory {
+    pub const ALL: [YatzyCategory; yatzy_category::NUM_CATEGORIES] = [
+        YatzyCategory::Ones,
+        YatzyCategory::Twos,
+        YatzyCategory::Threes,
+        YatzyCategory::Fours,
+        YatzyCategory::Fives,
+        YatzyCategory::Sixes,
+        YatzyCategory::OnePair,
+        YatzyCategory::TwoPairs,
+        YatzyCategory::ThreeOfAKind,
+        YatzyCategory::FourOfAKind,
+        YatzyCategory::SmallStraight,
+        YatzyCategory::LargeStraight,
+        YatzyCategory::FullHouse,
+        YatzyCategory::Chance,
+        YatzyCategory::Yatzy,
+    ];
+
+    pub fn from_index(index: usize) -> Option<YatzyCategory> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// 英語の表示名
+    pub fn name(self) -> &'static str {
+        match self {
+            YatzyCategory::Ones => "Ones",
+            YatzyCategory::Twos => "Twos",
+            YatzyCategory::Threes => "Threes",
+            YatzyCategory::Fours => "Fours",
+            YatzyCategory::Fives => "Fives",
+            YatzyCategory::Sixes => "Sixes",
+            YatzyCategory::OnePair => "One Pair",
+            YatzyCategory::TwoPairs => "Two Pairs",
+            YatzyCategory::ThreeOfAKind => "Three of a Kind",
+            YatzyCategory::FourOfAKind => "Four of a Kind",
+            YatzyCategory::SmallStraight => "Small Straight",
+            YatzyCategory::LargeStraight => "Large Straight",
+            YatzyCategory::FullHouse => "Full House",
+            YatzyCategory::Chance => "Chance",
+            YatzyCategory::Yatzy => "Yatzy",
+        }
+    }
+
+    /// 日本語の表示名
+    pub fn name_ja(self) -> &'static str {
+        match self {
+            YatzyCategory::Ones => "1の目",
+            YatzyCategory::Twos => "2の目",
+            YatzyCategory::Threes => "3の目",
+            YatzyCategory::Fours => "4の目",
+            YatzyCategory::Fives => "5の目",
+            YatzyCategory::Sixes => "6の目",
+            YatzyCategory::OnePair => "ワンペア",
+            YatzyCategory::TwoPairs => "ツーペア",
+            YatzyCategory::ThreeOfAKind => "スリーカード",
+            YatzyCategory::FourOfAKind => "フォーカード",
+            YatzyCategory::SmallStraight => "スモールストレート",
+            YatzyCategory::LargeStraight => "ラージストレート",
+            YatzyCategory::FullHouse => "フルハウス",
+            YatzyCategory::Chance => "チャンス",
+            YatzyCategory::Yatzy => "ヤッツィー",
+        }
+    }
+}
+
+/// 得点計算（サイコロ値配列版）
+pub fn calculate_yatzy_score(dice: &[u8; 5], category: YatzyCategory) -> u8 {
+    let pattern = dp_table::dice_to_pattern(dice);
+    dp_table::calculate_yatzy_score(&pattern, category as usize)
+}
+
+/// ヤッツィーの最適AI（期待得点を最大化する）
+///
+/// DPテーブルが必要なので、読み込む前には作れない（`YatzyAI::new` が `DpTableError::NotLoaded` を返す）。
+#[derive(Clone, Debug)]
+pub struct YatzyAI {
+    _table_loaded: (),
+}
+
+impl YatzyAI {
+    /// ヤッツィーのDPテーブルが読み込まれていなければ `DpTableError::NotLoaded`
+    pub fn new() -> Result<YatzyAI, DpTableError> {
+        YATZY.check_loaded()?;
+        Ok(YatzyAI { _table_loaded: () })
+    }
+
+    fn solver(board: &ScoreBoard) -> TurnSolver<TableLeaf> {
+        TurnSolver::for_table(&YATZY, board.upper_sum_capped(), board.used_hands_mask())
+    }
+
+    /// 手番の席が現在の状態から最適に続けた場合の最終合計点数の期待値
+    pub fn expected_score(&self, game: &GameState) -> f32 {
+        let board = game.current_board();
+        let future = if game.rolls_this_turn() == 0 {
+            YATZY.expected_score(board.upper_sum_capped(), board.used_hands_mask())
+        } else {
+            let pattern = dp_table::dice_to_pattern(&game.dice().values());
+            Self::solver(board).value_after_roll(&pattern, game.rolls_left())
+        };
+        board.total() as f32 + future
+    }
+}
+
+impl Strategy<GameState> for YatzyAI {
+    fn check_game(&self, game: &GameState) -> Result<(), GameError> {
+        game.check_ruleset(Ruleset::Yatzy)
+    }
+
+    fn decide_holds(&self, game: &GameState) -> HoldDecision {
+        let dice = game.dice().values();
+        let pattern = dp_table::dice_to_pattern(&dice);
+        match Self::solver(game.current_board()).best_keep(&pattern, game.rolls_left()) {
+            Some((keep, _)) => HoldDecision::Reroll(keep_to_holds(&dice, &keep)),
+            None => HoldDecision::Stop,
+        }
+    }
+
+    fn decide_cell(&self, game: &GameState) -> Option<Cell> {
+        let pattern = dp_table::dice_to_pattern(&game.dice().values());
+        let (row, _) = Self::solver(game.current_board()).best_category(&pattern, 0)?;
+        Some(Cell::new(0, row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 埋め込んでいないビルドではテストの前にテーブルを読み込む
+    fn load() {
+        if !is_table_loaded() {
+            match load_table(include_bytes!("yatzy_dp_table.ydp")) {
+                Ok(()) | Err(DpTableError::AlreadyLoaded) => {}
+                Err(e) => panic!("{}", e),
+            }
+        }
+        assert!(is_table_loaded());
+    }
+
+    #[test]
+    fn test_yatzy_scores() {
+        use YatzyCategory::*;
+        let full = [3, 3, 5, 5, 5];
+        assert_eq!(calculate_yatzy_score(&full, OnePair), 10);
+        assert_eq!(calculate_yatzy_score(&full, TwoPairs), 16);
+        assert_eq!(calculate_yatzy_score(&full, ThreeOfAKind), 15);
+        assert_eq!(calculate_yatzy_score(&full, FourOfAKind), 0);
+        assert_eq!(calculate_yatzy_score(&full, FullHouse), 21);
+        assert_eq!(calculate_yatzy_score(&full, Chance), 21);
+
+        let five = [4, 4, 4, 4, 4];
+        assert_eq!(calculate_yatzy_score(&five, Yatzy), 50);
+        assert_eq!(calculate_yatzy_score(&five, FourOfAKind), 16);
+        assert_eq!(calculate_yatzy_score(&five, TwoPairs), 0);
+        assert_eq!(calculate_yatzy_score(&five, FullHouse), 0);
+        assert_eq!(calculate_yatzy_score(&[6, 6, 6, 6, 2], TwoPairs), 0);
+
+        assert_eq!(calculate_yatzy_score(&[5, 4, 3, 2, 1], SmallStraight), 15);
+        assert_eq!(calculate_yatzy_score(&[5, 4, 3, 2, 1], LargeStraight), 0);
+        assert_eq!(calculate_yatzy_score(&[2, 3, 4, 5, 6], LargeStraight), 20);
+        assert_eq!(calculate_yatzy_score(&[1, 2, 3, 4, 4], SmallStraight), 0);
+    }
+
+    #[test]
+    fn test_table_matches_solver() {
+        load();
+        let board = ScoreBoard::with_ruleset(Ruleset::Yatzy);
+        let expected = board.expected_score().unwrap();
+        assert!((expected - 248.440).abs() < 0.01, "{}", expected);
+        // 手番内の探索とテーブルの値が一致する
+        let turn = YatzyAI::solver(&board).turn_value(3);
+        assert!((turn - expected).abs() < 0.01, "{} vs {}", turn, expected);
+    }
+
+    #[test]
+    fn test_ai_game() {
+        load();
+        let ai = YatzyAI::new().unwrap();
+        let mut game = GameState::solo(Ruleset::Yatzy);
+        game.set_seed(7);
+        assert!((ai.expected_score(&game) - 248.440).abs() < 0.01);
+        while !game.game_over {
+            ai.play_turn(&mut game).unwrap();
+        }
+        assert_eq!(ai.decide_cell(&game), None);
+        assert!(game.board(0).total() > 100);
+
+        // ヤッツィーが揃えば振り直さない
+        let mut game = GameState::solo(Ruleset::Yatzy);
+        game.enter_roll([6; 5]).unwrap();
+        assert_eq!(ai.decide_holds(&game), HoldDecision::Stop);
+        let yatzy = Cell::new(0, YatzyCategory::Yatzy as usize);
+        assert_eq!(ai.decide_cell(&game), Some(yatzy));
+        assert_eq!(game.select_cell(yatzy), Ok(50));
+
+        game.enter_roll([6, 6, 6, 1, 2]).unwrap();
+        assert_eq!(
+            ai.decide_holds(&game),
+            HoldDecision::Reroll([true, true, true, false, false])
+        );
+    }
+}
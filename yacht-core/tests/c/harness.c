@@ -0,0 +1,134 @@
+/*
+ * C ABI の動作確認用ハーネス
+ *
+ * ビルドと実行: tests/c/run.sh
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "yacht_core.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                    \
+    do {                                                               \
+        if (!(cond)) {                                                 \
+            fprintf(stderr, "%s:%d: CHECK failed: %s\n", __FILE__,     \
+                    __LINE__, #cond);                                  \
+            failures++;                                                \
+        }                                                              \
+    } while (0)
+
+#define CHECK_OK(expr) CHECK((expr) == YACHT_STATUS_OK)
+
+static void test_calculate_score(void) {
+    const uint8_t yacht[5] = {6, 6, 6, 6, 6};
+    const uint8_t bad[5] = {0, 6, 6, 6, 6};
+    uint8_t score = 0;
+
+    CHECK_OK(yacht_calculate_score(yacht, 11, &score));
+    CHECK(score == 50);
+    CHECK(yacht_calculate_score(yacht, 12, &score) == YACHT_STATUS_INVALID_ARGUMENT);
+    CHECK(yacht_calculate_score(bad, 0, &score) == YACHT_STATUS_INVALID_ARGUMENT);
+    CHECK(yacht_calculate_score(NULL, 0, &score) == YACHT_STATUS_NULL_POINTER);
+    CHECK(strcmp(yacht_status_message(YACHT_STATUS_INVALID_STATE), "invalid state") == 0);
+}
+
+static void test_turn(void) {
+    YachtGame *game = yacht_game_new(1);
+    YachtAi *ai = yacht_ai_new();
+    uint8_t dice[5], holds[5], locks[5];
+    uint8_t rolls_left = 0, current_player = 0, category = 0, score = 0;
+    bool stop = false, used = false;
+    YachtCategoryChoice categories[12];
+    YachtHoldChoice keeps[3];
+    size_t len = 0;
+
+    CHECK(game != NULL);
+    CHECK(yacht_game_new(2) == NULL);
+    CHECK_OK(yacht_game_set_seed(game, 42));
+    CHECK(yacht_game_select_category(game, 0) == YACHT_STATUS_INVALID_STATE);
+
+    CHECK_OK(yacht_game_roll(game));
+    CHECK_OK(yacht_game_get_dice(game, dice));
+    for (int i = 0; i < 5; i++) {
+        CHECK(dice[i] >= 1 && dice[i] <= 6);
+    }
+
+    CHECK_OK(yacht_ai_top_hold_choices(ai, game, keeps, 3, &len));
+    CHECK(len == 3);
+    CHECK(keeps[0].loss == 0.0f && keeps[1].loss >= 0.0f);
+
+    CHECK_OK(yacht_ai_hold_decision(ai, game, holds, &stop));
+    if (!stop) {
+        for (uint8_t i = 0; i < 5; i++) {
+            if (holds[i]) {
+                CHECK_OK(yacht_game_toggle_hold(game, i));
+            }
+        }
+        CHECK_OK(yacht_game_roll(game));
+        CHECK_OK(yacht_game_get_holds(game, NULL, locks));
+        for (int i = 0; i < 5; i++) {
+            CHECK(locks[i] == holds[i]);
+        }
+    }
+    CHECK_OK(yacht_game_stand(game));
+    CHECK_OK(yacht_game_get_turn(game, &rolls_left, NULL, NULL));
+    CHECK(rolls_left == 0);
+    CHECK(yacht_game_roll(game) == YACHT_STATUS_INVALID_STATE);
+    CHECK(yacht_ai_top_hold_choices(ai, game, keeps, 3, &len) == YACHT_STATUS_INVALID_STATE);
+
+    CHECK_OK(yacht_ai_top_category_choices(ai, game, categories, 12, &len));
+    CHECK(len == 12);
+    CHECK_OK(yacht_ai_category_decision(ai, game, &category));
+    CHECK(category == categories[0].category);
+    CHECK_OK(yacht_game_select_category(game, category));
+    CHECK_OK(yacht_game_get_score(game, 0, category, &score, &used));
+    CHECK(used && score == categories[0].score);
+    CHECK_OK(yacht_game_get_turn(game, NULL, &current_player, NULL));
+    CHECK(current_player == 1);
+
+    yacht_ai_free(ai);
+    yacht_game_free(game);
+}
+
+static void test_full_game(void) {
+    YachtGame *game = yacht_game_new(0);
+    YachtAi *ai = yacht_ai_new();
+    bool game_over = false;
+    uint16_t totals[2];
+    int turns = 0;
+
+    CHECK_OK(yacht_game_set_seed(game, 7));
+    while (!game_over && turns < 100) {
+        CHECK_OK(yacht_ai_play_turn(ai, game));
+        CHECK_OK(yacht_game_get_turn(game, NULL, NULL, &game_over));
+        turns++;
+    }
+    CHECK(turns == 24);
+    CHECK(yacht_ai_play_turn(ai, game) == YACHT_STATUS_INVALID_STATE);
+
+    for (uint8_t seat = 0; seat < 2; seat++) {
+        CHECK_OK(yacht_game_get_totals(game, seat, NULL, NULL, &totals[seat]));
+        CHECK(totals[seat] > 0);
+    }
+    CHECK(yacht_game_get_totals(game, 2, NULL, NULL, &totals[0]) == YACHT_STATUS_INVALID_ARGUMENT);
+    printf("full game: %u - %u\n", totals[0], totals[1]);
+
+    yacht_ai_free(ai);
+    yacht_game_free(game);
+    yacht_game_free(NULL);
+}
+
+int main(void) {
+    test_calculate_score();
+    test_turn();
+    test_full_game();
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("all checks passed\n");
+    return 0;
+}
@@ -0,0 +1,23 @@
+#!/bin/sh
+# C ABI のハーネスをビルドして実行する（yacht-core のどこから実行してもよい）
+#
+#   tests/c/run.sh                  # include/yacht_core.h が最新か確認してからハーネスを実行
+#   tests/c/run.sh --update-header  # 生成したヘッダーを include/yacht_core.h にコピーしてから実行
+set -eu
+
+cd "$(dirname "$0")/../.."
+
+out_dir=$(cargo build --release --features ffi --message-format=json-render-diagnostics |
+    jq -r 'select(.reason == "build-script-executed" and (.package_id | test("yacht-core"))) | .out_dir' |
+    tail -n 1)
+header="$out_dir/yacht_core.h"
+
+if [ "${1:-}" = "--update-header" ]; then
+    cp "$header" include/yacht_core.h
+elif ! cmp -s "$header" include/yacht_core.h; then
+    echo "include/yacht_core.h is stale: run tests/c/run.sh --update-header" >&2
+    exit 1
+fi
+
+cc -std=c99 -Wall -Werror -Iinclude tests/c/harness.c -Ltarget/release -lyacht_core -o target/harness
+LD_LIBRARY_PATH=target/release ./target/harness
@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback } from 'react'
-import init, { GameState, YachtAI, get_category_name_ja } from './wasm/yacht_core'
+import init, { AiStepKind, AiTurn, GameState, YachtAI, get_category_name_ja } from './wasm/yacht_core'
 import './App.css'
 
 type GamePhase = 'loading' | 'ready' | 'playing' | 'ai_turn' | 'game_over'
@@ -12,6 +12,7 @@ interface CategoryRecommendation {
 
 interface HoldRecommendation {
   holds: number[]
+  stop: boolean
   expected: number
 }
 
@@ -66,14 +67,16 @@ function App() {
 
     try {
       // カテゴリ推奨
-      const catJson = aiPlayer.get_top_category_choices(g)
-      const categories: CategoryRecommendation[] = JSON.parse(catJson)
+      const categories: CategoryRecommendation[] = aiPlayer
+        .get_top_category_choices(g, 3)
+        .map(c => ({ category: c.category, score: c.score, expected: c.expected }))
       setRecommendedCategories(categories)
 
       // キープ推奨（まだ振れる場合のみ）
       if (g.get_rolls_left() > 0) {
-        const holdJson = aiPlayer.get_top_hold_choices(g)
-        const holds: HoldRecommendation[] = JSON.parse(holdJson)
+        const holds: HoldRecommendation[] = aiPlayer
+          .get_top_hold_choices(g, 3)
+          .map(h => ({ holds: Array.from(h.holds), stop: h.stop, expected: h.expected }))
         setRecommendedHolds(holds)
       } else {
         setRecommendedHolds([])
@@ -183,104 +186,65 @@ function App() {
   }, [])
 
   // AIのターンを実行（アニメーション付き）
-  const executeAiTurn = useCallback(async (g: GameState, aiPlayer: YachtAI) => {
+  const executeAiTurn = useCallback(async (g: GameState) => {
     setPhase('ai_turn')
     // 推奨表示をクリア
     setRecommendedCategories([])
     setRecommendedHolds([])
 
-    // 1回目のロール
-    setMessage('AIがサイコロを振っています...')
-    await animateAiRoll([false, false, false, false, false])
-    g.roll_dice()
-    syncGameState(g)
-    await sleep(500)
-
-    // 2回目のロール判断
-    let skipThirdRoll = false
-    if (g.get_rolls_left() > 0) {
-      setMessage('AIが考えています...')
-      await sleep(400)
-
-      const holds1 = Array.from(aiPlayer.get_holds_decision(g)).map(h => h === 1)
-
-      // ホールドするサイコロを設定
-      for (let i = 0; i < 5; i++) {
-        if (holds1[i]) {
-          g.toggle_hold(i)
+    // AIの制御はRust側のAiTurnに任せ、ここでは各ステップの演出だけを行う
+    const turn = new AiTurn()
+    let isFirstRoll = true
+    for (let step = turn.next_step(g); step; step = turn.next_step(g)) {
+      switch (step.kind) {
+        case AiStepKind.Roll: {
+          if (!isFirstRoll) {
+            setMessage('AIが考えています...')
+            await sleep(400)
+          }
+          setMessage(isFirstRoll ? 'AIがサイコロを振っています...' : 'AIがサイコロを振り直しています...')
+          isFirstRoll = false
+          await animateAiRoll(Array.from(g.get_dice_holds()).map(h => h === 1))
+          turn.apply(g, step)
+          syncGameState(g)
+          await sleep(500)
+          break
         }
-      }
-      // 実際のゲーム状態を反映
-      syncGameState(g)
-
-      const actualHolds1 = Array.from(g.get_dice_holds()).map(h => h === 1)
-      if (actualHolds1.some(h => h)) {
-        setMessage('AIがサイコロをキープしています...')
-        await sleep(500)
-      }
-
-      // 全てキープなら振らない→カテゴリ選択へ
-      if (actualHolds1.every(h => h)) {
-        setMessage('AIは振り直しません')
-        await sleep(400)
-        skipThirdRoll = true
-      } else {
-        setMessage('AIがサイコロを振り直しています...')
-        await animateAiRoll(actualHolds1)
-        g.roll_dice()
-        syncGameState(g)
-        await sleep(500)
-      }
-    }
-
-    // 3回目のロール判断（2回目で全てキープした場合はスキップ）
-    if (!skipThirdRoll && g.get_rolls_left() > 0) {
-      setMessage('AIが考えています...')
-      await sleep(400)
-
-      // AIの決定を取得
-      const holds2 = Array.from(aiPlayer.get_holds_decision(g)).map(h => h === 1)
-
-      // 新しくキープするダイスのみtoggle（ロック済みは自動的に拒否される）
-      for (let i = 0; i < 5; i++) {
-        if (holds2[i]) {
-          g.toggle_hold(i)
+        case AiStepKind.SetHolds: {
+          setMessage('AIが考えています...')
+          await sleep(400)
+          turn.apply(g, step)
+          // 実際のゲーム状態を反映
+          syncGameState(g)
+          if (Array.from(g.get_dice_holds()).some(h => h === 1)) {
+            setMessage('AIがサイコロをキープしています...')
+            await sleep(500)
+          }
+          break
+        }
+        case AiStepKind.Stop: {
+          setMessage('AIは振り直しません')
+          await sleep(400)
+          turn.apply(g, step)
+          break
+        }
+        case AiStepKind.ChooseCategory: {
+          const categoryChoice = step.category!
+          setMessage('AIが役を選んでいます...')
+          await sleep(400)
+          setHighlightCategory(categoryChoice)
+          setMessage(`AIが「${get_category_name_ja(categoryChoice)}」を選択しました`)
+          await sleep(800)
+
+          turn.apply(g, step)
+          setHighlightCategory(null)
+          syncGameState(g)
+          break
         }
       }
-      // 実際のゲーム状態を反映
-      syncGameState(g)
-
-      const actualHolds2 = Array.from(g.get_dice_holds()).map(h => h === 1)
-      if (actualHolds2.some(h => h)) {
-        setMessage('AIがサイコロをキープしています...')
-        await sleep(500)
-      }
-
-      // 全てキープなら振らない
-      if (actualHolds2.every(h => h)) {
-        setMessage('AIは振り直しません')
-        await sleep(400)
-      } else {
-        setMessage('AIがサイコロを振り直しています...')
-        await animateAiRoll(actualHolds2)
-        g.roll_dice()
-        syncGameState(g)
-        await sleep(500)
-      }
+      step.free()
     }
-
-    // カテゴリ選択
-    setMessage('AIが役を選んでいます...')
-    await sleep(400)
-
-    const categoryChoice = aiPlayer.get_category_decision(g)
-    setHighlightCategory(categoryChoice)
-    setMessage(`AIが「${get_category_name_ja(categoryChoice)}」を選択しました`)
-    await sleep(800)
-
-    g.select_category(categoryChoice)
-    setHighlightCategory(null)
-    syncGameState(g)
+    turn.free()
 
     if (!g.is_game_over()) {
       setPhase('playing')
@@ -298,7 +262,7 @@ function App() {
       syncGameState(game)
 
       if (!game.is_game_over()) {
-        executeAiTurn(game, ai)
+        executeAiTurn(game)
       }
     }
   }, [game, ai, rollsLeft, phase, playerScores, syncGameState, executeAiTurn])
@@ -385,13 +349,17 @@ function App() {
                   {rollsLeft > 0 && recommendedHolds.map((rec, idx) => (
                     <div key={idx} className={`recommendation-item ${idx === 0 ? 'best' : ''}`}>
                       <span className="rank">{idx + 1}.</span>
-                      <span className="hold-pattern">
-                        {rec.holds.map((h, i) => (
-                          <span key={i} className={`hold-indicator ${h === 1 ? 'keep' : 'reroll'}`}>
-                            {diceValues[i]}
-                          </span>
-                        ))}
-                      </span>
+                      {rec.stop ? (
+                        <span className="hold-pattern">振らずに得点</span>
+                      ) : (
+                        <span className="hold-pattern">
+                          {rec.holds.map((h, i) => (
+                            <span key={i} className={`hold-indicator ${h === 1 ? 'keep' : 'reroll'}`}>
+                              {diceValues[i]}
+                            </span>
+                          ))}
+                        </span>
+                      )}
                       <span className="expected">期待値: {rec.expected.toFixed(1)}</span>
                     </div>
                   ))}